@@ -0,0 +1,352 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Criterion {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Pass,
+    Fail,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Criteria paired with their latest QA result
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    pub criteria: Vec<(Criterion, Option<Verdict>)>,
+}
+
+impl Coverage {
+    pub fn total(&self) -> usize {
+        self.criteria.len()
+    }
+
+    pub fn count(&self, verdict: Verdict) -> usize {
+        self.criteria.iter().filter(|(_, v)| *v == Some(verdict)).count()
+    }
+
+    /// IDs with no recorded result
+    pub fn missing(&self) -> Vec<&str> {
+        self.criteria
+            .iter()
+            .filter(|(_, v)| v.is_none())
+            .map(|(c, _)| c.id.as_str())
+            .collect()
+    }
+
+    pub fn failed(&self) -> Vec<&str> {
+        self.criteria
+            .iter()
+            .filter(|(_, v)| *v == Some(Verdict::Fail))
+            .map(|(c, _)| c.id.as_str())
+            .collect()
+    }
+}
+
+/// A checklist line: (prefix up to and including the checkbox, item text)
+pub fn checklist_item(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    for marker in ["- [ ]", "- [x]", "- [X]", "* [ ]", "* [x]", "* [X]"] {
+        if let Some(rest) = trimmed.strip_prefix(marker) {
+            return Some((&line[..indent + marker.len()], rest.trim()));
+        }
+    }
+    None
+}
+
+/// How a markdown section's list items are numbered: `AC-1` checklist items under
+/// `## Criteria` here, `REQ-1` bullets under `## Requirements` in the PRD
+pub struct IdScheme {
+    pub prefix: &'static str,
+    pub heading: &'static str,
+    /// Match an item line, returning (prefix to keep, item text)
+    pub item: fn(&str) -> Option<(&str, &str)>,
+}
+
+pub const CRITERIA: IdScheme = IdScheme {
+    prefix: "AC",
+    heading: "## Criteria",
+    item: checklist_item,
+};
+
+/// The numeric part of a leading `<prefix>-<n>` and the text after it
+fn explicit_id<'a>(text: &'a str, prefix: &str) -> Option<(u32, &'a str)> {
+    let rest = text.strip_prefix(prefix)?.strip_prefix('-')?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let n = digits.parse().ok()?;
+    let text = rest[digits.len()..].trim_start_matches([':', ' ', '—', '-']).trim();
+    Some((n, text))
+}
+
+/// Every `<prefix>-<n>` mentioned in `text`, in order
+pub fn find_ids(text: &str, prefix: &str) -> Vec<String> {
+    let marker = format!("{}-", prefix);
+    let mut ids = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(&marker) {
+        let after = &rest[pos + marker.len()..];
+        let digits: String = after.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let boundary = rest[..pos].chars().last().map(|c| !c.is_alphanumeric()).unwrap_or(true);
+        if !digits.is_empty() && boundary {
+            ids.push(format!("{}{}", marker, digits));
+        }
+        rest = &after[digits.len()..];
+    }
+    ids
+}
+
+/// Lines (with indices) under `heading` up to the next `## ` heading, or the whole
+/// file if the heading is absent
+pub fn section_lines<'a>(content: &'a str, heading: &str) -> Vec<(usize, &'a str)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.iter().position(|l| l.trim().eq_ignore_ascii_case(heading));
+    match start {
+        Some(start) => lines
+            .iter()
+            .enumerate()
+            .skip(start + 1)
+            .take_while(|(_, l)| !l.starts_with("## "))
+            .map(|(i, l)| (i, *l))
+            .collect(),
+        None => lines.into_iter().enumerate().collect(),
+    }
+}
+
+/// (ID, text) for each item, giving unnumbered items the next free IDs in order
+pub fn numbered_items(content: &str, scheme: &IdScheme) -> Vec<(String, String)> {
+    let items: Vec<(Option<u32>, String)> = section_lines(content, scheme.heading)
+        .into_iter()
+        .filter_map(|(_, line)| (scheme.item)(line))
+        .map(|(_, text)| match explicit_id(text, scheme.prefix) {
+            Some((n, rest)) => (Some(n), rest.to_string()),
+            None => (None, text.to_string()),
+        })
+        .collect();
+
+    let mut next = items.iter().filter_map(|(n, _)| *n).max().unwrap_or(0) + 1;
+    items
+        .into_iter()
+        .map(|(n, text)| {
+            let n = n.unwrap_or_else(|| {
+                next += 1;
+                next - 1
+            });
+            (format!("{}-{}", scheme.prefix, n), text)
+        })
+        .collect()
+}
+
+/// Write IDs into unnumbered items so they stay stable as the list changes.
+/// Returns true if the file was rewritten.
+pub fn number_items(path: &Path, scheme: &IdScheme) -> Result<bool, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(false);
+    };
+    let items = numbered_items(&content, scheme);
+    let mut ids = items.iter().map(|(id, _)| id.as_str());
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut changed = false;
+    for (index, line) in section_lines(&content, scheme.heading) {
+        let Some((prefix, text)) = (scheme.item)(line) else { continue };
+        let id = ids.next().expect("one ID per item");
+        if explicit_id(text, scheme.prefix).is_none() {
+            lines[index] = format!("{} {}: {}", prefix, id, text);
+            changed = true;
+        }
+    }
+
+    if changed {
+        let mut updated = lines.join("\n");
+        if content.ends_with('\n') {
+            updated.push('\n');
+        }
+        fs::write(path, updated).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(changed)
+}
+
+/// Parse criteria, giving unnumbered items the next free IDs in order
+pub fn parse_criteria(content: &str) -> Vec<Criterion> {
+    numbered_items(content, &CRITERIA)
+        .into_iter()
+        .map(|(id, text)| Criterion { id, text })
+        .collect()
+}
+
+/// Write IDs into unnumbered criteria in `acceptance.md`. Returns true if it was rewritten.
+pub fn assign_ids(work_dir: &Path) -> Result<bool, String> {
+    number_items(&work_dir.join("acceptance.md"), &CRITERIA)
+}
+
+/// The first pass/fail word after an AC ID on a line
+fn line_verdict(line: &str) -> Option<Verdict> {
+    line.split(|c: char| !c.is_alphanumeric() && c != '✅' && c != '❌')
+        .find_map(|word| match word.to_ascii_lowercase().as_str() {
+            "pass" | "passed" | "passes" | "✅" => Some(Verdict::Pass),
+            "fail" | "failed" | "fails" | "❌" => Some(Verdict::Fail),
+            _ => None,
+        })
+}
+
+/// Record every `AC-<n> … pass|fail` mention in `text`; later mentions win.
+/// HTML comments are skipped, since templates use them for example lines.
+fn collect_results(text: &str, results: &mut BTreeMap<String, Verdict>) {
+    for line in text.lines().filter(|l| !l.trim_start().starts_with("<!--")) {
+        let ids = find_ids(line, CRITERIA.prefix);
+        let Some(first) = ids.first() else { continue };
+        let after = &line[line.find(first.as_str()).unwrap() + first.len()..];
+        if let Some(verdict) = line_verdict(after) {
+            for id in ids {
+                results.insert(id, verdict);
+            }
+        }
+    }
+}
+
+/// Results recorded by QA: QA handoff notes oldest first, then `qa.md`
+pub fn qa_results(work_dir: &Path) -> BTreeMap<String, Verdict> {
+    let mut results = BTreeMap::new();
+    let mut handoffs: Vec<_> = fs::read_dir(work_dir.join("handoffs"))
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    handoffs.retain(|p: &std::path::PathBuf| {
+        p.file_name().map(|n| n.to_string_lossy().ends_with("-qa.md")).unwrap_or(false)
+    });
+    handoffs.sort();
+    for path in handoffs {
+        if let Ok(text) = fs::read_to_string(&path) {
+            collect_results(&text, &mut results);
+        }
+    }
+    if let Ok(text) = fs::read_to_string(work_dir.join("qa.md")) {
+        collect_results(&text, &mut results);
+    }
+    results
+}
+
+/// Criteria from `acceptance.md` with their recorded QA results
+pub fn coverage(work_dir: &Path) -> Coverage {
+    let criteria = fs::read_to_string(work_dir.join("acceptance.md"))
+        .map(|c| parse_criteria(&c))
+        .unwrap_or_default();
+    let results = qa_results(work_dir);
+    Coverage {
+        criteria: criteria
+            .into_iter()
+            .map(|c| {
+                let verdict = results.get(&c.id).copied();
+                (c, verdict)
+            })
+            .collect(),
+    }
+}
+
+/// Reasons `qa=pass` can't be accepted; empty if every criterion passed
+pub fn qa_pass_problems(work_dir: &Path) -> Vec<String> {
+    let coverage = coverage(work_dir);
+    let mut problems = Vec::new();
+    if coverage.total() == 0 {
+        problems.push("acceptance.md lists no criteria".into());
+    }
+    let missing = coverage.missing();
+    if !missing.is_empty() {
+        problems.push(format!("no QA result recorded for {}", missing.join(", ")));
+    }
+    let failed = coverage.failed();
+    if !failed.is_empty() {
+        problems.push(format!("QA recorded failures for {}", failed.join(", ")));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const ACCEPTANCE: &str = "# Acceptance Criteria\n\n## Criteria\n\n- [ ] AC-2: Export as CSV\n- [ ] Export as PDF\n- [x] Empty reports show a notice\n\n## Verification Steps\n\n- [ ] not a criterion\n";
+
+    #[test]
+    fn test_parse_assigns_ids_after_highest() {
+        let criteria = parse_criteria(ACCEPTANCE);
+        let ids: Vec<&str> = criteria.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["AC-2", "AC-3", "AC-4"]);
+        assert_eq!(criteria[0].text, "Export as CSV");
+        assert_eq!(criteria[1].text, "Export as PDF");
+    }
+
+    #[test]
+    fn test_assign_ids_is_stable() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("acceptance.md"), ACCEPTANCE).unwrap();
+        assert!(assign_ids(dir.path()).unwrap());
+        let content = fs::read_to_string(dir.path().join("acceptance.md")).unwrap();
+        assert!(content.contains("- [ ] AC-3: Export as PDF\n"));
+        assert!(content.contains("- [x] AC-4: Empty reports show a notice\n"));
+        assert!(content.contains("- [ ] not a criterion\n"));
+        assert!(!assign_ids(dir.path()).unwrap());
+
+        // A criterion added later gets a new ID; existing ones keep theirs
+        let content = content.replace("## Verification", "- [ ] Large exports stream\n\n## Verification");
+        fs::write(dir.path().join("acceptance.md"), &content).unwrap();
+        assign_ids(dir.path()).unwrap();
+        let ids: Vec<String> = parse_criteria(&fs::read_to_string(dir.path().join("acceptance.md")).unwrap())
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec!["AC-2", "AC-3", "AC-4", "AC-5"]);
+    }
+
+    #[test]
+    fn test_results_from_qa_md_and_handoffs() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("handoffs")).unwrap();
+        fs::write(dir.path().join("acceptance.md"), ACCEPTANCE).unwrap();
+        fs::write(dir.path().join("handoffs/20260101-000000-qa.md"), "- AC-2: FAIL — header row missing\n").unwrap();
+        fs::write(
+            dir.path().join("qa.md"),
+            "| ID | Result |\n|----|--------|\n| AC-2 | Pass |\n| AC-3 | ❌ |\n",
+        ).unwrap();
+
+        let coverage = coverage(dir.path());
+        assert_eq!(coverage.count(Verdict::Pass), 1);
+        assert_eq!(coverage.failed(), vec!["AC-3"]);
+        assert_eq!(coverage.missing(), vec!["AC-4"]);
+
+        let problems = qa_pass_problems(dir.path());
+        assert_eq!(problems, vec![
+            "no QA result recorded for AC-4".to_string(),
+            "QA recorded failures for AC-3".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_template_examples_are_not_results() {
+        let mut results = BTreeMap::new();
+        collect_results(crate::templates::QA_MD, &mut results);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_line_verdict_takes_first_word() {
+        assert_eq!(line_verdict(": PASS (previously failed)"), Some(Verdict::Pass));
+        assert_eq!(line_verdict(" — not checked"), None);
+    }
+}
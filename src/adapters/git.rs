@@ -0,0 +1,88 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Run a git command in `cwd` and return trimmed stdout
+pub fn run(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("failed to run git {}: {}", args.join(" "), e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Name of the currently checked-out branch (empty when detached)
+pub fn current_branch(cwd: &Path) -> Result<String, String> {
+    run(cwd, &["branch", "--show-current"])
+}
+
+/// Check whether a local branch exists
+pub fn branch_exists(cwd: &Path, branch: &str) -> bool {
+    run(cwd, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)]).is_ok()
+}
+
+/// Pick the remote to push to: `origin` if present, otherwise the first one listed
+pub fn default_remote(cwd: &Path) -> Option<String> {
+    let remotes = run(cwd, &["remote"]).ok()?;
+    let remotes: Vec<&str> = remotes.lines().map(str::trim).filter(|r| !r.is_empty()).collect();
+    if remotes.contains(&"origin") {
+        Some("origin".into())
+    } else {
+        remotes.first().map(|r| r.to_string())
+    }
+}
+
+/// True when the index has staged changes
+pub fn has_staged_changes(cwd: &Path) -> bool {
+    Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(cwd)
+        .status()
+        .map(|s| !s.success())
+        .unwrap_or(false)
+}
+
+/// Write the working tree (tracked and untracked, minus ignored files) as a tree
+/// object without touching the real index, and return its hash
+pub fn snapshot_tree(cwd: &Path) -> Result<String, String> {
+    let index = run(cwd, &["rev-parse", "--path-format=absolute", "--git-path", "pfm-snapshot-index"])?;
+    let git = |args: &[&str]| -> Result<String, String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .env("GIT_INDEX_FILE", &index)
+            .output()
+            .map_err(|e| format!("failed to run git {}: {}", args.join(" "), e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    };
+    // Seed from HEAD so only changed files are hashed; fails harmlessly before the first commit
+    let _ = git(&["read-tree", "HEAD"]);
+    let result = git(&["add", "-A"]).and_then(|_| git(&["write-tree"]));
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+/// Paths that differ between two trees or commits
+pub fn changed_files(cwd: &Path, from: &str, to: &str) -> Result<Vec<String>, String> {
+    Ok(run(cwd, &["diff", "--name-only", from, to])?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
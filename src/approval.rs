@@ -0,0 +1,530 @@
+use crate::runlog;
+use crate::state::{read_state, write_state, GateStatus, WorkState};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Set in the environment of every agent pfm starts, so agents can't sign off their own work
+pub const AGENT_ENV: &str = "PFM_AGENT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Approved,
+    Rejected,
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Approved => write!(f, "approved"),
+            Decision::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// Who signed off, from git config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Approver {
+    pub name: String,
+    pub email: String,
+}
+
+impl std::fmt::Display for Approver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// A signed decision on one approval gate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub gate: String,
+    pub decision: Decision,
+    pub approver: Approver,
+    #[serde(default)]
+    pub comment: String,
+    pub at: String,
+    /// HMAC-SHA256 over the work ID and the fields above
+    pub signature: String,
+}
+
+impl Record {
+    fn payload(&self, work_id: &str) -> String {
+        [
+            work_id,
+            &self.gate,
+            &self.decision.to_string(),
+            &self.approver.name,
+            &self.approver.email,
+            &self.at,
+            &self.comment,
+        ]
+        .join("\n")
+    }
+}
+
+/// Approval gates a work item must pass: the ones frozen into its state when it was created,
+/// plus any the effective config puts in its type's pipeline. Editing either file alone
+/// doesn't drop a sign-off.
+pub fn required(base: &Path, state: &WorkState) -> Vec<String> {
+    let config = crate::config::load(base).map(|layered| layered.config).unwrap_or_default();
+    let pipeline = if state.epic {
+        crate::commands::epic::EPIC_PIPELINE.iter().map(|g| g.to_string()).collect()
+    } else {
+        config.pipeline_for(state.work_type)
+    };
+    let mut required = state.approval_gates.clone();
+    for gate in pipeline.into_iter().filter(|g| config.approval_gates.contains(g)) {
+        if !required.contains(&gate) {
+            required.push(gate);
+        }
+    }
+    required
+}
+
+/// Required approval gates that are no longer in the item's pipeline
+pub fn missing(state: &WorkState, required: &[String]) -> Vec<String> {
+    required.iter().filter(|g| !state.pipeline.contains(g)).cloned().collect()
+}
+
+/// `approval.key` next to the global config file
+pub fn key_path() -> Option<PathBuf> {
+    crate::config::global_config_path().and_then(|path| path.parent().map(|dir| dir.join("approval.key")))
+}
+
+/// The signing key, if this machine has one yet
+pub fn read_key() -> Option<Vec<u8>> {
+    let key = fs::read_to_string(key_path()?).ok()?;
+    Some(key.trim().as_bytes().to_vec()).filter(|k| !k.is_empty())
+}
+
+/// The signing key, created on first use with owner-only permissions
+fn key_or_create() -> Result<Vec<u8>, String> {
+    if let Some(key) = read_key() {
+        return Ok(key);
+    }
+    let path = key_path().ok_or("cannot locate the global config directory for approval.key (set HOME or PFM_CONFIG_HOME)")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    }
+    let key = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&path).map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+    file.write_all(key.as_bytes())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    println!("  created approval key {}", path.display());
+    Ok(key.into_bytes())
+}
+
+/// HMAC-SHA256 (RFC 2104), hex encoded
+fn hmac(key: &[u8], message: &[u8]) -> String {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new().chain_update(block.map(|b| b ^ 0x36)).chain_update(message).finalize();
+    let outer = Sha256::new().chain_update(block.map(|b| b ^ 0x5c)).chain_update(inner).finalize();
+    format!("{:x}", outer)
+}
+
+fn record_path(work_dir: &Path, gate: &str) -> PathBuf {
+    work_dir.join("approvals").join(format!("{}.json", gate))
+}
+
+pub fn pending_path(work_dir: &Path, gate: &str) -> PathBuf {
+    work_dir.join("approvals").join(format!("{}.pending.md", gate))
+}
+
+/// The gate's decision record, if there is one
+fn read_record(work_dir: &Path, gate: &str) -> Option<Record> {
+    let record: Record = serde_json::from_str(&fs::read_to_string(record_path(work_dir, gate)).ok()?).ok()?;
+    (record.gate == gate).then_some(record)
+}
+
+/// True if `key` signed this record. Records signed on another machine, or edited after
+/// signing, don't verify.
+fn verifies(record: &Record, work_id: &str, key: Option<&[u8]>) -> bool {
+    key.is_some_and(|key| hmac(key, record.payload(work_id).as_bytes()) == record.signature)
+}
+
+/// The status an approval gate has according to its record: pass or fail once decided,
+/// in progress while a request is pending, todo otherwise. A record counts whether or not
+/// this machine can verify it; see [`unverified`].
+pub fn derived_status(work_dir: &Path, gate: &str) -> GateStatus {
+    match read_record(work_dir, gate) {
+        Some(record) if record.decision == Decision::Approved => GateStatus::Pass,
+        Some(_) => GateStatus::Fail,
+        None if pending_path(work_dir, gate).exists() => GateStatus::InProgress,
+        None => GateStatus::Todo,
+    }
+}
+
+/// Overwrite approval gate statuses in `state` with the ones their records give.
+/// Returns each corrected gate with the status `state.json` claimed.
+pub fn apply(work_dir: &Path, state: &mut WorkState, gates: &[String]) -> Vec<(String, GateStatus)> {
+    let mut corrected = Vec::new();
+    for gate in state.pipeline.clone().iter().filter(|g| gates.contains(g)) {
+        let derived = derived_status(work_dir, gate);
+        if let Some(claimed) = state.gates.get(gate).filter(|claimed| **claimed != derived).cloned() {
+            state.gates.set(gate, derived);
+            corrected.push((gate.clone(), claimed));
+        }
+    }
+    corrected
+}
+
+/// Whether the gate's decision record verifies with `key`; None if it has no record
+pub fn verification(work_dir: &Path, work_id: &str, gate: &str, key: Option<&[u8]>) -> Option<bool> {
+    read_record(work_dir, gate).map(|record| verifies(&record, work_id, key))
+}
+
+/// Decision records on `gates` that this machine's key doesn't verify: signed by an approver
+/// with a different key, or edited after signing. They still decide the gate, so they are
+/// reported rather than thrown away.
+pub fn unverified(work_dir: &Path, state: &WorkState, gates: &[String], key: Option<&[u8]>) -> Vec<(String, Record)> {
+    gates
+        .iter()
+        .filter_map(|gate| read_record(work_dir, gate).map(|record| (gate.clone(), record)))
+        .filter(|(_, record)| !verifies(record, &state.id, key))
+        .collect()
+}
+
+/// Re-derive a work item's approval gates from their records, saving and logging any
+/// status that had been changed by hand. Fails if a required approval gate was taken
+/// out of the pipeline.
+pub fn enforce(base: &Path, work_dir: &Path) -> Result<WorkState, String> {
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    let required = required(base, &state);
+    let missing = missing(&state, &required);
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} needs approval gate(s) {} but they are missing from its pipeline in state.json — put them back before continuing",
+            state.id,
+            missing.join(", ")
+        ));
+    }
+    for (gate, record) in unverified(work_dir, &state, &required, read_key().as_deref()) {
+        println!(
+            "[??] approval gate '{}' ({} by {}) can't be verified with this machine's key — check who added approvals/{}.json",
+            gate, record.decision, record.approver, gate
+        );
+    }
+    let corrected = apply(work_dir, &mut state, &required);
+    if corrected.is_empty() {
+        return Ok(state);
+    }
+    state.touch();
+    write_state(&state_path, &state)?;
+    let lines: Vec<String> = corrected
+        .iter()
+        .map(|(gate, claimed)| {
+            let status = state.gates.get(gate).expect("gate corrected");
+            println!("[!!] approval gate '{}' was set to {} outside pfm approve — restored to {}", gate, claimed, status);
+            format!("- {}: state.json said {}, the approval record says {}", gate, claimed, status)
+        })
+        .collect();
+    runlog::append(
+        work_dir,
+        &format!(
+            "\n## Approval Enforced: {}\n\n{}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            lines.join("\n")
+        ),
+    )?;
+    Ok(state)
+}
+
+/// Ask for sign-off on `gate`: write the pending approval file and mark the gate in progress
+pub fn request(work_dir: &Path, gate: &str) -> Result<PathBuf, String> {
+    let path = pending_path(work_dir, gate);
+    if path.exists() {
+        return Ok(path);
+    }
+    let state = read_state(&work_dir.join("state.json"))?;
+    let done: Vec<String> = state
+        .pipeline
+        .iter()
+        .take_while(|g| *g != gate)
+        .map(|g| format!("- {}: {}", g, state.gates.get(g).map(|s| s.to_string()).unwrap_or_default()))
+        .collect();
+    let now = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+    let content = format!(
+        "# Approval Needed: {gate}\n\nWork item: {id} — {title}\nBranch: {branch}\nRequested: {now}\n\n## Gates\n\n{done}\n\n## Decide\n\n```bash\npfm approve {id} {gate} --comment \"...\"\npfm reject {id} {gate} --comment \"what needs to change\"\n```\n",
+        gate = gate,
+        id = state.id,
+        title = state.title,
+        branch = state.branch,
+        now = now,
+        done = done.join("\n"),
+    );
+    fs::create_dir_all(work_dir.join("approvals")).map_err(|e| format!("failed to create approvals dir: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    runlog::append(work_dir, &format!("\n## Approval Requested: {} — {}\n", now, gate))?;
+    let mut state = state;
+    state.gates.set(gate, GateStatus::InProgress);
+    state.touch();
+    write_state(&work_dir.join("state.json"), &state)?;
+    Ok(path)
+}
+
+/// Record a human decision on an approval gate. Refuses to run inside an agent session
+/// or without a terminal.
+pub fn decide(base: &Path, work_id: &str, gate: &str, decision: Decision, comment: &str) -> Result<(), String> {
+    let verb = if decision == Decision::Approved { "approve" } else { "reject" };
+    if let Ok(role) = std::env::var(AGENT_ENV) {
+        return Err(format!("`pfm {}` can't be run by an agent (running as the {} agent)", verb, role));
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(format!("`pfm {}` must be run by a person at a terminal", verb));
+    }
+    let approver = git_identity(base)?;
+    let key = key_or_create()?;
+    record(base, work_id, gate, decision, comment, &approver, &key)
+}
+
+/// Validate, sign and save a decision, then apply it to the gate
+fn record(
+    base: &Path,
+    work_id: &str,
+    gate: &str,
+    decision: Decision,
+    comment: &str,
+    approver: &Approver,
+    key: &[u8],
+) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let state = read_state(&work_dir.join("state.json"))?;
+    let Some(index) = state.gate_index(gate).filter(|_| required(base, &state).iter().any(|g| g == gate)) else {
+        return Err(format!("'{}' is not an approval gate of {}", gate, work_id));
+    };
+    let comment = comment.trim();
+    if decision == Decision::Rejected && comment.is_empty() {
+        return Err("`pfm reject` needs a --comment saying what needs to change".into());
+    }
+    let unfinished: Vec<String> = state.pipeline[..index]
+        .iter()
+        .filter_map(|g| state.gates.get(g).filter(|s| !s.is_satisfied()).map(|s| format!("{}={}", g, s)))
+        .collect();
+    if !unfinished.is_empty() {
+        return Err(format!("{} isn't ready for sign-off — earlier gates not done: {}", gate, unfinished.join(", ")));
+    }
+    if let Some(existing) = read_record(&work_dir, gate) {
+        if existing.decision == decision {
+            return Err(format!("{} gate '{}' was already {} by {}", work_id, gate, decision, existing.approver));
+        }
+    }
+
+    let mut record = Record {
+        gate: gate.to_string(),
+        decision,
+        approver: approver.clone(),
+        comment: comment.to_string(),
+        at: Utc::now().to_rfc3339(),
+        signature: String::new(),
+    };
+    record.signature = hmac(key, record.payload(work_id).as_bytes());
+    let json = serde_json::to_string_pretty(&record).map_err(|e| format!("failed to serialize approval: {}", e))?;
+    fs::create_dir_all(work_dir.join("approvals")).map_err(|e| format!("failed to create approvals dir: {}", e))?;
+    fs::write(record_path(&work_dir, gate), json + "\n").map_err(|e| format!("failed to write approval: {}", e))?;
+    let _ = fs::remove_file(pending_path(&work_dir, gate));
+
+    let heading = if decision == Decision::Approved { "Approved" } else { "Rejected" };
+    let comment_line = if comment.is_empty() { String::new() } else { format!("\nComment: {}\n", comment) };
+    runlog::append(
+        &work_dir,
+        &format!(
+            "\n## {}: {} — {} by {}\n{}",
+            heading,
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            gate,
+            approver,
+            comment_line
+        ),
+    )?;
+    let mut state = read_state(&work_dir.join("state.json"))?;
+    apply(&work_dir, &mut state, &[gate.to_string()]);
+    state.touch();
+    write_state(&work_dir.join("state.json"), &state)?;
+    println!("{} {} gate {} as {}", heading.to_lowercase(), work_id, gate, approver);
+    Ok(())
+}
+
+/// Drop a gate's decision and pending request, so it needs sign-off again.
+/// Returns true if there was anything to drop.
+pub fn clear(work_dir: &Path, gate: &str) -> bool {
+    let record = fs::remove_file(record_path(work_dir, gate)).is_ok();
+    let pending = fs::remove_file(pending_path(work_dir, gate)).is_ok();
+    record || pending
+}
+
+fn git_identity(base: &Path) -> Result<Approver, String> {
+    let get = |key: &str| {
+        Command::new("git")
+            .args(["config", key])
+            .current_dir(base)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    match (get("user.name"), get("user.email")) {
+        (Some(name), Some(email)) => Ok(Approver { name, email }),
+        _ => Err("approvals record who signed off — set `git config user.name` and `git config user.email`".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{gate, init, work};
+    use tempfile::tempdir;
+
+    const KEY: &[u8] = b"test-key";
+
+    fn approver() -> Approver {
+        Approver { name: "Dana Reviewer".into(), email: "dana@example.com".into() }
+    }
+
+    /// A work item whose pipeline ends `qa → signoff → git`, with every gate before `signoff` passed
+    fn setup() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::write(
+            dir.path().join(".pfm/config.json"),
+            r#"{"pipeline": ["prd", "qa", "signoff", "git"], "approval_gates": ["signoff"]}"#,
+        )
+        .unwrap();
+        work::new_work(dir.path(), "Auth", Some("FEAT-AP"), Some("rust")).unwrap();
+        for g in ["prd", "qa"] {
+            gate::set(dir.path(), "FEAT-AP", g, GateStatus::Pass, "done").unwrap();
+        }
+        dir
+    }
+
+    fn work_dir(dir: &Path) -> PathBuf {
+        dir.join(".pfm/work/FEAT-AP")
+    }
+
+    #[test]
+    fn test_hmac_matches_rfc_4231() {
+        assert_eq!(
+            hmac(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_request_then_approve() {
+        let dir = setup();
+        let pending = request(&work_dir(dir.path()), "signoff").unwrap();
+        assert!(fs::read_to_string(&pending).unwrap().contains("pfm approve FEAT-AP signoff"));
+        assert_eq!(derived_status(&work_dir(dir.path()), "signoff"), GateStatus::InProgress);
+
+        record(dir.path(), "FEAT-AP", "signoff", Decision::Approved, "ship it", &approver(), KEY).unwrap();
+        assert!(!pending.exists());
+        let state = read_state(&work_dir(dir.path()).join("state.json")).unwrap();
+        assert_eq!(state.gates.get("signoff"), Some(&GateStatus::Pass));
+        let runlog = fs::read_to_string(work_dir(dir.path()).join("runlog.md")).unwrap();
+        assert!(runlog.contains("signoff by Dana Reviewer <dana@example.com>\n\nComment: ship it"));
+
+        let err = record(dir.path(), "FEAT-AP", "signoff", Decision::Approved, "", &approver(), KEY).unwrap_err();
+        assert!(err.contains("already approved by Dana Reviewer"), "{}", err);
+    }
+
+    #[test]
+    fn test_removing_approval_gate_from_state_or_config_is_refused() {
+        let dir = setup();
+        let wd = work_dir(dir.path());
+        let mut state = read_state(&wd.join("state.json")).unwrap();
+        assert_eq!(state.approval_gates, vec!["signoff"]);
+
+        // Drop the gate from state.json and from config: the frozen list still requires it
+        state.set_pipeline(state.pipeline.iter().filter(|g| *g != "signoff").cloned().collect());
+        write_state(&wd.join("state.json"), &state).unwrap();
+        fs::write(dir.path().join(".pfm/config.json"), r#"{"pipeline": ["prd", "qa", "git"]}"#).unwrap();
+        let opts = crate::commands::git_gate::GitGateOptions { no_push: true, ..Default::default() };
+        let err = crate::commands::git_gate::run(dir.path(), "FEAT-AP", &opts).unwrap_err();
+        assert!(err.contains("needs approval gate(s) signoff"), "{}", err);
+        let findings = crate::commands::doctor::diagnose(dir.path());
+        assert!(findings.iter().any(|f| f.subject == "FEAT-AP" && f.message.contains("signoff missing from the pipeline")));
+
+        // Emptying the frozen list too still leaves the config requirement
+        fs::write(
+            dir.path().join(".pfm/config.json"),
+            r#"{"pipeline": ["prd", "qa", "signoff", "git"], "approval_gates": ["signoff"]}"#,
+        )
+        .unwrap();
+        state.approval_gates.clear();
+        write_state(&wd.join("state.json"), &state).unwrap();
+        assert!(crate::commands::git_gate::run(dir.path(), "FEAT-AP", &opts).unwrap_err().contains("signoff"));
+        assert_eq!(read_state(&wd.join("state.json")).unwrap().status, crate::state::WorkStatus::InProgress);
+    }
+
+    #[test]
+    fn test_hand_edits_without_a_record_are_undone() {
+        let dir = setup();
+        let wd = work_dir(dir.path());
+        let mut state = read_state(&wd.join("state.json")).unwrap();
+        state.gates.set("signoff", GateStatus::Pass);
+        let corrected = apply(&wd, &mut state, &["signoff".into()]);
+        assert_eq!(corrected, vec![("signoff".to_string(), GateStatus::Pass)]);
+        assert_eq!(state.gates.get("signoff"), Some(&GateStatus::Todo));
+        let err = gate::skip(dir.path(), "FEAT-AP", "signoff", "trust me").unwrap_err();
+        assert!(err.contains("is an approval gate"), "{}", err);
+    }
+
+    #[test]
+    fn test_record_signed_under_another_key_counts_but_is_unverified() {
+        let dir = setup();
+        let wd = work_dir(dir.path());
+        // A teammate approved on their machine and committed the record
+        record(dir.path(), "FEAT-AP", "signoff", Decision::Approved, "lgtm", &approver(), b"teammate").unwrap();
+
+        let state = enforce(dir.path(), &wd).unwrap();
+        assert_eq!(state.gates.get("signoff"), Some(&GateStatus::Pass));
+        let required = vec!["signoff".to_string()];
+        let flagged = unverified(&wd, &state, &required, Some(KEY));
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].1.approver, approver());
+        assert!(unverified(&wd, &state, &required, Some(b"teammate")).is_empty());
+        assert!(!fs::read_to_string(wd.join("runlog.md")).unwrap().contains("Approval Enforced"));
+
+        // Editing the record after signing breaks even the signer's verification
+        let path = record_path(&wd, "signoff");
+        fs::write(&path, fs::read_to_string(&path).unwrap().replace("lgtm", "ok")).unwrap();
+        assert_eq!(unverified(&wd, &state, &required, Some(b"teammate")).len(), 1);
+
+        let findings = crate::commands::doctor::diagnose(dir.path());
+        assert!(findings.iter().any(|f| f.subject == "FEAT-AP" && f.message.contains("can't be verified")));
+        let view = crate::commands::status::view(dir.path(), "FEAT-AP").unwrap();
+        let signoff = view.gates.iter().find(|g| g.name == "signoff").unwrap();
+        assert_eq!((&signoff.status, signoff.verified), (&GateStatus::Pass, Some(false)));
+    }
+
+    #[test]
+    fn test_decisions_need_an_approval_gate_and_finished_work() {
+        let dir = setup();
+        let err = record(dir.path(), "FEAT-AP", "qa", Decision::Approved, "", &approver(), KEY).unwrap_err();
+        assert!(err.contains("not an approval gate"), "{}", err);
+        let err = record(dir.path(), "FEAT-AP", "signoff", Decision::Rejected, " ", &approver(), KEY).unwrap_err();
+        assert!(err.contains("needs a --comment"), "{}", err);
+        gate::reset(dir.path(), "FEAT-AP", "qa", "redo").unwrap();
+        let err = record(dir.path(), "FEAT-AP", "signoff", Decision::Approved, "", &approver(), KEY).unwrap_err();
+        assert!(err.contains("earlier gates not done: qa=todo"), "{}", err);
+    }
+}
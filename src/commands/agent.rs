@@ -1,36 +1,64 @@
-use crate::state::{Role, read_state, write_state, role_to_gate, GateStatus};
+use crate::runlog;
+use crate::state::{Role, read_state, write_state, role_to_gate, GateStatus, WorkState};
 use chrono::Utc;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// Render the bootstrap prompt for a role agent
+/// Render the bootstrap prompt for a role agent from `.pfm/prompts/bootstrap.md`
+/// (or the built-in template)
 pub fn render_bootstrap_prompt(role: &Role, work_dir: &Path, pfm_base: &Path) -> String {
-    let role_name = role.to_string();
-    let role_spec_path = pfm_base
-        .join(".pfm/roles")
-        .join(format!("{}.md", role_name));
-    let work_dir_str = work_dir.display();
-    let role_spec_str = role_spec_path.display();
-
-    format!(
-        r#"You are acting as the {role_name} agent.
-Read and follow your role spec exactly: {role_spec_str}
-Your assigned work item directory is: {work_dir_str}
-Start by reading:
-1) {work_dir_str}/state.json
-2) {work_dir_str}/tasks.md
-3) The most recent file in {work_dir_str}/handoffs/ (if any)
-
-Hard requirements:
-- Ask the user clarifying questions when requirements are ambiguous or incomplete. Do not assume — confirm with the user.
-- Update only the gate you own in state.json (do not modify other gates).
-- Log commands, outputs, and failures in {work_dir_str}/runlog.md.
-- When finished, write a handoff note to {work_dir_str}/handoffs/{{TIMESTAMP}}-{role_name}.md using the standard format.
-- When you are done, tell the user you are finished and they can exit the session with /exit to return to PFM.
-- Stop when your role spec stop condition is met."#
-    )
+    let context = crate::prompt::context(pfm_base, work_dir, Some(role));
+    crate::prompt::render_named(pfm_base, "bootstrap.md", &context)
+}
+
+/// Render the nudge message for a role agent from `.pfm/prompts/nudge.md`
+pub fn render_nudge_prompt(role: &Role, work_dir: &Path, pfm_base: &Path) -> String {
+    let context = crate::prompt::context(pfm_base, work_dir, Some(role));
+    crate::prompt::render_named(pfm_base, "nudge.md", &context)
+}
+
+/// Where a role's spec lives
+pub fn role_spec_path(base: &Path, role: &Role) -> PathBuf {
+    base.join(".pfm/roles").join(format!("{}.md", role))
+}
+
+/// Custom roles exist only through their spec file; built-in specs are restored by `pfm init`
+pub fn require_role_spec(base: &Path, role: &Role) -> Result<(), String> {
+    let path = role_spec_path(base, role);
+    if !role.is_builtin() && !path.exists() {
+        return Err(format!(
+            "custom role '{}' has no role spec — create {}",
+            role,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// A role can only act on an item whose pipeline has its gate
+pub fn require_role_for(base: &Path, role: &Role, state: &WorkState) -> Result<(), String> {
+    require_role_spec(base, role)?;
+    let gate = role_to_gate(role);
+    if state.gates.get(gate).is_none() {
+        return Err(format!(
+            "gate '{}' is not part of {}'s pipeline ({})",
+            gate,
+            state.id,
+            state.pipeline.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Whether an artifact has been written over its template. Placeholder lines left from
+/// the template must not be numbered as if they were real criteria or requirements.
+fn written(base: &Path, work_dir: &Path, filename: &str) -> bool {
+    fs::read_to_string(work_dir.join(filename))
+        .map(|content| {
+            crate::lint::check_artifact(base, work_dir, filename, &content).is_none_or(|fill| fill.is_filled())
+        })
+        .unwrap_or(false)
 }
 
 /// Start a role agent for a work item
@@ -39,6 +67,37 @@ pub fn start(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
     if !work_dir.exists() {
         return Err(format!("work item {} not found", work_id));
     }
+    let state = read_state(&work_dir.join("state.json"))?;
+    if crate::approval::required(base, &state).iter().any(|g| g == role_to_gate(role)) {
+        return Err(format!(
+            "{} is an approval gate — a person signs it off with `pfm approve {} {}`",
+            role_to_gate(role),
+            work_id,
+            role_to_gate(role)
+        ));
+    }
+    require_role_for(base, role, &state)?;
+
+    // The role spec's contract (if any) lists files the role can't start without
+    let contract = crate::role_spec::load(base, role)?;
+    if let Some(contract) = &contract {
+        let missing = crate::role_spec::missing_inputs(contract, &work_dir);
+        if !missing.is_empty() {
+            return Err(format!(
+                "cannot start {} agent — missing inputs: {}",
+                role,
+                missing.join(", ")
+            ));
+        }
+    }
+
+    // Number acceptance criteria once the PRD exists, so later roles can cite stable IDs
+    if *role != Role::Prd && written(base, &work_dir, "acceptance.md") && crate::acceptance::assign_ids(&work_dir)? {
+        println!("numbered acceptance criteria in acceptance.md");
+    }
+    if *role != Role::Prd && written(base, &work_dir, "prd.md") && crate::trace::assign_requirement_ids(&work_dir)? {
+        println!("numbered requirements in prd.md");
+    }
 
     // Ensure handoffs dir exists
     let handoffs_dir = work_dir.join("handoffs");
@@ -54,7 +113,10 @@ pub fn start(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
     state.touch();
     write_state(&state_path, &state)?;
 
-    // Render bootstrap prompt
+    // Build the context pack (if enabled), then render the bootstrap prompt
+    if let Some(path) = crate::context::write_if_enabled(base, &work_dir, role)? {
+        println!("wrote context pack: {}", path.display());
+    }
     let prompt = render_bootstrap_prompt(role, &work_dir, base);
 
     // Log agent start
@@ -66,14 +128,11 @@ pub fn start(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
         role,
         gate,
     );
-    append_to_runlog(&work_dir, &log_entry)?;
+    runlog::append(&work_dir, &log_entry)?;
 
     // Determine working directory (prefer worktree if set)
-    let cwd = if !state.workspace.worktree.is_empty() {
-        state.workspace.worktree.clone()
-    } else {
-        base.to_string_lossy().to_string()
-    };
+    let cwd = crate::trace::workspace_dir(base, &state);
+    crate::trace::mark_gate_start(&work_dir, &cwd, gate)?;
 
     // Run claude interactively — the user needs to be in the conversation
     println!("starting {} agent for {} (interactive)", role, work_id);
@@ -81,22 +140,27 @@ pub fn start(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
     println!("  when the agent is done, type /exit to return to PFM");
     println!("---");
 
+    let mut tasks_seen = crate::tasks::load(base, &work_dir);
     let status = Command::new("claude")
         .arg(&prompt)
         .current_dir(&cwd)
+        .env(crate::approval::AGENT_ENV, role.to_string())
         .stdin(std::process::Stdio::inherit())
         .stdout(std::process::Stdio::inherit())
         .stderr(std::process::Stdio::inherit())
         .status()
         .map_err(|e| format!("failed to start claude: {}", e))?;
 
+    crate::tasks::record_completed(base, &work_dir, &mut tasks_seen, &role.to_string())?;
+    crate::trace::mark_gate_end(&work_dir, &cwd, gate)?;
+
     if !status.success() {
         let log_entry = format!(
             "\n## Agent Exit (non-zero): {} — {}\n",
             Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
             role,
         );
-        append_to_runlog(&work_dir, &log_entry)?;
+        runlog::append(&work_dir, &log_entry)?;
         return Err(format!("claude exited with status: {}", status));
     }
 
@@ -105,8 +169,89 @@ pub fn start(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
         Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
         role,
     );
-    append_to_runlog(&work_dir, &log_entry)?;
+    runlog::append(&work_dir, &log_entry)?;
+
+    // Agents can't pass approval gates, whatever they wrote to state.json
+    crate::approval::enforce(base, &work_dir)?;
+    enforce_completion(base, &work_dir, role, contract.as_ref())?;
+
+    Ok(())
+}
+
+/// Check the gate the agent set against its role contract, and `qa=pass` against
+/// the acceptance criteria results. A completion that fails either is sent back to
+/// `in_progress` so the pipeline doesn't move on. Returns true if it was rejected.
+pub fn enforce_completion(
+    base: &Path,
+    work_dir: &Path,
+    role: &Role,
+    contract: Option<&crate::role_spec::RoleContract>,
+) -> Result<bool, String> {
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    let gate = role_to_gate(role);
+    let status = state.gates.get(gate).cloned().unwrap_or(GateStatus::Todo);
+    if !status.is_terminal() {
+        return Ok(false);
+    }
+
+    let mut problems = contract
+        .map(|c| crate::role_spec::completion_problems(c, base, work_dir, &status))
+        .unwrap_or_default();
+    if gate == "qa" && status == GateStatus::Pass {
+        problems.extend(crate::acceptance::qa_pass_problems(work_dir));
+    }
+    if problems.is_empty() {
+        if gate == "impl" && status == GateStatus::Pass {
+            warn_unchecked_tasks(base, work_dir, role)?;
+        }
+        return Ok(false);
+    }
+
+    state.gates.set(gate, GateStatus::InProgress);
+    state.touch();
+    write_state(&state_path, &state)?;
+
+    let log_entry = format!(
+        "\n## Completion Rejected: {} — {} set {}={}\n\n{}\n",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        role,
+        gate,
+        status,
+        problems.iter().map(|p| format!("- {}", p)).collect::<Vec<_>>().join("\n"),
+    );
+    runlog::append(work_dir, &log_entry)?;
+
+    println!("{} set {}={}, but it can't be accepted:", role, gate, status);
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    println!("gate '{}' reset to in_progress", gate);
+    Ok(true)
+}
+
+/// `impl=pass` with tasks still open is allowed (plans change), but worth a look
+fn warn_unchecked_tasks(base: &Path, work_dir: &Path, role: &Role) -> Result<(), String> {
+    let tasks = crate::tasks::load(base, work_dir);
+    let open: Vec<&crate::tasks::Task> = tasks.iter().filter(|t| !t.done).collect();
+    if open.is_empty() {
+        return Ok(());
+    }
+
+    let progress = crate::tasks::Progress::of(&tasks);
+    let log_entry = format!(
+        "\n## Warning: {} — {} set impl=pass with {}\n\n{}\n",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        role,
+        progress,
+        open.iter().map(|t| format!("- [ ] {}", t.text)).collect::<Vec<_>>().join("\n"),
+    );
+    runlog::append(work_dir, &log_entry)?;
 
+    println!("warning: impl=pass with {} — unchecked in tasks.md:", progress);
+    for task in open {
+        println!("  - [ ] {}", task.text);
+    }
     Ok(())
 }
 
@@ -118,19 +263,14 @@ pub fn nudge(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
     }
 
     let state = read_state(&work_dir.join("state.json"))?;
+    require_role_for(base, role, &state)?;
     let session_name = if !state.workspace.tmux_session.is_empty() {
         state.workspace.tmux_session.clone()
     } else {
         format!("pfm-{}-{}", work_id, role)
     };
 
-    let gate = role_to_gate(role);
-    let nudge_msg = format!(
-        "Resume your work. Check {}/state.json for current state. \
-         Your gate is '{}'. Complete your role spec requirements and write a handoff note.",
-        work_dir.display(),
-        gate,
-    );
+    let nudge_msg = render_nudge_prompt(role, &work_dir, base);
 
     if crate::adapters::tmux::session_exists(&session_name) {
         crate::adapters::tmux::send_keys(&session_name, &nudge_msg)?;
@@ -144,18 +284,6 @@ pub fn nudge(base: &Path, role: &Role, work_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn append_to_runlog(work_dir: &Path, entry: &str) -> Result<(), String> {
-    let runlog_path = work_dir.join("runlog.md");
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&runlog_path)
-        .map_err(|e| format!("failed to open runlog: {}", e))?;
-    file.write_all(entry.as_bytes())
-        .map_err(|e| format!("failed to write runlog: {}", e))?;
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,6 +317,179 @@ mod tests {
         assert!(prompt.contains("/exit"));
     }
 
+    #[test]
+    fn test_render_bootstrap_prompt_override() {
+        let dir = tempdir().unwrap();
+        let work_dir = dir.path().join("work/FEAT-001");
+        fs::create_dir_all(dir.path().join(".pfm/prompts")).unwrap();
+        fs::write(
+            dir.path().join(".pfm/prompts/bootstrap.md"),
+            "Custom {{role}} prompt for {{work.id}}",
+        ).unwrap();
+        let prompt = render_bootstrap_prompt(&Role::Qa, &work_dir, dir.path());
+        assert_eq!(prompt, "Custom qa prompt for FEAT-001");
+    }
+
+    #[test]
+    fn test_render_bootstrap_prompt_points_to_context_pack() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Packed", Some("FEAT-001"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-001");
+        fs::write(work_dir.join("plan.md"), "# Plan").unwrap();
+        crate::context::write(&work_dir, &Role::Implementation, 1000).unwrap();
+
+        // Packing disabled: the stale pack is not mentioned
+        let prompt = render_bootstrap_prompt(&Role::Implementation, &work_dir, dir.path());
+        assert!(!prompt.contains("context-implementation.md"));
+
+        fs::write(
+            dir.path().join(".pfm/config.local.json"),
+            r#"{"context": {"enabled": true}}"#,
+        ).unwrap();
+        let prompt = render_bootstrap_prompt(&Role::Implementation, &work_dir, dir.path());
+        assert!(prompt.contains("artifacts/context-implementation.md — read it first"));
+    }
+
+    #[test]
+    fn test_enforce_completion_resets_gate() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Test", Some("FEAT-EC"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-EC");
+        let state_path = work_dir.join("state.json");
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("plan", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+
+        let contract = crate::role_spec::load(dir.path(), &Role::Orchestrator).unwrap().unwrap();
+        assert!(enforce_completion(dir.path(), &work_dir, &Role::Orchestrator, Some(&contract)).unwrap());
+        assert_eq!(read_state(&state_path).unwrap().gates.plan, GateStatus::InProgress);
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("## Completion Rejected: "));
+        assert!(runlog.contains("- plan.md is still the template"));
+
+        fs::write(work_dir.join("plan.md"), "# Plan\n\n1. Do it\n").unwrap();
+        fs::write(work_dir.join("tasks.md"), "- [ ] Do it\n").unwrap();
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("plan", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+        assert!(!enforce_completion(dir.path(), &work_dir, &Role::Orchestrator, Some(&contract)).unwrap());
+        assert_eq!(read_state(&state_path).unwrap().gates.plan, GateStatus::Pass);
+    }
+
+    #[test]
+    fn test_impl_pass_with_open_tasks_warns() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Test", Some("FEAT-IT"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-IT");
+        fs::write(work_dir.join("tasks.md"), "## Tasks\n\n- [x] Endpoint\n- [ ] Docs\n").unwrap();
+        let state_path = work_dir.join("state.json");
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("impl", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+
+        assert!(!enforce_completion(dir.path(), &work_dir, &Role::Implementation, None).unwrap());
+        assert_eq!(read_state(&state_path).unwrap().gates.impl_, GateStatus::Pass);
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("implementation set impl=pass with 1/2 tasks done"));
+        assert!(runlog.contains("- [ ] Docs\n"));
+    }
+
+    #[test]
+    fn test_qa_pass_requires_result_per_criterion() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Test", Some("FEAT-QA"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-QA");
+        fs::write(work_dir.join("acceptance.md"), "## Criteria\n\n- [ ] AC-1: Login\n- [ ] AC-2: Logout\n").unwrap();
+        fs::write(work_dir.join("qa.md"), "# QA\n\n- AC-1: PASS\n").unwrap();
+        let state_path = work_dir.join("state.json");
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("qa", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+
+        assert!(enforce_completion(dir.path(), &work_dir, &Role::Qa, None).unwrap());
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("- no QA result recorded for AC-2"));
+
+        fs::write(work_dir.join("qa.md"), "# QA\n\n- AC-1: PASS\n- AC-2: PASS\n").unwrap();
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("qa", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+        assert!(!enforce_completion(dir.path(), &work_dir, &Role::Qa, None).unwrap());
+    }
+
+    #[test]
+    fn test_template_criteria_are_not_numbered() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Test", Some("FEAT-AN"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-AN");
+        let template = fs::read_to_string(work_dir.join("acceptance.md")).unwrap();
+        assert!(!written(dir.path(), &work_dir, "acceptance.md"));
+
+        // One real criterion next to the placeholders is still only partly filled
+        let partial = template.replace("- [ ] Criterion 1", "- [ ] Login works");
+        fs::write(work_dir.join("acceptance.md"), &partial).unwrap();
+        assert!(!written(dir.path(), &work_dir, "acceptance.md"));
+
+        let filled = partial.replace("- [ ] Criterion 2", "- [ ] Logout works").replace("1. Step 1\n2. Step 2", "1. Log in and out");
+        fs::write(work_dir.join("acceptance.md"), &filled).unwrap();
+        assert!(written(dir.path(), &work_dir, "acceptance.md"));
+        assert!(!written(dir.path(), &work_dir, "missing.md"));
+    }
+
+    #[test]
+    fn test_template_requirements_are_not_numbered() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        // A repo PRD template with example bullets under Requirements
+        let template = crate::templates::PRD_MD.replace(
+            "<!-- One requirement per top-level bullet; PFM numbers them REQ-1, REQ-2, … -->",
+            "- Requirement 1\n- Requirement 2",
+        );
+        fs::write(dir.path().join(".pfm/templates/prd.md"), &template).unwrap();
+        crate::commands::work::new_work(dir.path(), "Test", Some("FEAT-RN"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-RN");
+        assert!(fs::read_to_string(work_dir.join("prd.md")).unwrap().contains("- Requirement 1"));
+        assert!(!written(dir.path(), &work_dir, "prd.md"));
+
+        let prd = fs::read_to_string(work_dir.join("prd.md")).unwrap();
+        fs::write(work_dir.join("prd.md"), prd.replace("- Requirement 1", "- Export as CSV")).unwrap();
+        assert!(!written(dir.path(), &work_dir, "prd.md"));
+
+        let prd = fs::read_to_string(work_dir.join("prd.md")).unwrap();
+        fs::write(work_dir.join("prd.md"), prd.replace("- Requirement 2", "- Export as PDF")).unwrap();
+        assert!(written(dir.path(), &work_dir, "prd.md"));
+        assert!(crate::trace::assign_requirement_ids(&work_dir).unwrap());
+        assert!(fs::read_to_string(work_dir.join("prd.md")).unwrap().contains("- REQ-2: Export as PDF"));
+    }
+
+    #[test]
+    fn test_nudge_requires_a_role_in_the_pipeline() {
+        let dir = tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Nudge", Some("FEAT-NUDGE"), Some("rust")).unwrap();
+
+        let typo: Role = "qa_revew".parse().unwrap();
+        let err = nudge(dir.path(), &typo, "FEAT-NUDGE").unwrap_err();
+        assert!(err.contains("custom role 'qa_revew' has no role spec"), "{}", err);
+
+        fs::write(dir.path().join(".pfm/roles/docs.md"), "# Role: Docs").unwrap();
+        let err = nudge(dir.path(), &"docs".parse().unwrap(), "FEAT-NUDGE").unwrap_err();
+        assert!(err.contains("gate 'docs' is not part of FEAT-NUDGE's pipeline"), "{}", err);
+    }
+
+    #[test]
+    fn test_render_nudge_prompt_mentions_gate() {
+        let dir = tempdir().unwrap();
+        let work_dir = dir.path().join("work/FEAT-001");
+        let prompt = render_nudge_prompt(&Role::Implementation, &work_dir, dir.path());
+        assert!(prompt.contains("Your gate is 'impl'"));
+    }
+
     #[test]
     fn test_render_bootstrap_prompt_all_roles() {
         let dir = tempdir().unwrap();
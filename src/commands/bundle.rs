@@ -0,0 +1,366 @@
+use crate::runlog;
+use crate::state::{read_state, write_state};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Bump when the bundle layout changes incompatibly
+pub const SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub schema_version: u32,
+    pub pfm_version: String,
+    pub work_id: String,
+    pub exported_at: String,
+    /// Repo root the bundle was exported from, used to rewrite absolute paths on import
+    pub source_root: String,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Relative to the work item directory
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Files under `dir`, as sorted `/`-separated paths relative to it
+fn walk(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("failed to read {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, &format!("{}/", name), files)?;
+        } else {
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Write a work item (active or archived) with its manifest to a tar file
+pub fn export(base: &Path, work_id: &str, output: &Path) -> Result<Manifest, String> {
+    let (work_dir, _) = crate::commands::lifecycle::locate(base, work_id)
+        .ok_or_else(|| format!("work item {} not found", work_id))?;
+
+    let mut files = Vec::new();
+    walk(&work_dir, "", &mut files)?;
+    let mut contents = Vec::new();
+    for (name, path) in files {
+        let data = fs::read(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        contents.push((name, data));
+    }
+
+    let manifest = Manifest {
+        schema_version: SCHEMA_VERSION,
+        pfm_version: env!("CARGO_PKG_VERSION").to_string(),
+        work_id: work_id.to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        source_root: base.display().to_string(),
+        files: contents
+            .iter()
+            .map(|(name, data)| ManifestFile { path: name.clone(), size: data.len() as u64, sha256: sha256(data) })
+            .collect(),
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("failed to serialize manifest: {}", e))?;
+
+    let file = File::create(output).map_err(|e| format!("failed to create {}: {}", output.display(), e))?;
+    let mut tar = tar::Builder::new(file);
+    let mtime = Utc::now().timestamp() as u64;
+    let mut append = |path: &str, data: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        tar.append_data(&mut header, path, data)
+            .map_err(|e| format!("failed to write {} to bundle: {}", path, e))
+    };
+    append(MANIFEST, &manifest_json)?;
+    for (name, data) in &contents {
+        append(&format!("work/{}/{}", work_id, name), data)?;
+    }
+    tar.into_inner()
+        .and_then(|mut f| f.flush())
+        .map_err(|e| format!("failed to write {}: {}", output.display(), e))?;
+
+    println!("exported {} ({} files) to {}", work_id, manifest.files.len(), output.display());
+    Ok(manifest)
+}
+
+/// Read a bundle, checking its schema version and every file's checksum
+pub fn read_bundle(path: &Path) -> Result<(Manifest, BTreeMap<String, Vec<u8>>), String> {
+    let file = File::open(path).map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let mut archive = tar::Archive::new(file);
+    let mut entries: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for entry in archive.entries().map_err(|e| format!("failed to read bundle: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("failed to read bundle: {}", e))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(|e| format!("bad path in bundle: {}", e))?.to_path_buf();
+        let mut parts = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::CurDir => {}
+                _ => return Err(format!("bundle entry {} escapes the bundle", path.display())),
+            }
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| format!("failed to read bundle: {}", e))?;
+        entries.insert(parts.join("/"), data);
+    }
+
+    let manifest: Manifest = serde_json::from_slice(
+        &entries.remove(MANIFEST).ok_or_else(|| format!("{} has no {}", path.display(), MANIFEST))?,
+    )
+    .map_err(|e| format!("invalid bundle manifest: {}", e))?;
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "bundle schema version {} is newer than this pfm supports ({}) — upgrade pfm",
+            manifest.schema_version, SCHEMA_VERSION
+        ));
+    }
+
+    // The manifest names the paths written on import, so they must stay inside the work dir
+    let normal = |p: &str| !p.is_empty() && Path::new(p).components().all(|c| matches!(c, Component::Normal(_)));
+    crate::ids::validate(&manifest.work_id).map_err(|e| format!("bundle manifest: {}", e))?;
+    if let Some(bad) = manifest.files.iter().find(|f| !normal(&f.path)) {
+        return Err(format!("bundle entry {} escapes the bundle", bad.path));
+    }
+
+    let prefix = format!("work/{}/", manifest.work_id);
+    let mut files = BTreeMap::new();
+    for expected in &manifest.files {
+        let data = entries
+            .remove(&format!("{}{}", prefix, expected.path))
+            .ok_or_else(|| format!("bundle is missing {}", expected.path))?;
+        if sha256(&data) != expected.sha256 || data.len() as u64 != expected.size {
+            return Err(format!("checksum mismatch for {} — the bundle is corrupt or was modified", expected.path));
+        }
+        files.insert(expected.path.clone(), data);
+    }
+    if let Some(extra) = entries.keys().next() {
+        return Err(format!("bundle contains {}, which is not in the manifest", extra));
+    }
+    Ok((manifest, files))
+}
+
+/// Unpack a bundle into `.pfm/work/<id>`, rewriting paths that pointed into the source repo.
+/// The item is assembled in a staging directory and only moved into place once it checks out.
+pub fn import_bundle(base: &Path, path: &Path) -> Result<String, String> {
+    let (manifest, files) = read_bundle(path)?;
+    let work_id = &manifest.work_id;
+    if let Some((existing, _)) = crate::commands::lifecycle::locate(base, work_id) {
+        return Err(format!("work item {} already exists ({})", work_id, existing.display()));
+    }
+    if !base.join(".pfm").exists() {
+        return Err("not initialized — run `pfm init` first".into());
+    }
+
+    let staging = base.join(".pfm").join(format!(".import-{}", uuid::Uuid::new_v4()));
+    let work_dir = base.join(".pfm/work").join(work_id);
+    let staged = stage(base, path, &manifest, &files, &staging).and_then(|rewritten| {
+        fs::create_dir_all(base.join(".pfm/work"))
+            .and_then(|_| fs::rename(&staging, &work_dir))
+            .map(|_| rewritten)
+            .map_err(|e| format!("failed to move {} into {}: {}", work_id, work_dir.display(), e))
+    });
+    let rewritten = match staged {
+        Ok(rewritten) => rewritten,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    println!("imported {} ({} files, checksums verified)", work_id, files.len());
+    for change in &rewritten {
+        println!("  {}", change);
+    }
+    Ok(work_id.clone())
+}
+
+/// Write the bundle's files to `dir` and fix up its state for this repo; returns what was rewritten
+fn stage(
+    base: &Path,
+    path: &Path,
+    manifest: &Manifest,
+    files: &BTreeMap<String, Vec<u8>>,
+    dir: &Path,
+) -> Result<Vec<String>, String> {
+    for (name, data) in files {
+        let target = dir.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, data).map_err(|e| format!("failed to write {}: {}", target.display(), e))?;
+    }
+    for sub in ["handoffs", "artifacts"] {
+        fs::create_dir_all(dir.join(sub)).map_err(|e| format!("failed to create {} dir: {}", sub, e))?;
+    }
+
+    let state_path = dir.join("state.json");
+    let mut state = read_state(&state_path).map_err(|e| format!("bundle has a bad state.json: {}", e))?;
+    if state.id != manifest.work_id {
+        return Err(format!(
+            "bundle manifest is for {} but its state.json is for {}",
+            manifest.work_id, state.id
+        ));
+    }
+    let mut rewritten = Vec::new();
+    let worktree = &state.workspace.worktree;
+    if !worktree.is_empty() {
+        let relocated = Path::new(worktree)
+            .strip_prefix(&manifest.source_root)
+            .map(|rest| base.join(rest))
+            .ok()
+            .filter(|p| p.exists());
+        let new = relocated.map(|p| p.display().to_string()).unwrap_or_default();
+        rewritten.push(format!("workspace.worktree: {} → {}", worktree, if new.is_empty() { "(none)" } else { &new }));
+        state.workspace.worktree = new;
+    }
+    // Sessions and containers belong to the machine the bundle came from
+    state.workspace.tmux_session.clear();
+    state.workspace.container.clear();
+    // Gate snapshots only mean something if their tree objects are in this repo
+    let before = state.gate_refs.len();
+    state.gate_refs.retain(|_, refs| {
+        [&refs.start, &refs.end]
+            .iter()
+            .filter(|sha| !sha.is_empty())
+            .all(|sha| crate::adapters::git::run(base, &["cat-file", "-e", sha]).is_ok())
+    });
+    if state.gate_refs.len() < before {
+        rewritten.push(format!("dropped {} gate snapshot(s) missing from this repo", before - state.gate_refs.len()));
+    }
+    write_state(&state_path, &state)?;
+
+    let entry = format!(
+        "\n## Imported: {} — from {} (exported {} from {})\n{}",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        path.display(),
+        manifest.exported_at,
+        manifest.source_root,
+        rewritten.iter().map(|r| format!("\n- {}", r)).collect::<String>() + "\n"
+    );
+    runlog::append(dir, &entry)?;
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    fn exported() -> (tempfile::TempDir, PathBuf) {
+        let src = tempdir().unwrap();
+        init::run(src.path()).unwrap();
+        work::new_work(src.path(), "Portable", Some("FEAT-port"), Some("rust")).unwrap();
+        let work_dir = src.path().join(".pfm/work/FEAT-port");
+        fs::write(work_dir.join("handoffs/prd-1.md"), "PRD done").unwrap();
+        let mut state = read_state(&work_dir.join("state.json")).unwrap();
+        state.workspace.worktree = src.path().join("wt/FEAT-port").display().to_string();
+        state.workspace.tmux_session = "pfm-FEAT-port".into();
+        write_state(&work_dir.join("state.json"), &state).unwrap();
+
+        let bundle = src.path().join("bundle.tar");
+        export(src.path(), "FEAT-port", &bundle).unwrap();
+        (src, bundle)
+    }
+
+    #[test]
+    fn test_round_trip_rewrites_paths() {
+        let (_src, bundle) = exported();
+        let dest = tempdir().unwrap();
+        init::run(dest.path()).unwrap();
+        fs::create_dir_all(dest.path().join("wt/FEAT-port")).unwrap();
+
+        import_bundle(dest.path(), &bundle).unwrap();
+        let work_dir = dest.path().join(".pfm/work/FEAT-port");
+        assert_eq!(fs::read_to_string(work_dir.join("handoffs/prd-1.md")).unwrap(), "PRD done");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.workspace.worktree, dest.path().join("wt/FEAT-port").display().to_string());
+        assert!(state.workspace.tmux_session.is_empty());
+        assert!(fs::read_to_string(work_dir.join("runlog.md")).unwrap().contains("## Imported:"));
+
+        assert!(import_bundle(dest.path(), &bundle).unwrap_err().contains("already exists"));
+    }
+
+    #[test]
+    fn test_bad_state_leaves_nothing_behind() {
+        let (src, _) = exported();
+        let work_dir = src.path().join(".pfm/work/FEAT-port");
+        let good = fs::read_to_string(work_dir.join("state.json")).unwrap();
+        let dest = tempdir().unwrap();
+        init::run(dest.path()).unwrap();
+        let leftovers = |dest: &Path| {
+            fs::read_dir(dest.join(".pfm")).unwrap().flatten().filter(|e| e.file_name().to_string_lossy().starts_with(".import-")).count()
+        };
+
+        fs::write(work_dir.join("state.json"), good.replace("\"FEAT-port\"", "\"FEAT-other\"")).unwrap();
+        let bundle = src.path().join("renamed.tar");
+        export(src.path(), "FEAT-port", &bundle).unwrap();
+        let err = import_bundle(dest.path(), &bundle).unwrap_err();
+        assert!(err.contains("manifest is for FEAT-port but its state.json is for FEAT-other"), "{}", err);
+        assert!(!dest.path().join(".pfm/work/FEAT-port").exists());
+
+        fs::write(work_dir.join("state.json"), "{ not json").unwrap();
+        export(src.path(), "FEAT-port", &bundle).unwrap();
+        assert!(import_bundle(dest.path(), &bundle).unwrap_err().contains("bad state.json"));
+        assert!(!dest.path().join(".pfm/work/FEAT-port").exists());
+        assert_eq!(leftovers(dest.path()), 0);
+
+        fs::write(work_dir.join("state.json"), &good).unwrap();
+        export(src.path(), "FEAT-port", &bundle).unwrap();
+        import_bundle(dest.path(), &bundle).unwrap();
+        assert!(dest.path().join(".pfm/work/FEAT-port/state.json").exists());
+        assert_eq!(leftovers(dest.path()), 0);
+    }
+
+    #[test]
+    fn test_manifest_lists_checksums() {
+        let (_src, bundle) = exported();
+        let (manifest, files) = read_bundle(&bundle).unwrap();
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.work_id, "FEAT-port");
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.contains(&"state.json") && paths.contains(&"handoffs/prd-1.md"));
+        assert_eq!(files["handoffs/prd-1.md"], b"PRD done");
+        assert_eq!(manifest.files.iter().find(|f| f.path == "handoffs/prd-1.md").unwrap().sha256, sha256(b"PRD done"));
+    }
+
+    #[test]
+    fn test_tampered_bundle_rejected() {
+        let (src, bundle) = exported();
+        let unpacked = src.path().join("unpacked");
+        tar::Archive::new(File::open(&bundle).unwrap()).unpack(&unpacked).unwrap();
+        fs::write(unpacked.join("work/FEAT-port/handoffs/prd-1.md"), "PRD tampered").unwrap();
+        let repacked = src.path().join("repacked.tar");
+        let mut tar = tar::Builder::new(File::create(&repacked).unwrap());
+        tar.append_dir_all(".", &unpacked).unwrap();
+        tar.into_inner().unwrap();
+
+        let err = read_bundle(&repacked).unwrap_err();
+        assert!(err.contains("checksum mismatch for handoffs/prd-1.md"), "{}", err);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::config::{self, LayerPaths};
+use crate::output::Format;
+use crate::state::{read_state, write_state};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Which layer `pfm config set` writes to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scope {
+    Global,
+    Repo,
+    Local,
+    Work(String),
+}
+
+/// One effective config value and the layer(s) it came from
+#[derive(Debug, Serialize)]
+pub struct EntryView {
+    pub key: String,
+    pub value: Value,
+    pub origin: String,
+}
+
+/// Print the effective value of a key, optionally with where it came from
+pub fn get(base: &Path, key: &str, with_origin: bool, work_id: Option<&str>, format: Format) -> Result<(), String> {
+    format.emit(&lookup(base, key, work_id)?, |entry| {
+        if with_origin {
+            println!("{:<40} {}", display_value(&entry.value), entry.origin);
+        } else {
+            println!("{}", display_value(&entry.value));
+        }
+    })
+}
+
+/// The effective value of a key and the layer(s) it came from. A key naming a section
+/// (e.g. `stacks.rails`) lists the origin of every value below it.
+fn lookup(base: &Path, key: &str, work_id: Option<&str>) -> Result<EntryView, String> {
+    if let Some(work_id) = work_id {
+        if let Some(entry) = work_entries(base, work_id)?.into_iter().find(|e| e.key == key) {
+            return Ok(entry);
+        }
+    }
+
+    let layered = config::load(base)?;
+    let value = layered
+        .get(key)
+        .ok_or_else(|| format!("unknown config key: {}", key))?;
+    let mut origins: Vec<String> = Vec::new();
+    for (k, origin) in &layered.origins {
+        let origin = origin.to_string();
+        if (k == key || k.starts_with(&format!("{}.", key))) && !origins.contains(&origin) {
+            origins.push(origin);
+        }
+    }
+    Ok(EntryView { key: key.to_string(), value: value.clone(), origin: origins.join(", ") })
+}
+
+/// Set a key in the chosen layer
+pub fn set(base: &Path, key: &str, value: &str, scope: &Scope) -> Result<(), String> {
+    if let Scope::Work(work_id) = scope {
+        return set_work_command(base, work_id, key, value);
+    }
+
+    let paths = LayerPaths::for_repo(base);
+    let path: PathBuf = match scope {
+        Scope::Global => paths
+            .global
+            .ok_or("cannot determine global config path (HOME is not set)")?,
+        Scope::Repo => paths.repo,
+        Scope::Local => paths.local,
+        Scope::Work(_) => unreachable!(),
+    };
+
+    let previous = if path.exists() {
+        Some(config::read_layer(&path)?)
+    } else {
+        None
+    };
+    let mut layer = previous.clone().unwrap_or_else(|| Value::Object(Default::default()));
+    config::set_path(&mut layer, key, config::parse_value(value));
+    config::write_layer(&path, &layer)?;
+
+    // Reject writes that leave the effective config unloadable
+    if let Err(e) = config::load(base) {
+        match previous {
+            Some(prev) => config::write_layer(&path, &prev)?,
+            None => std::fs::remove_file(&path)
+                .map_err(|err| format!("failed to remove {}: {}", path.display(), err))?,
+        }
+        return Err(format!("refusing to set {}: {}", key, e));
+    }
+
+    println!("set {} = {} in {}", key, value, path.display());
+    Ok(())
+}
+
+/// Print every effective value, optionally with where it came from
+pub fn show(base: &Path, with_origin: bool, work_id: Option<&str>, format: Format) -> Result<(), String> {
+    let layered = config::load(base)?;
+    let mut entries: Vec<EntryView> = layered
+        .entries()
+        .into_iter()
+        .map(|(key, value, origin)| EntryView { key, value, origin: origin.to_string() })
+        .collect();
+    if let Some(work_id) = work_id {
+        entries.extend(work_entries(base, work_id)?);
+    }
+
+    format.emit(&entries, |entries| {
+        for entry in entries {
+            let value = display_value(&entry.value);
+            if with_origin {
+                println!("{:<32} {:<40} {}", entry.key, value, entry.origin);
+            } else {
+                println!("{} = {}", entry.key, value);
+            }
+        }
+    })
+}
+
+/// Per-work command overrides, stored in the work item's state.json
+fn work_entries(base: &Path, work_id: &str) -> Result<Vec<EntryView>, String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let state = read_state(&state_path)?;
+    let origin = format!("work ({})", work_id);
+    Ok([
+        ("commands.verify", state.commands.verify),
+        ("commands.security", state.commands.security),
+        ("commands.qa_smoke", state.commands.qa_smoke),
+    ]
+    .into_iter()
+    .map(|(key, value)| EntryView { key: key.to_string(), value: Value::String(value), origin: origin.clone() })
+    .collect())
+}
+
+fn set_work_command(base: &Path, work_id: &str, key: &str, value: &str) -> Result<(), String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let mut state = read_state(&state_path)?;
+    match key {
+        "commands.verify" => state.commands.verify = value.to_string(),
+        "commands.security" => state.commands.security = value.to_string(),
+        "commands.qa_smoke" => state.commands.qa_smoke = value.to_string(),
+        _ => {
+            return Err(format!(
+                "unknown work config key: {} (use commands.verify, commands.security or commands.qa_smoke)",
+                key
+            ))
+        }
+    }
+    state.touch();
+    write_state(&state_path, &state)?;
+    println!("set {} = {} for {}", key, value, work_id);
+    Ok(())
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_local_overrides_repo() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        set(dir.path(), "default_stack", "rust", &Scope::Local).unwrap();
+
+        let layered = config::load(dir.path()).unwrap();
+        assert_eq!(layered.config.default_stack, "rust");
+        assert!(dir.path().join(".pfm/config.local.json").exists());
+        // Repo file untouched
+        let repo = config::read_layer(&dir.path().join(".pfm/config.json")).unwrap();
+        assert_eq!(repo["default_stack"], "rails");
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_value() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        let result = set(dir.path(), "stacks.rails.verify", "42", &Scope::Local);
+        assert!(result.is_err());
+        assert!(!dir.path().join(".pfm/config.local.json").exists());
+    }
+
+    #[test]
+    fn test_set_work_command() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Test", Some("FEAT-CFG"), Some("rails")).unwrap();
+        set(dir.path(), "commands.verify", "bin/rspec spec/models", &Scope::Work("FEAT-CFG".into()))
+            .unwrap();
+
+        let entries = work_entries(dir.path(), "FEAT-CFG").unwrap();
+        assert!(entries.iter().any(|e| e.key == "commands.verify" && e.value == "bin/rspec spec/models"));
+        assert!(set(dir.path(), "default_stack", "rust", &Scope::Work("FEAT-CFG".into())).is_err());
+    }
+
+    #[test]
+    fn test_lookup_reports_origin() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        set(dir.path(), "stacks.rust.verify", "cargo nextest run", &Scope::Local).unwrap();
+        let local = dir.path().join(".pfm/config.local.json");
+
+        let entry = lookup(dir.path(), "stacks.rust.verify", None).unwrap();
+        assert_eq!(entry.value, "cargo nextest run");
+        assert_eq!(entry.origin, config::Origin::Local(local.clone()).to_string());
+        let entry = lookup(dir.path(), "stacks.rust", None).unwrap();
+        assert!(entry.origin.contains(&config::Origin::Local(local).to_string()));
+        assert!(entry.origin.contains(", "));
+        assert_eq!(entry.value["verify"], "cargo nextest run");
+
+        work::new_work(dir.path(), "Test", Some("FEAT-OR"), Some("rust")).unwrap();
+        let entry = lookup(dir.path(), "commands.verify", Some("FEAT-OR")).unwrap();
+        assert_eq!(entry.origin, "work (FEAT-OR)");
+        let json: serde_json::Value = serde_json::from_str(&crate::output::to_json(&entry).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({"key": "commands.verify", "value": "cargo nextest run", "origin": "work (FEAT-OR)"}));
+        assert!(lookup(dir.path(), "nope", None).unwrap_err().contains("unknown config key"));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::deps::{describe, Graph};
+use crate::output::Format;
+use crate::state::{read_state, write_state, WorkStatus};
+use serde::Serialize;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// How often `pfm run --wait` rechecks upstream items
+const WAIT_POLL: Duration = Duration::from_secs(30);
+
+/// Make `work_id` depend on `upstream`
+pub fn add(base: &Path, work_id: &str, upstream: &str) -> Result<(), String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    if upstream == work_id {
+        return Err(format!("{} cannot depend on itself", work_id));
+    }
+    let graph = Graph::load(base)?;
+    if !graph.nodes.contains_key(upstream) {
+        return Err(format!("work item {} not found", upstream));
+    }
+    if let Some(path) = graph.path(upstream, work_id) {
+        return Err(format!(
+            "{} → {} would create a cycle: {} → {}",
+            work_id,
+            upstream,
+            work_id,
+            path.join(" → ")
+        ));
+    }
+
+    let mut state = read_state(&state_path)?;
+    if state.depends_on.iter().any(|d| d == upstream) {
+        return Err(format!("{} already depends on {}", work_id, upstream));
+    }
+    state.depends_on.push(upstream.to_string());
+    state.touch();
+    write_state(&state_path, &state)?;
+    println!("{} now depends on {}", work_id, upstream);
+    Ok(())
+}
+
+/// Drop `upstream` from `work_id`'s dependencies
+pub fn remove(base: &Path, work_id: &str, upstream: &str) -> Result<(), String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let mut state = read_state(&state_path)?;
+    let before = state.depends_on.len();
+    state.depends_on.retain(|d| d != upstream);
+    if state.depends_on.len() == before {
+        return Err(format!("{} does not depend on {}", work_id, upstream));
+    }
+    state.touch();
+    write_state(&state_path, &state)?;
+    println!("{} no longer depends on {}", work_id, upstream);
+    Ok(())
+}
+
+/// Fail unless every upstream item of `work_id` is done. With `wait`, poll until they are.
+pub fn ensure_ready(base: &Path, work_id: &str, wait: bool) -> Result<(), String> {
+    let mut announced = false;
+    loop {
+        let graph = Graph::load(base)?;
+        if let Some(cycle) = graph.cycles().into_iter().find(|c| c.iter().any(|id| id == work_id)) {
+            return Err(format!("{} is in a dependency cycle: {}", work_id, cycle.join(" → ")));
+        }
+        let unmet = graph.unmet(work_id);
+        if unmet.is_empty() {
+            return Ok(());
+        }
+        if let Some((missing, _)) = unmet.iter().find(|(_, status)| status.is_none()) {
+            return Err(format!("{} depends on {}, which does not exist", work_id, missing));
+        }
+        if !wait {
+            return Err(format!(
+                "{} is waiting on {} — finish them first or pass --wait",
+                work_id,
+                describe(&unmet)
+            ));
+        }
+        if !announced {
+            println!("waiting for {} to be done (checking every {}s)", describe(&unmet), WAIT_POLL.as_secs());
+            announced = true;
+        }
+        thread::sleep(WAIT_POLL);
+    }
+}
+
+/// One work item in `pfm work graph`
+#[derive(Debug, Serialize)]
+pub struct GraphNodeView {
+    pub id: String,
+    pub title: String,
+    pub status: WorkStatus,
+    pub depends_on: Vec<String>,
+    /// Upstream items that aren't done yet
+    pub waiting_on: Vec<String>,
+    pub archived: bool,
+}
+
+/// What `pfm work graph` reports. Items are listed upstream first.
+#[derive(Debug, Serialize)]
+pub struct GraphView {
+    pub items: Vec<GraphNodeView>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+/// Build the graph view. Archived items appear only when something depends on them.
+pub fn view(base: &Path) -> Result<GraphView, String> {
+    let graph = Graph::load(base)?;
+    let referenced: Vec<&String> = graph.nodes.values().flat_map(|n| &n.depends_on).collect();
+    let items = graph
+        .order()
+        .into_iter()
+        .filter_map(|id| {
+            let node = &graph.nodes[&id];
+            if node.archived && !referenced.contains(&&id) {
+                return None;
+            }
+            Some(GraphNodeView {
+                waiting_on: graph.unmet(&id).into_iter().map(|(dep, _)| dep).collect(),
+                title: node.title.clone(),
+                status: node.status.clone(),
+                depends_on: node.depends_on.clone(),
+                archived: node.archived,
+                id,
+            })
+        })
+        .collect();
+    Ok(GraphView { items, cycles: graph.cycles() })
+}
+
+/// Print the dependency graph; fails if it has a cycle
+pub fn graph(base: &Path, format: Format) -> Result<(), String> {
+    let view = view(base)?;
+    format.emit(&view, print_graph)?;
+    if view.cycles.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} dependency cycle(s) found", view.cycles.len()))
+    }
+}
+
+fn print_graph(view: &GraphView) {
+    if view.items.is_empty() {
+        println!("no work items found");
+        return;
+    }
+    for item in &view.items {
+        let mut line = format!("{:<20} {:<12}", item.id, item.status.to_string());
+        if !item.depends_on.is_empty() {
+            line.push_str(&format!(" ← {}", item.depends_on.join(", ")));
+        }
+        if !item.waiting_on.is_empty() {
+            line.push_str(&format!("  (waiting on {})", item.waiting_on.join(", ")));
+        }
+        if item.archived {
+            line.push_str("  [archived]");
+        }
+        println!("{}", line.trim_end());
+    }
+    for cycle in &view.cycles {
+        println!();
+        println!("[!!] cycle: {}", cycle.join(" → "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, lifecycle, work};
+    use crate::state::NoteKind;
+    use tempfile::tempdir;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        for id in ["FEAT-a", "FEAT-b", "FEAT-c"] {
+            work::new_work(dir.path(), id, Some(id), Some("rust")).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_add_rejects_cycles_and_unknown_items() {
+        let dir = setup();
+        add(dir.path(), "FEAT-b", "FEAT-a").unwrap();
+        add(dir.path(), "FEAT-c", "FEAT-b").unwrap();
+        let err = add(dir.path(), "FEAT-a", "FEAT-c").unwrap_err();
+        assert!(err.contains("cycle: FEAT-a → FEAT-c → FEAT-b → FEAT-a"), "{}", err);
+        assert!(add(dir.path(), "FEAT-a", "FEAT-a").unwrap_err().contains("itself"));
+        assert!(add(dir.path(), "FEAT-a", "FEAT-z").unwrap_err().contains("FEAT-z not found"));
+        assert!(add(dir.path(), "FEAT-b", "FEAT-a").unwrap_err().contains("already depends"));
+
+        let view = view(dir.path()).unwrap();
+        let ids: Vec<&str> = view.items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["FEAT-a", "FEAT-b", "FEAT-c"]);
+        assert_eq!(view.items[2].waiting_on, vec!["FEAT-b"]);
+
+        remove(dir.path(), "FEAT-c", "FEAT-b").unwrap();
+        assert!(remove(dir.path(), "FEAT-c", "FEAT-b").is_err());
+    }
+
+    #[test]
+    fn test_ready_only_when_upstream_done() {
+        let dir = setup();
+        add(dir.path(), "FEAT-b", "FEAT-a").unwrap();
+        let err = ensure_ready(dir.path(), "FEAT-b", false).unwrap_err();
+        assert!(err.contains("waiting on FEAT-a (in_progress)"), "{}", err);
+        ensure_ready(dir.path(), "FEAT-a", false).unwrap();
+
+        lifecycle::run(dir.path(), "FEAT-a", lifecycle::Action::Close, "done", false).unwrap();
+        ensure_ready(dir.path(), "FEAT-b", false).unwrap();
+    }
+
+    #[test]
+    fn test_graph_reports_hand_edited_cycles() {
+        let dir = setup();
+        add(dir.path(), "FEAT-b", "FEAT-a").unwrap();
+        let path = dir.path().join(".pfm/work/FEAT-a/state.json");
+        let mut state = read_state(&path).unwrap();
+        state.depends_on.push("FEAT-b".into());
+        write_state(&path, &state).unwrap();
+
+        assert_eq!(view(dir.path()).unwrap().cycles, vec![vec!["FEAT-a", "FEAT-b", "FEAT-a"]]);
+        assert!(graph(dir.path(), Format::Json).is_err());
+        assert!(ensure_ready(dir.path(), "FEAT-a", true).unwrap_err().contains("dependency cycle"));
+    }
+
+    #[test]
+    fn test_reopen_flags_downstream() {
+        let dir = setup();
+        add(dir.path(), "FEAT-b", "FEAT-a").unwrap();
+        add(dir.path(), "FEAT-c", "FEAT-b").unwrap();
+        lifecycle::run(dir.path(), "FEAT-a", lifecycle::Action::Close, "shipped", false).unwrap();
+        lifecycle::run(dir.path(), "FEAT-a", lifecycle::Action::Reopen, "regression", false).unwrap();
+
+        for id in ["FEAT-b", "FEAT-c"] {
+            let work_dir = dir.path().join(".pfm/work").join(id);
+            let state = read_state(&work_dir.join("state.json")).unwrap();
+            assert_eq!(state.notes.len(), 1);
+            assert_eq!(state.notes[0].text, "upstream FEAT-a was reopened: regression");
+            assert_eq!(state.notes[0].kind, NoteKind::Blocker);
+            let runlog = std::fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+            assert!(runlog.contains("## Upstream Reopened:"));
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use crate::config::{self, LayerPaths};
+use crate::output::Format;
+use crate::state::read_state;
+use serde::Serialize;
+use crate::templates;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Severity of a doctor finding
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Ok,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn indicator(&self) -> &'static str {
+        match self {
+            Level::Ok => "OK",
+            Level::Warn => "!!",
+            Level::Error => "XX",
+        }
+    }
+}
+
+/// One check result, grouped under a section heading
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub section: &'static str,
+    pub level: Level,
+    pub subject: String,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(section: &'static str, level: Level, subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Finding {
+            section,
+            level,
+            subject: subject.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// External tools pfm shells out to: (name, version flag, required)
+const TOOLS: &[(&str, &str, bool)] = &[
+    ("claude", "--version", true),
+    ("git", "--version", true),
+    ("tmux", "-V", false),
+    ("groot", "--version", false),
+];
+
+/// What `pfm doctor` reports
+#[derive(Debug, Serialize)]
+pub struct DoctorView {
+    pub errors: usize,
+    pub warnings: usize,
+    pub findings: Vec<Finding>,
+}
+
+/// Run all checks and print a report; fails if any check is an error
+pub fn run(base: &Path, format: Format) -> Result<(), String> {
+    let findings = diagnose(base);
+    let view = DoctorView {
+        errors: findings.iter().filter(|f| f.level == Level::Error).count(),
+        warnings: findings.iter().filter(|f| f.level == Level::Warn).count(),
+        findings,
+    };
+    format.emit(&view, print_report)?;
+    if view.errors > 0 {
+        Err(format!("doctor found {} error(s) and {} warning(s)", view.errors, view.warnings))
+    } else {
+        Ok(())
+    }
+}
+
+fn print_report(view: &DoctorView) {
+    let mut section = "";
+    for finding in &view.findings {
+        if finding.section != section {
+            if !section.is_empty() {
+                println!();
+            }
+            section = finding.section;
+            println!("{}:", section);
+        }
+        println!(
+            "  [{}] {:<28} {}",
+            finding.level.indicator(),
+            finding.subject,
+            finding.message
+        );
+    }
+
+    println!();
+    if view.errors == 0 {
+        println!("no errors ({} warning(s))", view.warnings);
+    }
+}
+
+/// Collect findings for config, tools, role specs, templates and work items
+pub fn diagnose(base: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_config(base, &mut findings);
+    check_tools(&mut findings);
+
+    let pfm_dir = base.join(".pfm");
+    if !pfm_dir.exists() {
+        findings.push(Finding::new("Repo", Level::Error, ".pfm", "not initialized — run `pfm init`"));
+        return findings;
+    }
+    check_role_specs(&pfm_dir, &mut findings);
+    check_custom_roles(base, &mut findings);
+    check_role_contracts(base, &mut findings);
+    check_templates(&pfm_dir, &mut findings);
+    check_prompts(base, &mut findings);
+    check_work_items(&pfm_dir, &mut findings);
+    findings
+}
+
+fn check_config(base: &Path, findings: &mut Vec<Finding>) {
+    const SECTION: &str = "Config";
+    let paths = LayerPaths::for_repo(base);
+    let layers = [paths.global.clone(), Some(paths.repo.clone()), Some(paths.local.clone())];
+
+    for path in layers.into_iter().flatten().filter(|p| p.exists()) {
+        let subject = path.display().to_string();
+        match config::read_layer(&path) {
+            Ok(value) => {
+                let errors = config::validate_layer(&value);
+                if errors.is_empty() {
+                    findings.push(Finding::new(SECTION, Level::Ok, subject, "valid"));
+                } else {
+                    for error in errors {
+                        findings.push(Finding::new(SECTION, Level::Error, subject.clone(), error));
+                    }
+                }
+            }
+            Err(e) => findings.push(Finding::new(SECTION, Level::Error, subject, e)),
+        }
+    }
+
+    match config::load(base) {
+        Ok(layered) => {
+            let config = layered.config;
+            if config.stacks.contains_key(&config.default_stack) {
+                findings.push(Finding::new(
+                    SECTION,
+                    Level::Ok,
+                    "default_stack",
+                    format!("{} ({})", config.default_stack, layered.origins["default_stack"]),
+                ));
+            } else {
+                let names: Vec<&str> = config.stacks.keys().map(String::as_str).collect();
+                let hint = config::suggest(&config.default_stack, &names)
+                    .map(|s| format!(" — did you mean '{}'?", s))
+                    .unwrap_or_default();
+                findings.push(Finding::new(
+                    SECTION,
+                    Level::Error,
+                    "default_stack",
+                    format!(
+                        "'{}' is not a configured stack ({}){}",
+                        config.default_stack, layered.origins["default_stack"], hint
+                    ),
+                ));
+            }
+        }
+        Err(e) => findings.push(Finding::new(SECTION, Level::Error, "effective config", e)),
+    }
+}
+
+fn check_tools(findings: &mut Vec<Finding>) {
+    for (tool, flag, required) in TOOLS {
+        match tool_version(tool, flag) {
+            Some(version) => findings.push(Finding::new("Tools", Level::Ok, *tool, version)),
+            None => {
+                let level = if *required { Level::Error } else { Level::Warn };
+                let message = if *required {
+                    "not found on PATH"
+                } else {
+                    "not found on PATH (optional)"
+                };
+                findings.push(Finding::new("Tools", level, *tool, message));
+            }
+        }
+    }
+}
+
+/// First line of `<tool> <flag>`, or None if the tool can't be run
+fn tool_version(tool: &str, flag: &str) -> Option<String> {
+    let output = Command::new(tool).arg(flag).output().ok()?;
+    if !output.status.success() {
+        return Some("installed (version unknown)".into());
+    }
+    let text = if output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stderr).to_string()
+    } else {
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let line = text.lines().next().unwrap_or("").trim().to_string();
+    Some(if line.is_empty() { "installed".into() } else { line })
+}
+
+fn check_role_specs(pfm_dir: &Path, findings: &mut Vec<Finding>) {
+    for (filename, _) in templates::role_specs() {
+        let path = pfm_dir.join("roles").join(filename);
+        if path.exists() {
+            findings.push(Finding::new("Role specs", Level::Ok, filename, "present"));
+        } else {
+            findings.push(Finding::new(
+                "Role specs",
+                Level::Error,
+                filename,
+                "missing — run `pfm init` to restore",
+            ));
+        }
+    }
+}
+
+/// Custom gates in the configured pipeline need a role spec of the same name, unless they are approval gates
+fn check_custom_roles(base: &Path, findings: &mut Vec<Finding>) {
+    let Ok(layered) = config::load(base) else {
+        return; // reported under Config
+    };
+    let agent_gates = layered.config.pipeline.iter().filter(|g| !layered.config.approval_gates.contains(g));
+    for role in agent_gates.filter_map(|g| crate::state::gate_to_role(g)) {
+        if role.is_builtin() {
+            continue;
+        }
+        let subject = format!("{}.md", role);
+        match crate::commands::agent::require_role_spec(base, &role) {
+            Ok(()) => findings.push(Finding::new("Role specs", Level::Ok, subject, "present (custom)")),
+            Err(e) => findings.push(Finding::new("Role specs", Level::Error, subject, e)),
+        }
+    }
+}
+
+/// Role spec front matter must parse and name the gate its role owns
+fn check_role_contracts(base: &Path, findings: &mut Vec<Finding>) {
+    let mut roles: Vec<crate::state::Role> = crate::state::GATE_ORDER
+        .iter()
+        .filter_map(|g| crate::state::gate_to_role(g))
+        .collect();
+    if let Ok(layered) = config::load(base) {
+        let agent_gates = layered.config.pipeline.iter().filter(|g| !layered.config.approval_gates.contains(g));
+        for role in agent_gates.filter_map(|g| crate::state::gate_to_role(g)) {
+            if !roles.contains(&role) {
+                roles.push(role);
+            }
+        }
+    }
+    for role in roles {
+        if let Err(e) = crate::role_spec::load(base, &role) {
+            findings.push(Finding::new("Role specs", Level::Error, format!("{}.md", role), e));
+        }
+    }
+}
+
+fn check_templates(pfm_dir: &Path, findings: &mut Vec<Finding>) {
+    for (filename, _) in templates::work_templates() {
+        let path = pfm_dir.join("templates").join(filename);
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => {
+                findings.push(Finding::new(
+                    "Templates",
+                    Level::Error,
+                    filename,
+                    "missing — run `pfm init` to restore",
+                ));
+                continue;
+            }
+        };
+        let missing: Vec<&str> = templates::template_placeholders(filename)
+            .iter()
+            .filter(|p| !content.contains(*p))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            findings.push(Finding::new("Templates", Level::Ok, filename, "present"));
+        } else {
+            findings.push(Finding::new(
+                "Templates",
+                Level::Warn,
+                filename,
+                format!("missing placeholder(s): {}", missing.join(", ")),
+            ));
+        }
+    }
+}
+
+fn check_prompts(base: &Path, findings: &mut Vec<Finding>) {
+    let sample_dir = base.join(".pfm/work/SAMPLE");
+    let mut context = crate::prompt::context(base, &sample_dir, Some(&crate::state::Role::Prd));
+    context["roles"] = serde_json::json!([]);
+
+    for (filename, _) in templates::prompt_templates() {
+        let edited = match crate::prompt::edited_template(base, filename) {
+            Ok(Some(edited)) => edited,
+            Ok(None) => {
+                findings.push(Finding::new("Prompts", Level::Ok, filename, "built-in"));
+                continue;
+            }
+            Err(e) => {
+                findings.push(Finding::new("Prompts", Level::Error, filename, e));
+                continue;
+            }
+        };
+        match crate::prompt::render(&edited, &context) {
+            Ok(_) => findings.push(Finding::new("Prompts", Level::Ok, filename, "renders")),
+            Err(e) => findings.push(Finding::new("Prompts", Level::Error, filename, e)),
+        }
+    }
+}
+
+fn check_work_items(pfm_dir: &Path, findings: &mut Vec<Finding>) {
+    let mut entries: Vec<_> = match fs::read_dir(pfm_dir.join("work")) {
+        Ok(entries) => entries.flatten().filter(|e| e.path().is_dir()).collect(),
+        Err(_) => return,
+    };
+    entries.sort_by_key(|e| e.file_name());
+    let base = pfm_dir.parent().unwrap_or(pfm_dir);
+    let key = crate::approval::read_key();
+
+    for entry in entries {
+        let id = entry.file_name().to_string_lossy().to_string();
+        let state_path = entry.path().join("state.json");
+        if !state_path.exists() {
+            findings.push(Finding::new("Work items", Level::Error, id, "state.json missing"));
+            continue;
+        }
+        match read_state(&state_path) {
+            Ok(state) if state.id != id => findings.push(Finding::new(
+                "Work items",
+                Level::Warn,
+                id,
+                format!("state.json id is '{}'", state.id),
+            )),
+            Ok(mut state) => {
+                let approval_gates = crate::approval::required(base, &state);
+                let missing = crate::approval::missing(&state, &approval_gates);
+                let unverified = crate::approval::unverified(&entry.path(), &state, &approval_gates, key.as_deref());
+                let tampered = crate::approval::apply(&entry.path(), &mut state, &approval_gates);
+                if tampered.is_empty() && missing.is_empty() && unverified.is_empty() {
+                    findings.push(Finding::new("Work items", Level::Ok, id.clone(), "state.json valid"));
+                }
+                if !missing.is_empty() {
+                    findings.push(Finding::new(
+                        "Work items",
+                        Level::Error,
+                        id.clone(),
+                        format!("approval gate(s) {} missing from the pipeline in state.json", missing.join(", ")),
+                    ));
+                }
+                for (gate, record) in unverified {
+                    findings.push(Finding::new(
+                        "Work items",
+                        Level::Warn,
+                        id.clone(),
+                        format!(
+                            "approval gate '{}' ({} by {}) can't be verified with this machine's key",
+                            gate, record.decision, record.approver
+                        ),
+                    ));
+                }
+                for (gate, claimed) in tampered {
+                    let actual = state.gates.get(&gate).map(|s| s.to_string()).unwrap_or_default();
+                    findings.push(Finding::new(
+                        "Work items",
+                        Level::Warn,
+                        id.clone(),
+                        format!("approval gate '{}' is {} in state.json but {} by its sign-off record", gate, claimed, actual),
+                    ));
+                }
+            }
+            Err(e) => findings.push(Finding::new("Work items", Level::Error, id, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    fn errors_for<'a>(findings: &'a [Finding], section: &str) -> Vec<&'a Finding> {
+        findings
+            .iter()
+            .filter(|f| f.section == section && f.level == Level::Error)
+            .collect()
+    }
+
+    #[test]
+    fn test_doctor_clean_repo() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        let findings = diagnose(dir.path());
+        for section in ["Config", "Role specs", "Templates", "Work items"] {
+            assert!(errors_for(&findings, section).is_empty(), "{} has errors", section);
+        }
+    }
+
+    #[test]
+    fn test_doctor_flags_custom_role_without_spec() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::write(
+            dir.path().join(".pfm/config.local.json"),
+            r#"{"pipeline": ["prd", "plan", "impl", "docs", "perf", "git"]}"#,
+        ).unwrap();
+        fs::write(dir.path().join(".pfm/roles/docs.md"), "# Role: Docs").unwrap();
+
+        let findings = diagnose(dir.path());
+        let errors = errors_for(&findings, "Role specs");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].subject, "perf.md");
+    }
+
+    #[test]
+    fn test_doctor_flags_bad_front_matter() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::write(dir.path().join(".pfm/roles/qa.md"), "---\ngate: qa\nstatuses: [shipped]\n---\n# QA\n").unwrap();
+
+        let findings = diagnose(dir.path());
+        let errors = errors_for(&findings, "Role specs");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("unknown status 'shipped'"));
+    }
+
+    #[test]
+    fn test_doctor_flags_default_stack_typo() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        let path = dir.path().join(".pfm/config.json");
+        let content = fs::read_to_string(&path).unwrap().replace(
+            "\"default_stack\": \"rails\"",
+            "\"default_stack\": \"rials\"",
+        );
+        fs::write(&path, content).unwrap();
+
+        let findings = diagnose(dir.path());
+        let errors = errors_for(&findings, "Config");
+        assert!(errors.iter().any(|f| f.message.contains("did you mean 'rails'")));
+    }
+
+    #[test]
+    fn test_doctor_flags_missing_role_spec_and_placeholder() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::remove_file(dir.path().join(".pfm/roles/qa.md")).unwrap();
+        fs::write(dir.path().join(".pfm/templates/prd.md"), "# PRD\n").unwrap();
+
+        let findings = diagnose(dir.path());
+        assert!(errors_for(&findings, "Role specs").iter().any(|f| f.subject == "qa.md"));
+        assert!(findings.iter().any(|f| f.section == "Templates"
+            && f.subject == "prd.md"
+            && f.message.contains("{WORK_ID}")));
+    }
+
+    #[test]
+    fn test_doctor_flags_broken_prompt_template() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::write(dir.path().join(".pfm/prompts/nudge.md"), "{{#if gate}}never closed").unwrap();
+
+        let findings = diagnose(dir.path());
+        let errors = errors_for(&findings, "Prompts");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].subject, "nudge.md");
+    }
+
+    #[test]
+    fn test_doctor_flags_invalid_state() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Broken", Some("FEAT-BAD"), None).unwrap();
+        fs::write(dir.path().join(".pfm/work/FEAT-BAD/state.json"), "{ not json").unwrap();
+
+        let findings = diagnose(dir.path());
+        let errors = errors_for(&findings, "Work items");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("failed to parse"));
+    }
+}
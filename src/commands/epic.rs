@@ -0,0 +1,329 @@
+use crate::runlog;
+use crate::state::{read_state, write_state, Transition, WorkState, WorkStatus};
+use chrono::Utc;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Epics stop after planning: the orchestrator's task list becomes the child work items
+pub const EPIC_PIPELINE: &[&str] = &["prd", "plan"];
+
+/// Turn a freshly created work item into an epic
+pub fn make_epic(base: &Path, work_id: &str) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    state.epic = true;
+    state.set_pipeline(EPIC_PIPELINE.iter().map(|g| g.to_string()).collect());
+    state.approval_gates.retain(|g| EPIC_PIPELINE.contains(&g.as_str()));
+    state.touch();
+    write_state(&state_path, &state)?;
+
+    let tasks_path = work_dir.join("tasks.md");
+    if let Ok(content) = fs::read_to_string(&tasks_path) {
+        let content = content.replacen(
+            "## Tasks\n",
+            "## Tasks\n\n<!-- Epic: one implementable slice per task. `pfm work split` turns each task into a child work item. -->\n",
+            1,
+        );
+        fs::write(&tasks_path, content).map_err(|e| format!("failed to write tasks.md: {}", e))?;
+    }
+    println!("  epic: pipeline {}", EPIC_PIPELINE.join(" → "));
+    Ok(())
+}
+
+/// The child a task line was split into: split writes the child's ID in front of the
+/// task text (`- [ ] EPIC-x-1: Invoice model`), like acceptance criteria carry `AC-1`
+fn linked_child<'a>(text: &str, children: &'a [String]) -> Option<&'a str> {
+    children
+        .iter()
+        .find(|id| text.strip_prefix(id.as_str()).is_some_and(|rest| rest.starts_with(':')))
+        .map(String::as_str)
+}
+
+/// Create a child work item for each task in the epic's `tasks.md` that doesn't have one yet,
+/// and write the child's ID into the task line. Tasks are matched to children by that ID, so
+/// either can be renamed. Children inherit the epic's commands and components. Returns the
+/// new child IDs.
+pub fn split(base: &Path, epic_id: &str) -> Result<Vec<String>, String> {
+    let epic_dir = base.join(".pfm/work").join(epic_id);
+    let epic_path = epic_dir.join("state.json");
+    if !epic_path.exists() {
+        return Err(format!("work item {} not found", epic_id));
+    }
+    let mut epic = read_state(&epic_path)?;
+    if !epic.epic {
+        return Err(format!("{} is not an epic (create one with `pfm work new --epic`)", epic_id));
+    }
+
+    let content = fs::read_to_string(epic_dir.join("tasks.md"))
+        .map_err(|e| format!("failed to read tasks.md: {}", e))?;
+    if let Some(fill) = crate::lint::check_artifact(base, &epic_dir, "tasks.md", &content) {
+        if !fill.is_filled() {
+            return Err(format!("{}/tasks.md {}", epic_id, fill.describe()));
+        }
+    }
+    let tasks = crate::tasks::parse(&content);
+    if tasks.is_empty() {
+        return Err(format!("{}/tasks.md lists no tasks to split", epic_id));
+    }
+
+    let task_lines: Vec<(usize, &str, &str)> = crate::acceptance::section_lines(&content, "## Tasks")
+        .into_iter()
+        .filter_map(|(index, line)| {
+            crate::acceptance::checklist_item(line).map(|(prefix, text)| (index, prefix, text))
+        })
+        .collect();
+    let mut linked: Vec<String> = task_lines
+        .iter()
+        .filter_map(|(_, _, text)| linked_child(text, &epic.children).map(str::to_string))
+        .collect();
+    let existing = children(base, &epic);
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut created = Vec::new();
+    for &(index, prefix, text) in &task_lines {
+        if linked_child(text, &epic.children).is_some() {
+            continue;
+        }
+        // Tasks split before child IDs were written into tasks.md are matched once by title
+        let adopted = existing.iter().find(|c| c.title == text && !linked.contains(&c.id));
+        if let Some(child) = adopted {
+            linked.push(child.id.clone());
+            lines[index] = format!("{} {}: {}", prefix, child.id, text);
+            continue;
+        }
+
+        let child_id = next_child_id(base, epic_id);
+        crate::commands::work::new_work(base, text, Some(&child_id), None)?;
+
+        let child_dir = base.join(".pfm/work").join(&child_id);
+        let child_path = child_dir.join("state.json");
+        let mut child = read_state(&child_path)?;
+        child.parent = Some(epic_id.to_string());
+        child.commands = epic.commands.clone();
+        child.components = epic.components.clone();
+        write_state(&child_path, &child)?;
+        append(
+            &child_dir.join("prd.md"),
+            &format!(
+                "\n## Parent Epic\n\nSlice of {} ({}). Read .pfm/work/{}/prd.md and plan.md for the wider context.\n",
+                epic_id, epic.title, epic_id
+            ),
+        )?;
+
+        lines[index] = format!("{} {}: {}", prefix, child_id, text);
+        epic.children.push(child_id.clone());
+        created.push(child_id);
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    if updated != content {
+        fs::write(epic_dir.join("tasks.md"), updated).map_err(|e| format!("failed to write tasks.md: {}", e))?;
+    }
+
+    if !created.is_empty() {
+        epic.touch();
+        write_state(&epic_path, &epic)?;
+        runlog::append(
+            &epic_dir,
+            &format!(
+                "\n## Split: {} — {} child item(s)\n\n{}\n",
+                Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+                created.len(),
+                created.iter().map(|id| format!("- {}", id)).collect::<Vec<_>>().join("\n")
+            ),
+        )?;
+    }
+    rollup(base, epic_id)?;
+    Ok(created)
+}
+
+/// `<EPIC>-<n>` with the lowest n not taken by an active or archived item
+/// A long epic ID is shortened so its children still fit the ID length limit
+fn next_child_id(base: &Path, epic_id: &str) -> String {
+    (1..)
+        .map(|n| crate::ids::with_suffix(epic_id, &format!("-{}", n)))
+        .find(|id| crate::commands::lifecycle::locate(base, id).is_none())
+        .expect("some child ID is free")
+}
+
+/// The status an epic rolls up to: done once every child is done, blocked if any child
+/// is blocked, in progress otherwise. None without children.
+pub fn rolled_up(children: &[WorkState]) -> Option<WorkStatus> {
+    if children.is_empty() {
+        None
+    } else if children.iter().all(|c| c.status == WorkStatus::Done) {
+        Some(WorkStatus::Done)
+    } else if children.iter().any(|c| c.status == WorkStatus::Blocked) {
+        Some(WorkStatus::Blocked)
+    } else {
+        Some(WorkStatus::InProgress)
+    }
+}
+
+/// The child states of an epic that still exist
+pub fn children(base: &Path, epic: &WorkState) -> Vec<WorkState> {
+    epic.children
+        .iter()
+        .filter_map(|id| crate::commands::lifecycle::locate(base, id))
+        .filter_map(|(dir, _)| read_state(&dir.join("state.json")).ok())
+        .collect()
+}
+
+/// Update an epic's status from its children, recording the change in its history.
+/// Rolls up through parent epics too.
+pub fn rollup(base: &Path, epic_id: &str) -> Result<(), String> {
+    let Some((dir, _)) = crate::commands::lifecycle::locate(base, epic_id) else {
+        return Ok(());
+    };
+    let state_path = dir.join("state.json");
+    let mut epic = read_state(&state_path)?;
+    let kids = children(base, &epic);
+    let Some(status) = rolled_up(&kids) else {
+        return Ok(());
+    };
+    if status != epic.status {
+        let done = kids.iter().filter(|c| c.status == WorkStatus::Done).count();
+        epic.history.push(Transition {
+            at: Utc::now().to_rfc3339(),
+            action: "rollup".into(),
+            from: epic.status.clone(),
+            to: status.clone(),
+            reason: format!("{}/{} children done", done, kids.len()),
+        });
+        println!("  epic {} rolled up: {} → {}", epic_id, epic.status, status);
+        epic.status = status;
+        epic.touch();
+        write_state(&state_path, &epic)?;
+    }
+    match epic.parent {
+        Some(parent) if parent != epic_id => rollup(base, &parent),
+        _ => Ok(()),
+    }
+}
+
+fn append(path: &Path, text: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    file.write_all(text.as_bytes())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, lifecycle, work};
+    use tempfile::tempdir;
+
+    fn setup_epic(dir: &Path) {
+        init::run(dir).unwrap();
+        work::new_work(dir, "Billing", Some("EPIC-bill"), Some("rust")).unwrap();
+        make_epic(dir, "EPIC-bill").unwrap();
+        let work_dir = dir.join(".pfm/work/EPIC-bill");
+        let mut state = read_state(&work_dir.join("state.json")).unwrap();
+        state.commands.verify = "make check".into();
+        write_state(&work_dir.join("state.json"), &state).unwrap();
+        fs::write(work_dir.join("tasks.md"), "# Tasks\n\n## Tasks\n\n- [ ] Invoice model\n- [ ] Stripe webhook\n").unwrap();
+    }
+
+    #[test]
+    fn test_split_creates_children_once() {
+        let dir = tempdir().unwrap();
+        setup_epic(dir.path());
+
+        let epic = read_state(&dir.path().join(".pfm/work/EPIC-bill/state.json")).unwrap();
+        assert!(epic.epic);
+        assert_eq!(epic.pipeline, vec!["prd", "plan"]);
+
+        assert_eq!(split(dir.path(), "EPIC-bill").unwrap(), vec!["EPIC-bill-1", "EPIC-bill-2"]);
+        let child = read_state(&dir.path().join(".pfm/work/EPIC-bill-2/state.json")).unwrap();
+        assert_eq!(child.title, "Stripe webhook");
+        assert_eq!(child.parent.as_deref(), Some("EPIC-bill"));
+        assert_eq!(child.commands.verify, "make check");
+        assert_eq!(child.pipeline.len(), 8);
+        let prd = fs::read_to_string(dir.path().join(".pfm/work/EPIC-bill-1/prd.md")).unwrap();
+        assert!(prd.contains("## Parent Epic\n\nSlice of EPIC-bill (Billing)"));
+
+        // Only new tasks get children on a second split
+        let tasks = dir.path().join(".pfm/work/EPIC-bill/tasks.md");
+        let content = fs::read_to_string(&tasks).unwrap() + "- [ ] Dunning emails\n";
+        fs::write(&tasks, content).unwrap();
+        assert_eq!(split(dir.path(), "EPIC-bill").unwrap(), vec!["EPIC-bill-3"]);
+        assert!(split(dir.path(), "EPIC-bill").unwrap().is_empty());
+        assert!(fs::read_to_string(&tasks).unwrap().contains("- [ ] EPIC-bill-3: Dunning emails\n"));
+
+        let view = crate::commands::status::view(dir.path(), "EPIC-bill").unwrap();
+        let ids: Vec<&str> = view.children.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["EPIC-bill-1", "EPIC-bill-2", "EPIC-bill-3"]);
+        assert_eq!(view.children[0].gates_total, 8);
+    }
+
+    #[test]
+    fn test_split_matches_tasks_by_child_id() {
+        let dir = tempdir().unwrap();
+        setup_epic(dir.path());
+        split(dir.path(), "EPIC-bill").unwrap();
+
+        // Rewording a task or renaming its child doesn't make a new child
+        let tasks = dir.path().join(".pfm/work/EPIC-bill/tasks.md");
+        let content = fs::read_to_string(&tasks).unwrap();
+        fs::write(&tasks, content.replace("EPIC-bill-1: Invoice model", "EPIC-bill-1: Invoice and credit note models")).unwrap();
+        let child_path = dir.path().join(".pfm/work/EPIC-bill-2/state.json");
+        let mut child = read_state(&child_path).unwrap();
+        child.title = "Stripe webhooks and retries".into();
+        write_state(&child_path, &child).unwrap();
+        assert!(split(dir.path(), "EPIC-bill").unwrap().is_empty());
+
+        // Epics split before IDs were written into tasks.md are linked by title, once
+        fs::write(&tasks, "# Tasks\n\n## Tasks\n\n- [ ] Stripe webhooks and retries\n- [x] EPIC-bill-1: Invoice model\n").unwrap();
+        assert!(split(dir.path(), "EPIC-bill").unwrap().is_empty());
+        let content = fs::read_to_string(&tasks).unwrap();
+        assert!(content.contains("- [ ] EPIC-bill-2: Stripe webhooks and retries\n"), "{}", content);
+        assert!(content.contains("- [x] EPIC-bill-1: Invoice model\n"));
+    }
+
+    #[test]
+    fn test_child_ids_fit_the_length_limit() {
+        let dir = tempdir().unwrap();
+        let epic_id = format!("EPIC-{}", "x".repeat(59));
+        let child = next_child_id(dir.path(), &epic_id);
+        assert_eq!(child, format!("EPIC-{}-1", "x".repeat(57)));
+        assert!(crate::ids::validate(&child).is_ok());
+    }
+
+    #[test]
+    fn test_split_needs_epic_with_written_tasks() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Plain", Some("FEAT-p"), Some("rust")).unwrap();
+        assert!(split(dir.path(), "FEAT-p").unwrap_err().contains("not an epic"));
+        make_epic(dir.path(), "FEAT-p").unwrap();
+        assert!(split(dir.path(), "FEAT-p").unwrap_err().contains("is still the template"));
+    }
+
+    #[test]
+    fn test_status_rolls_up_from_children() {
+        let dir = tempdir().unwrap();
+        setup_epic(dir.path());
+        split(dir.path(), "EPIC-bill").unwrap();
+        let epic_status = || read_state(&dir.path().join(".pfm/work/EPIC-bill/state.json")).unwrap().status;
+
+        lifecycle::run(dir.path(), "EPIC-bill-1", lifecycle::Action::Block, "waiting", false).unwrap();
+        assert_eq!(epic_status(), WorkStatus::Blocked);
+        lifecycle::run(dir.path(), "EPIC-bill-1", lifecycle::Action::Close, "done", false).unwrap();
+        assert_eq!(epic_status(), WorkStatus::InProgress);
+        lifecycle::run(dir.path(), "EPIC-bill-2", lifecycle::Action::Close, "done", false).unwrap();
+        assert_eq!(epic_status(), WorkStatus::Done);
+
+        let epic = read_state(&dir.path().join(".pfm/work/EPIC-bill/state.json")).unwrap();
+        let last = epic.history.last().unwrap();
+        assert_eq!((last.action.as_str(), last.reason.as_str()), ("rollup", "2/2 children done"));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::runlog;
+use crate::state::{gate_to_role, read_state, write_state, GateStatus, Transition, WorkState, WorkStatus};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+
+/// Load a work item for a gate override, checking the gate is in its pipeline
+fn load(base: &Path, work_id: &str, gate: &str, reason: &str, command: &str) -> Result<(PathBuf, WorkState), String> {
+    if reason.trim().is_empty() {
+        return Err(format!("`pfm gate {}` needs a --reason", command));
+    }
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let state = read_state(&work_dir.join("state.json"))?;
+    if !state.pipeline.iter().any(|g| g == gate) || state.gates.get(gate).is_none() {
+        return Err(format!(
+            "{} has no gate '{}' (pipeline: {})",
+            work_id,
+            gate,
+            state.pipeline.join(", ")
+        ));
+    }
+    Ok((work_dir, state))
+}
+
+/// Keep the item's status in line with its gates: done once every gate is satisfied, back in
+/// progress when a gate reopens on a done item. Blocked items stay blocked. Returns the change.
+fn sync_status(state: &mut WorkState, action: &str, reason: &str) -> Option<(WorkStatus, WorkStatus)> {
+    let to = match (&state.status, state.next_pending_gate()) {
+        (WorkStatus::InProgress, None) => WorkStatus::Done,
+        (WorkStatus::Done, Some(_)) => WorkStatus::InProgress,
+        _ => return None,
+    };
+    let from = std::mem::replace(&mut state.status, to.clone());
+    state.history.push(Transition {
+        at: Utc::now().to_rfc3339(),
+        action: action.to_string(),
+        from: from.clone(),
+        to: to.clone(),
+        reason: reason.trim().to_string(),
+    });
+    Some((from, to))
+}
+
+/// Report a status change made by a gate command and let dependents and the epic know
+fn after_status_change(
+    base: &Path,
+    state: &WorkState,
+    change: &Option<(WorkStatus, WorkStatus)>,
+    reason: &str,
+) -> Result<(), String> {
+    let Some((from, to)) = change else { return Ok(()) };
+    println!("{}: {} → {}", state.id, from, to);
+    if *to == WorkStatus::InProgress {
+        for id in crate::deps::flag_downstream(base, &state.id, reason.trim())? {
+            println!("  flagged {} (depends on {})", id, state.id);
+        }
+    }
+    if let Some(parent) = &state.parent {
+        crate::commands::epic::rollup(base, parent)?;
+    }
+    Ok(())
+}
+
+fn status_line(change: &Option<(WorkStatus, WorkStatus)>) -> String {
+    change.as_ref().map(|(from, to)| format!("\nStatus: {} → {}\n", from, to)).unwrap_or_default()
+}
+
+/// Set a gate's status by hand, recording the change and its reason in the runlog
+pub fn set(base: &Path, work_id: &str, gate: &str, status: GateStatus, reason: &str) -> Result<(), String> {
+    let command = if status == GateStatus::Skipped { "skip" } else { "set" };
+    let (work_dir, mut state) = load(base, work_id, gate, reason, command)?;
+    if crate::approval::required(base, &state).iter().any(|g| g == gate) {
+        return Err(format!(
+            "{} is an approval gate — resolve it with `pfm approve` or `pfm reject`",
+            gate
+        ));
+    }
+    let from = state.gates.get(gate).cloned().expect("gate checked by load");
+    if from == status {
+        return Err(format!("{} gate '{}' is already {}", work_id, gate, status));
+    }
+
+    state.gates.set(gate, status.clone());
+    // A gate going back to todo will run again, so its recorded commits no longer apply
+    if status == GateStatus::Todo {
+        state.gate_refs.remove(gate);
+    }
+    let change = sync_status(&mut state, &format!("gate {}", command), reason);
+    state.touch();
+    write_state(&work_dir.join("state.json"), &state)?;
+    runlog::append(
+        &work_dir,
+        &format!(
+            "\n## Gate Override: {} — {} ({} → {})\n\nReason: {}\n{}",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            gate,
+            from,
+            status,
+            reason.trim(),
+            status_line(&change)
+        ),
+    )?;
+    println!("{} gate {}: {} → {}", work_id, gate, from, status);
+    after_status_change(base, &state, &change, reason)
+}
+
+/// Mark a gate skipped. The pipeline treats it as satisfied without running its role.
+pub fn skip(base: &Path, work_id: &str, gate: &str, reason: &str) -> Result<(), String> {
+    set(base, work_id, gate, GateStatus::Skipped, reason)
+}
+
+/// Put `from_gate` and every gate after it back to todo, handing the item to `from_gate`'s role.
+/// Approval gates among them lose their sign-off. Returns the gates that changed.
+pub fn reset(base: &Path, work_id: &str, from_gate: &str, reason: &str) -> Result<Vec<String>, String> {
+    let (work_dir, mut state) = load(base, work_id, from_gate, reason, "reset")?;
+    let start = state.gate_index(from_gate).expect("gate checked by load");
+
+    let mut changed = Vec::new();
+    for gate in &state.pipeline[start..] {
+        let cleared = crate::approval::clear(&work_dir, gate);
+        if let Some(status) = state.gates.get(gate).filter(|s| **s != GateStatus::Todo || cleared) {
+            changed.push(format!("{} ({})", gate, status));
+            state.gates.set(gate, GateStatus::Todo);
+        }
+        state.gate_refs.remove(gate);
+    }
+    if changed.is_empty() {
+        return Err(format!("{} gates from '{}' on are already todo", work_id, from_gate));
+    }
+    if let Some(role) = gate_to_role(from_gate) {
+        state.owner = role;
+    }
+    let change = sync_status(&mut state, "gate reset", reason);
+    state.touch();
+    write_state(&work_dir.join("state.json"), &state)?;
+    runlog::append(
+        &work_dir,
+        &format!(
+            "\n## Gate Reset: {} — from {}\n\nReset to todo: {}\n\nReason: {}\n{}",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            from_gate,
+            changed.join(", "),
+            reason.trim(),
+            status_line(&change)
+        ),
+    )?;
+    println!("{}: reset {} to todo", work_id, changed.join(", "));
+    after_status_change(base, &state, &change, reason)?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use crate::state::Role;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Gates", Some("FEAT-GT"), Some("rust")).unwrap();
+        dir
+    }
+
+    fn state(dir: &Path) -> WorkState {
+        read_state(&dir.join(".pfm/work/FEAT-GT/state.json")).unwrap()
+    }
+
+    #[test]
+    fn test_skip_satisfies_gate_and_is_logged() {
+        let dir = setup();
+        set(dir.path(), "FEAT-GT", "prd", GateStatus::Pass, "written by hand").unwrap();
+        skip(dir.path(), "FEAT-GT", "plan", "one-line change").unwrap();
+        assert_eq!(state(dir.path()).next_pending_gate(), Some("env"));
+
+        let runlog = fs::read_to_string(dir.path().join(".pfm/work/FEAT-GT/runlog.md")).unwrap();
+        assert!(runlog.contains("## Gate Override:"));
+        assert!(runlog.contains("plan (todo → skipped)\n\nReason: one-line change"));
+    }
+
+    #[test]
+    fn test_set_validates_gate_and_reason() {
+        let dir = setup();
+        let err = set(dir.path(), "FEAT-GT", "docs", GateStatus::Pass, "x").unwrap_err();
+        assert!(err.contains("no gate 'docs'"), "{}", err);
+        assert!(skip(dir.path(), "FEAT-GT", "plan", " ").unwrap_err().contains("needs a --reason"));
+        assert!(set(dir.path(), "FEAT-GT", "prd", GateStatus::Todo, "x").unwrap_err().contains("already todo"));
+        assert!("passed".parse::<GateStatus>().unwrap_err().contains("use todo, in_progress"));
+    }
+
+    #[test]
+    fn test_reset_from_gate() {
+        let dir = setup();
+        for gate in ["prd", "plan", "env", "tests"] {
+            set(dir.path(), "FEAT-GT", gate, GateStatus::Pass, "done").unwrap();
+        }
+        assert_eq!(reset(dir.path(), "FEAT-GT", "env", "env drifted").unwrap(), vec!["env (pass)", "tests (pass)"]);
+        let state = state(dir.path());
+        assert_eq!(state.gates.plan, GateStatus::Pass);
+        assert_eq!(state.gates.tests, GateStatus::Todo);
+        assert_eq!(state.owner, Role::Env);
+        assert!(reset(dir.path(), "FEAT-GT", "env", "again").unwrap_err().contains("already todo"));
+    }
+
+    #[test]
+    fn test_status_follows_the_gates() {
+        let dir = setup();
+        let pipeline = state(dir.path()).pipeline;
+        for gate in &pipeline[..pipeline.len() - 1] {
+            set(dir.path(), "FEAT-GT", gate, GateStatus::Pass, "done").unwrap();
+        }
+        assert_eq!(state(dir.path()).status, WorkStatus::InProgress);
+        set(dir.path(), "FEAT-GT", "git", GateStatus::Pass, "merged by hand").unwrap();
+        let done = state(dir.path());
+        assert_eq!(done.status, WorkStatus::Done);
+        let last = done.history.last().unwrap();
+        assert_eq!((last.action.as_str(), last.reason.as_str()), ("gate set", "merged by hand"));
+
+        reset(dir.path(), "FEAT-GT", "qa", "regression").unwrap();
+        let reopened = state(dir.path());
+        assert_eq!(reopened.status, WorkStatus::InProgress);
+        assert_eq!(reopened.history.last().unwrap().action, "gate reset");
+        let runlog = fs::read_to_string(dir.path().join(".pfm/work/FEAT-GT/runlog.md")).unwrap();
+        assert!(runlog.contains("Status: done → in_progress"));
+
+        // Blocked is a decision made by a person, not something the gates undo
+        crate::commands::lifecycle::run(dir.path(), "FEAT-GT", crate::commands::lifecycle::Action::Block, "x", false).unwrap();
+        skip(dir.path(), "FEAT-GT", "qa", "covered elsewhere").unwrap();
+        set(dir.path(), "FEAT-GT", "git", GateStatus::Pass, "merged").unwrap();
+        assert_eq!(state(dir.path()).status, WorkStatus::Blocked);
+    }
+}
@@ -0,0 +1,568 @@
+use crate::adapters::git;
+use crate::runlog;
+use crate::state::{write_state, GateStatus, WorkStatus};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for the built-in git gate executor
+#[derive(Debug, Clone)]
+pub struct GitGateOptions {
+    /// Squash all commits since the merge-base with `base_ref` into one
+    pub squash: bool,
+    /// Branch the work was started from (used for squashing)
+    pub base_ref: String,
+    /// Skip pushing even if a remote is configured
+    pub no_push: bool,
+}
+
+impl Default for GitGateOptions {
+    fn default() -> Self {
+        GitGateOptions {
+            squash: false,
+            base_ref: "main".into(),
+            no_push: false,
+        }
+    }
+}
+
+impl GitGateOptions {
+    /// The repo's `git` config, with command-line flags on top
+    pub fn resolve(base: &Path, squash: bool, base_ref: Option<String>, no_push: bool) -> Result<Self, String> {
+        let git = crate::config::load(base)?.config.git;
+        Ok(GitGateOptions {
+            squash: squash || git.squash,
+            base_ref: base_ref.unwrap_or(git.base),
+            no_push: no_push || !git.push,
+        })
+    }
+}
+
+/// Execute the `git` gate without an agent: commit, write the PR bundle, push if possible
+pub fn run(base: &Path, work_id: &str, opts: &GitGateOptions) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+
+    let state_path = work_dir.join("state.json");
+    let state = crate::approval::enforce(base, &work_dir)?;
+
+    // Every gate before `git` must have passed or been skipped
+    let pending: Vec<String> = state
+        .pipeline
+        .iter()
+        .take_while(|g| *g != "git")
+        .filter_map(|g| match state.gates.get(g) {
+            Some(status) if status.is_satisfied() => None,
+            Some(status) => Some(format!("{}={}", g, status)),
+            None => None,
+        })
+        .collect();
+    if !pending.is_empty() {
+        return Err(format!(
+            "cannot run git gate — prior gates not passed: {}",
+            pending.join(", ")
+        ));
+    }
+
+    let cwd = if !state.workspace.worktree.is_empty() {
+        PathBuf::from(&state.workspace.worktree)
+    } else {
+        base.to_path_buf()
+    };
+
+    // Only tracked changes and the work item's own files are committed. Anything else
+    // new in the tree may be scratch output or local config, so a person decides.
+    let untracked = untracked_outside_pfm(&cwd)?;
+    if !untracked.is_empty() {
+        return Err(format!(
+            "cannot run git gate — untracked files outside .pfm:\n  {}\n`git add` the ones that belong to {}, and delete or ignore the rest",
+            untracked.join("\n  "),
+            work_id
+        ));
+    }
+
+    runlog::append(
+        &work_dir,
+        &format!(
+            "\n## Git Gate Start: {}\n\nBranch: {}\nSquash: {}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            state.branch,
+            opts.squash,
+        ),
+    )?;
+
+    let prd = fs::read_to_string(work_dir.join("prd.md")).unwrap_or_default();
+    let acceptance = fs::read_to_string(work_dir.join("acceptance.md")).unwrap_or_default();
+    let handoffs = list_handoffs(&work_dir.join("handoffs"));
+
+    let message = commit_message(&state.id, &state.title, &prd, &handoffs);
+    let pr = pr_description(&state.id, &state.title, &state.branch, &prd, &acceptance, &handoffs);
+
+    let artifacts_dir = work_dir.join("artifacts");
+    fs::create_dir_all(&artifacts_dir)
+        .map_err(|e| format!("failed to create artifacts dir: {}", e))?;
+    fs::write(artifacts_dir.join("pr.md"), &pr)
+        .map_err(|e| format!("failed to write pr.md: {}", e))?;
+    println!("  wrote {}", artifacts_dir.join("pr.md").display());
+
+    // Record the gate as passed before committing so the ledger lands in the commit.
+    // If the commit or the push fails, the state and handoff are put back below.
+    let mut done_state = state.clone();
+    done_state.gates.set("git", GateStatus::Pass);
+    done_state.status = WorkStatus::Done;
+    done_state.owner = crate::state::Role::Git;
+    done_state.touch();
+    write_state(&state_path, &done_state)?;
+    let handoff = write_handoff(&work_dir, &state.branch, opts)?;
+
+    let outcome = commit(&cwd, &state.branch, work_id, &message, opts)
+        .map_err(|e| ("commit", e))
+        .and_then(|previous_head| {
+            // A commit that never reached the remote must not claim the item is done
+            push(&cwd, &state.branch, opts).map_err(|e| match undo_commit(&cwd, previous_head.as_deref()) {
+                Ok(()) => ("push", e),
+                Err(undo) => ("push", format!("{}\n{}", e, undo)),
+            })
+        });
+    match outcome {
+        Ok(pushed) => {
+            let push_note = match &pushed {
+                Some(remote) => format!("pushed {} to {}", state.branch, remote),
+                None => "no remote configured — push skipped".to_string(),
+            };
+            println!("  {}", push_note);
+            runlog::append(
+                &work_dir,
+                &format!(
+                    "\n## Git Gate Complete: {}\n\n{}\n",
+                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+                    push_note,
+                ),
+            )?;
+            println!("git gate passed — {} is done", work_id);
+            if let Some(parent) = &done_state.parent {
+                crate::commands::epic::rollup(base, parent)?;
+            }
+            Ok(())
+        }
+        Err((step, e)) => {
+            let mut failed = state;
+            failed.gates.set("git", GateStatus::Fail);
+            failed.touch();
+            write_state(&state_path, &failed)?;
+            let _ = fs::remove_file(&handoff);
+            runlog::append(
+                &work_dir,
+                &format!(
+                    "\n## Git Gate Failed: {} — {}\n\n```\n{}\n```\n",
+                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+                    step,
+                    e,
+                ),
+            )?;
+            Err(e)
+        }
+    }
+}
+
+/// Commit everything on the work branch. Returns the branch head from before the
+/// commit (None on an unborn branch) so a failed push can take the commit back.
+/// A failed commit is undone here, including any squash.
+fn commit(cwd: &Path, branch: &str, work_id: &str, message: &str, opts: &GitGateOptions) -> Result<Option<String>, String> {
+    let current = git::current_branch(cwd)?;
+    if current != branch {
+        if git::branch_exists(cwd, branch) {
+            git::run(cwd, &["checkout", branch])?;
+        } else {
+            git::run(cwd, &["checkout", "-b", branch])?;
+        }
+    }
+    let previous_head = git::run(cwd, &["rev-parse", "--verify", "--quiet", "HEAD"]).ok();
+
+    match squash_and_commit(cwd, work_id, message, opts) {
+        Ok(()) => Ok(previous_head),
+        Err(e) => match undo_commit(cwd, previous_head.as_deref()) {
+            Ok(()) => Err(e),
+            Err(undo) => Err(format!("{}\n{}", e, undo)),
+        },
+    }
+}
+
+/// Untracked, non-ignored files that aren't pfm's own
+fn untracked_outside_pfm(cwd: &Path) -> Result<Vec<String>, String> {
+    let listed = git::run(cwd, &["ls-files", "--others", "--exclude-standard"])?;
+    Ok(listed.lines().filter(|path| !path.starts_with(".pfm/")).map(str::to_string).collect())
+}
+
+fn squash_and_commit(cwd: &Path, work_id: &str, message: &str, opts: &GitGateOptions) -> Result<(), String> {
+    if opts.squash {
+        let merge_base = git::run(cwd, &["merge-base", "HEAD", &opts.base_ref])?;
+        git::run(cwd, &["reset", "--soft", &merge_base])?;
+    }
+    git::run(cwd, &["add", "-u"])?;
+    let item = format!(".pfm/work/{}", work_id);
+    if cwd.join(&item).exists() {
+        git::run(cwd, &["add", "--", &item])?;
+    }
+    if git::has_staged_changes(cwd) {
+        git::run(cwd, &["commit", "-m", message])?;
+    }
+    Ok(())
+}
+
+/// Move the branch back to `previous_head`, keeping every change staged
+fn undo_commit(cwd: &Path, previous_head: Option<&str>) -> Result<(), String> {
+    match previous_head {
+        Some(head) => git::run(cwd, &["reset", "--soft", head]).map(|_| ()),
+        None => git::run(cwd, &["update-ref", "-d", "HEAD"]).map(|_| ()),
+    }
+}
+
+/// Push the work branch; returns the remote pushed to, if any
+fn push(cwd: &Path, branch: &str, opts: &GitGateOptions) -> Result<Option<String>, String> {
+    if opts.no_push {
+        return Ok(None);
+    }
+    match git::default_remote(cwd) {
+        Some(remote) => {
+            git::run(cwd, &["push", "-u", &remote, branch])?;
+            Ok(Some(remote))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Build the commit message from the PRD and handoff summaries
+fn commit_message(id: &str, title: &str, prd: &str, handoffs: &[(String, String)]) -> String {
+    let mut msg = format!("{}: {}\n", id, title);
+    if let Some(problem) = markdown_section(prd, "Problem Statement") {
+        msg.push('\n');
+        msg.push_str(&problem);
+        msg.push('\n');
+    }
+    if !handoffs.is_empty() {
+        msg.push_str("\nHandoffs:\n");
+        for (name, summary) in handoffs {
+            if summary.is_empty() {
+                msg.push_str(&format!("- {}\n", name));
+            } else {
+                msg.push_str(&format!("- {}: {}\n", name, summary));
+            }
+        }
+    }
+    msg
+}
+
+/// Build the PR description written to artifacts/pr.md
+fn pr_description(
+    id: &str,
+    title: &str,
+    branch: &str,
+    prd: &str,
+    acceptance: &str,
+    handoffs: &[(String, String)],
+) -> String {
+    let mut pr = format!("# {}\n\nWork item: `{}`\nBranch: `{}`\n", title, id, branch);
+    let sections = [
+        ("Summary", markdown_section(prd, "Problem Statement")),
+        ("Requirements", markdown_section(prd, "Requirements")),
+        ("Acceptance Criteria", markdown_section(acceptance, "Criteria")),
+    ];
+    for (heading, body) in sections {
+        if let Some(body) = body {
+            pr.push_str(&format!("\n## {}\n\n{}\n", heading, body));
+        }
+    }
+    if !handoffs.is_empty() {
+        pr.push_str("\n## Handoffs\n\n");
+        for (name, summary) in handoffs {
+            if summary.is_empty() {
+                pr.push_str(&format!("- `{}`\n", name));
+            } else {
+                pr.push_str(&format!("- `{}` — {}\n", name, summary));
+            }
+        }
+    }
+    pr
+}
+
+/// Extract the body of a `## heading` section, dropping HTML comments
+fn markdown_section(content: &str, heading: &str) -> Option<String> {
+    let mut lines = content.lines();
+    lines.find(|l| l.trim_start_matches('#').trim() == heading && l.starts_with("##"))?;
+
+    let body: Vec<&str> = lines
+        .take_while(|l| !l.starts_with("## "))
+        .filter(|l| !(l.trim_start().starts_with("<!--") && l.trim_end().ends_with("-->")))
+        .collect();
+    let body = body.join("\n").trim().to_string();
+    if body.is_empty() {
+        None
+    } else {
+        Some(body)
+    }
+}
+
+/// Handoff file names (sorted) with their first content line as a summary
+fn list_handoffs(handoffs_dir: &Path) -> Vec<(String, String)> {
+    let mut handoffs: Vec<(String, String)> = fs::read_dir(handoffs_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().ends_with(".md"))
+                .map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    let summary = fs::read_to_string(e.path())
+                        .unwrap_or_default()
+                        .lines()
+                        .map(str::trim)
+                        .find(|l| !l.is_empty() && !l.starts_with('#'))
+                        .unwrap_or("")
+                        .to_string();
+                    (name, summary)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    handoffs.sort();
+    handoffs
+}
+
+fn write_handoff(work_dir: &Path, branch: &str, opts: &GitGateOptions) -> Result<PathBuf, String> {
+    let now = Utc::now();
+    let path = work_dir
+        .join("handoffs")
+        .join(format!("{}-git.md", now.format("%Y%m%d-%H%M%S")));
+    let content = format!(
+        "# Handoff: git\n\nCommitted work on `{}`{} and wrote `artifacts/pr.md`.\n\nGate `git` = `pass`. Work status = `done`.\n",
+        branch,
+        if opts.squash { " (squashed)" } else { "" },
+    );
+    fs::create_dir_all(work_dir.join("handoffs"))
+        .map_err(|e| format!("failed to create handoffs dir: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use crate::state::read_state;
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let out = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    }
+
+    fn setup_repo(dir: &Path) {
+        git(dir, &["init", "-b", "main"]);
+        git(dir, &["config", "user.email", "dev@example.com"]);
+        git(dir, &["config", "user.name", "Dev"]);
+        git(dir, &["commit", "--allow-empty", "-m", "init"]);
+        init::run(dir).unwrap();
+    }
+
+    fn pass_prior_gates(dir: &Path, work_id: &str) {
+        let path = dir.join(".pfm/work").join(work_id).join("state.json");
+        let mut state = read_state(&path).unwrap();
+        for gate in state.pipeline.clone().iter().filter(|g| *g != "git") {
+            state.gates.set(gate, GateStatus::Pass);
+        }
+        write_state(&path, &state).unwrap();
+    }
+
+    #[test]
+    fn test_git_gate_refuses_unpassed_gates() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        work::new_work(dir.path(), "Test", Some("FEAT-G1"), None).unwrap();
+        let err = run(dir.path(), "FEAT-G1", &GitGateOptions::default()).unwrap_err();
+        assert!(err.contains("prd=todo"));
+    }
+
+    #[test]
+    fn test_git_gate_commits_without_remote() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        work::new_work(dir.path(), "Local only", Some("FEAT-G2"), None).unwrap();
+        pass_prior_gates(dir.path(), "FEAT-G2");
+
+        run(dir.path(), "FEAT-G2", &GitGateOptions::default()).unwrap();
+
+        let work_dir = dir.path().join(".pfm/work/FEAT-G2");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.gates.git, GateStatus::Pass);
+        assert_eq!(state.status, WorkStatus::Done);
+        assert!(work_dir.join("artifacts/pr.md").exists());
+        assert_eq!(git::current_branch(dir.path()).unwrap(), "pfm/FEAT-G2");
+        let subject = git::run(dir.path(), &["log", "-1", "--format=%s"]).unwrap();
+        assert_eq!(subject, "FEAT-G2: Local only");
+    }
+
+    #[test]
+    fn test_git_gate_commits_only_tracked_changes_and_the_work_item() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        fs::write(dir.path().join("lib.rs"), "v1").unwrap();
+        git(dir.path(), &["add", "lib.rs"]);
+        git(dir.path(), &["commit", "-m", "lib"]);
+        work::new_work(dir.path(), "Scoped", Some("FEAT-G6"), None).unwrap();
+        pass_prior_gates(dir.path(), "FEAT-G6");
+        fs::write(dir.path().join("lib.rs"), "v2").unwrap();
+        fs::write(dir.path().join("scratch.log"), "debug").unwrap();
+
+        let err = run(dir.path(), "FEAT-G6", &GitGateOptions::default()).unwrap_err();
+        assert!(err.contains("untracked files outside .pfm:\n  scratch.log"), "{}", err);
+        let state = read_state(&dir.path().join(".pfm/work/FEAT-G6/state.json")).unwrap();
+        assert_eq!(state.gates.git, GateStatus::Todo);
+
+        fs::remove_file(dir.path().join("scratch.log")).unwrap();
+        run(dir.path(), "FEAT-G6", &GitGateOptions::default()).unwrap();
+        let files = git::run(dir.path(), &["show", "--name-only", "--format=", "HEAD"]).unwrap();
+        assert!(files.lines().any(|f| f == "lib.rs"));
+        assert!(files.lines().any(|f| f == ".pfm/work/FEAT-G6/state.json"));
+        assert!(!files.lines().any(|f| f == ".pfm/config.json"));
+    }
+
+    #[test]
+    fn test_options_come_from_config_under_flags() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        assert_eq!(GitGateOptions::resolve(dir.path(), false, None, false).unwrap().base_ref, "main");
+        fs::write(
+            dir.path().join(".pfm/config.local.json"),
+            r#"{"git": {"squash": true, "base": "develop", "push": false}}"#,
+        )
+        .unwrap();
+        let opts = GitGateOptions::resolve(dir.path(), false, None, false).unwrap();
+        assert!(opts.squash && opts.no_push);
+        assert_eq!(opts.base_ref, "develop");
+        let opts = GitGateOptions::resolve(dir.path(), false, Some("trunk".into()), false).unwrap();
+        assert_eq!(opts.base_ref, "trunk");
+    }
+
+    #[test]
+    fn test_git_gate_pushes_to_bare_remote() {
+        let dir = tempdir().unwrap();
+        let remote = tempdir().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo(dir.path());
+        git(dir.path(), &["remote", "add", "origin", &remote.path().to_string_lossy()]);
+        work::new_work(dir.path(), "Pushed", Some("FEAT-G3"), None).unwrap();
+        pass_prior_gates(dir.path(), "FEAT-G3");
+
+        run(dir.path(), "FEAT-G3", &GitGateOptions::default()).unwrap();
+
+        let remote_head = git::run(remote.path(), &["rev-parse", "refs/heads/pfm/FEAT-G3"]).unwrap();
+        let local_head = git::run(dir.path(), &["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(remote_head, local_head);
+    }
+
+    #[test]
+    fn test_git_gate_squash() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-m", "pfm init"]);
+        work::new_work(dir.path(), "Squashed", Some("FEAT-G4"), None).unwrap();
+        git(dir.path(), &["checkout", "pfm/FEAT-G4"]);
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-m", "wip 1"]);
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-m", "wip 2"]);
+        pass_prior_gates(dir.path(), "FEAT-G4");
+
+        let opts = GitGateOptions { squash: true, ..GitGateOptions::default() };
+        run(dir.path(), "FEAT-G4", &opts).unwrap();
+
+        let count = git::run(dir.path(), &["rev-list", "--count", "main..HEAD"]).unwrap();
+        assert_eq!(count, "1");
+    }
+
+    fn git_handoffs(work_dir: &Path) -> usize {
+        list_handoffs(&work_dir.join("handoffs")).iter().filter(|(name, _)| name.ends_with("-git.md")).count()
+    }
+
+    #[test]
+    fn test_git_gate_failed_push_takes_back_commit() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        git(dir.path(), &["remote", "add", "origin", &dir.path().join("no-such-remote").to_string_lossy()]);
+        work::new_work(dir.path(), "Unpushed", Some("FEAT-G5"), None).unwrap();
+        pass_prior_gates(dir.path(), "FEAT-G5");
+        let before = git::run(dir.path(), &["rev-parse", "HEAD"]).unwrap();
+
+        let err = run(dir.path(), "FEAT-G5", &GitGateOptions::default()).unwrap_err();
+        assert!(err.contains("git push"), "{}", err);
+
+        let work_dir = dir.path().join(".pfm/work/FEAT-G5");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.gates.git, GateStatus::Fail);
+        assert_eq!(state.status, WorkStatus::InProgress);
+        assert_eq!(git_handoffs(&work_dir), 0);
+        assert_eq!(git::run(dir.path(), &["rev-parse", "HEAD"]).unwrap(), before);
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("## Git Gate Failed:") && runlog.contains(" — push\n"));
+    }
+
+    #[test]
+    fn test_git_gate_failed_commit_restores_state() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        let hook = dir.path().join(".git/hooks/pre-commit");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        work::new_work(dir.path(), "Rejected", Some("FEAT-G6"), None).unwrap();
+        pass_prior_gates(dir.path(), "FEAT-G6");
+
+        assert!(run(dir.path(), "FEAT-G6", &GitGateOptions::default()).unwrap_err().contains("git commit"));
+
+        let work_dir = dir.path().join(".pfm/work/FEAT-G6");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.gates.git, GateStatus::Fail);
+        assert_eq!(state.status, WorkStatus::InProgress);
+        assert_eq!(git_handoffs(&work_dir), 0);
+        assert!(fs::read_to_string(work_dir.join("runlog.md")).unwrap().contains(" — commit\n"));
+    }
+
+    #[test]
+    fn test_git_gate_rolls_up_parent_epic() {
+        let dir = tempdir().unwrap();
+        setup_repo(dir.path());
+        work::new_work(dir.path(), "Billing", Some("EPIC-g"), None).unwrap();
+        crate::commands::epic::make_epic(dir.path(), "EPIC-g").unwrap();
+        fs::write(dir.path().join(".pfm/work/EPIC-g/tasks.md"), "# Tasks\n\n## Tasks\n\n- [ ] Invoices\n").unwrap();
+        crate::commands::epic::split(dir.path(), "EPIC-g").unwrap();
+        pass_prior_gates(dir.path(), "EPIC-g-1");
+
+        run(dir.path(), "EPIC-g-1", &GitGateOptions::default()).unwrap();
+
+        let epic = read_state(&dir.path().join(".pfm/work/EPIC-g/state.json")).unwrap();
+        assert_eq!(epic.status, WorkStatus::Done);
+    }
+
+    #[test]
+    fn test_commit_message_uses_prd_and_handoffs() {
+        let prd = "# PRD\n\n## Problem Statement\n\n<!-- Describe -->\nUsers cannot log in.\n\n## Requirements\n";
+        let handoffs = vec![("20260101-000000-prd.md".to_string(), "Wrote the PRD".to_string())];
+        let msg = commit_message("FEAT-1", "Login", prd, &handoffs);
+        assert!(msg.starts_with("FEAT-1: Login\n"));
+        assert!(msg.contains("Users cannot log in."));
+        assert!(!msg.contains("<!--"));
+        assert!(msg.contains("- 20260101-000000-prd.md: Wrote the PRD"));
+    }
+
+    #[test]
+    fn test_markdown_section_template_only_is_none() {
+        let prd = "## Problem Statement\n\n<!-- Describe the problem -->\n\n## Requirements\n";
+        assert_eq!(markdown_section(prd, "Problem Statement"), None);
+    }
+}
@@ -0,0 +1,403 @@
+use crate::state::{read_state, write_state, Note, NoteKind};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// One work item to create, as read from an issues file
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    pub title: String,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub stack: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Lands in the PRD's "Problem Statement", or in notes if the PRD has no such section
+    #[serde(default, alias = "body")]
+    pub description: Option<String>,
+}
+
+/// Read entries from a `.json`, `.csv` or `.md` issues file
+pub fn parse_file(path: &Path) -> Result<Vec<Entry>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let entries = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => parse_json(&content),
+        Some("csv") => parse_csv(&content),
+        Some("md") | Some("markdown") => Ok(parse_markdown(&content)),
+        _ => Err("unsupported issues file (expected .json, .csv or .md)".into()),
+    }
+    .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(entries
+        .into_iter()
+        .map(|mut entry| {
+            let trim = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+            entry.title = entry.title.trim().to_string();
+            entry.id = trim(entry.id);
+            entry.stack = trim(entry.stack);
+            entry.priority = trim(entry.priority);
+            entry.description = trim(entry.description);
+            entry.labels = entry.labels.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+            entry
+        })
+        .collect())
+}
+
+/// A JSON array of entry objects
+pub fn parse_json(content: &str) -> Result<Vec<Entry>, String> {
+    serde_json::from_str(content).map_err(|e| format!("invalid issues JSON: {}", e))
+}
+
+/// A header row naming the columns, then one entry per row.
+/// Labels are separated by `;` or `,` within their cell.
+pub fn parse_csv(content: &str) -> Result<Vec<Entry>, String> {
+    let mut rows = csv_rows(content)?.into_iter();
+    let header: Vec<String> = rows.next().unwrap_or_default().into_iter().map(|h| h.trim().to_lowercase()).collect();
+    if !header.iter().any(|h| h == "title") {
+        return Err("CSV header needs a `title` column".into());
+    }
+    if let Some(unknown) = header
+        .iter()
+        .find(|h| !["title", "id", "stack", "priority", "labels", "description", "body"].contains(&h.as_str()))
+    {
+        return Err(format!("unknown CSV column `{}`", unknown));
+    }
+
+    Ok(rows
+        .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+        .map(|row| {
+            let mut entry = Entry::default();
+            for (column, value) in header.iter().zip(row) {
+                match column.as_str() {
+                    "title" => entry.title = value,
+                    "id" => entry.id = Some(value),
+                    "stack" => entry.stack = Some(value),
+                    "priority" => entry.priority = Some(value),
+                    "labels" => entry.labels = value.split([';', ',']).map(|l| l.trim().to_string()).collect(),
+                    _ => entry.description = Some(value),
+                }
+            }
+            entry
+        })
+        .collect())
+}
+
+/// Split CSV into rows of cells, honouring double-quoted cells with `""` escapes and newlines
+fn csv_rows(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if cell.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut cell)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted CSV cell".into());
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Each `## ` heading is an entry title. `key: value` lines right below it (optionally
+/// bulleted) set id, stack, priority and labels; the rest is the description.
+pub fn parse_markdown(content: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut description: Vec<&str> = Vec::new();
+    let mut in_metadata = false;
+
+    let finish = |entries: &mut Vec<Entry>, description: &mut Vec<&str>| {
+        if let Some(entry) = entries.last_mut() {
+            let text = description.join("\n").trim().to_string();
+            entry.description = (!text.is_empty()).then_some(text);
+        }
+        description.clear();
+    };
+
+    for line in content.lines() {
+        if let Some(title) = line.strip_prefix("## ") {
+            finish(&mut entries, &mut description);
+            entries.push(Entry { title: title.to_string(), ..Entry::default() });
+            in_metadata = true;
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else { continue };
+        if in_metadata {
+            let field = line.trim_start_matches(['-', '*']).trim();
+            let parsed = field.split_once(':').and_then(|(key, value)| {
+                let value = value.trim().to_string();
+                match key.trim().to_lowercase().as_str() {
+                    "id" => entry.id = Some(value),
+                    "stack" => entry.stack = Some(value),
+                    "priority" => entry.priority = Some(value),
+                    "labels" => entry.labels = value.split([';', ',']).map(|l| l.trim().to_string()).collect(),
+                    _ => return None,
+                }
+                Some(())
+            });
+            if parsed.is_some() || (field.is_empty() && description.is_empty()) {
+                continue;
+            }
+            in_metadata = false;
+        }
+        description.push(line);
+    }
+    finish(&mut entries, &mut description);
+    entries
+}
+
+/// What happened to one entry
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Created(String),
+    Skipped(String),
+}
+
+/// Create a work item for each entry that doesn't exist yet: by ID if the entry has
+/// one, else by title. Every entry is validated before anything is created. With
+/// `dry_run`, only report what would happen.
+pub fn import(base: &Path, entries: &[Entry], dry_run: bool) -> Result<Vec<Outcome>, String> {
+    let config = crate::config::load(base)?.config;
+    let existing_titles: BTreeMap<String, String> = crate::commands::work::load_all(base, true)?
+        .into_iter()
+        .filter_map(|(_, state)| state.ok())
+        .map(|state| (state.title, state.id))
+        .collect();
+
+    // Explicit IDs first, so generated ones steer around them
+    let mut reserved: BTreeSet<String> = BTreeSet::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let at = format!("entry {} ({})", i + 1, entry.title);
+        if entry.title.is_empty() {
+            return Err(format!("entry {} has no title", i + 1));
+        }
+        if let Some(stack) = &entry.stack {
+            if !config.stacks.contains_key(stack) {
+                return Err(format!("{}: unknown stack: {}", at, stack));
+            }
+        }
+        if let Some(id) = &entry.id {
+            crate::ids::validate(id).map_err(|e| format!("{}: {}", at, e))?;
+            if !reserved.insert(id.clone()) {
+                return Err(format!("{}: ID {} appears more than once in the file", at, id));
+            }
+        }
+    }
+
+    let mut titles = BTreeSet::new();
+    let mut plan = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let (id, exists) = match &entry.id {
+            Some(id) => (id.clone(), crate::commands::lifecycle::locate(base, id).is_some()),
+            None => {
+                if !titles.insert(entry.title.clone()) {
+                    return Err(format!(
+                        "entry {} ({}): title appears more than once without an ID",
+                        i + 1,
+                        entry.title
+                    ));
+                }
+                match existing_titles.get(&entry.title) {
+                    Some(id) => (id.clone(), true),
+                    None => {
+                        let id = crate::ids::propose(base, &config.ids, &config.ids.prefix, &entry.title, &reserved)
+                            .map_err(|e| format!("entry {} ({}): {}", i + 1, entry.title, e))?;
+                        reserved.insert(id.clone());
+                        (id, false)
+                    }
+                }
+            }
+        };
+        plan.push((id, entry, exists));
+    }
+
+    let mut outcomes = Vec::new();
+    for (id, entry, exists) in plan {
+        if exists {
+            println!("skip    {:<20} already exists", id);
+            outcomes.push(Outcome::Skipped(id));
+            continue;
+        }
+        if dry_run {
+            println!("create  {:<20} {}{}", id, entry.title, describe(entry));
+            outcomes.push(Outcome::Created(id));
+            continue;
+        }
+        if entry.id.is_none() {
+            crate::ids::reserve(base, &config.ids, &config.ids.prefix, &id)?;
+        }
+        create(base, &id, entry)?;
+        outcomes.push(Outcome::Created(id));
+    }
+
+    let created = outcomes.iter().filter(|o| matches!(o, Outcome::Created(_))).count();
+    println!();
+    println!(
+        "{} {} work item(s), skipped {} existing",
+        if dry_run { "would create" } else { "created" },
+        created,
+        outcomes.len() - created
+    );
+    Ok(outcomes)
+}
+
+fn describe(entry: &Entry) -> String {
+    let mut parts = Vec::new();
+    if let Some(stack) = &entry.stack {
+        parts.push(format!("stack {}", stack));
+    }
+    if let Some(priority) = &entry.priority {
+        parts.push(format!("priority {}", priority));
+    }
+    if !entry.labels.is_empty() {
+        parts.push(format!("labels {}", entry.labels.join(", ")));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("  ({})", parts.join("; "))
+    }
+}
+
+fn create(base: &Path, id: &str, entry: &Entry) -> Result<(), String> {
+    crate::commands::work::new_work(base, &entry.title, Some(id), entry.stack.as_deref())?;
+    let work_dir = base.join(".pfm/work").join(id);
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    state.priority = entry.priority.clone();
+    state.labels = entry.labels.clone();
+
+    if let Some(description) = &entry.description {
+        let prd_path = work_dir.join("prd.md");
+        let prd = fs::read_to_string(&prd_path).unwrap_or_default();
+        match fill_section(&prd, "## Problem Statement", description) {
+            Some(prd) => fs::write(&prd_path, prd).map_err(|e| format!("failed to write prd.md: {}", e))?,
+            None => state.notes.push(Note::new(description, "pfm import", NoteKind::Context)),
+        }
+    }
+    write_state(&state_path, &state)
+}
+
+/// Put `text` in the section under `heading`, replacing its body if that holds only
+/// template comments. None if there's no such heading.
+fn fill_section(content: &str, heading: &str, text: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.iter().position(|l| l.trim() == heading)?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with('#'))
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len());
+    let body = &lines[start + 1..end];
+    let placeholder = body.iter().all(|l| l.trim().is_empty() || l.trim().starts_with("<!--"));
+
+    let mut out: Vec<String> = lines[..=start].iter().map(|l| l.to_string()).collect();
+    out.push(String::new());
+    if !placeholder {
+        out.extend(body.iter().map(|l| l.to_string()).skip_while(|l| l.trim().is_empty()));
+        while out.last().is_some_and(|l| l.trim().is_empty()) {
+            out.pop();
+        }
+        out.push(String::new());
+    }
+    out.push(text.to_string());
+    out.push(String::new());
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    Some(out.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_csv_with_quotes() {
+        let csv = "title,id,labels,description\n\"Export, fast\",FEAT-exp,\"ui;api\",\"Says \"\"hi\"\"\nacross lines\"\nPlain,,,\n\n";
+        let entries = parse_csv(csv).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "Export, fast");
+        assert_eq!(entries[0].id.as_deref(), Some("FEAT-exp"));
+        assert_eq!(entries[0].labels, vec!["ui", "api"]);
+        assert_eq!(entries[0].description.as_deref(), Some("Says \"hi\"\nacross lines"));
+        assert!(parse_csv("name\nx\n").unwrap_err().contains("`title` column"));
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let md = "# Backlog\n\n## Export CSV\n- id: FEAT-csv\n- labels: data, export\npriority: high\n\nUsers need CSV.\n\nWith headers.\n## Dark mode\n";
+        let entries = parse_markdown(md);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id.as_deref(), Some("FEAT-csv"));
+        assert_eq!(entries[0].priority.as_deref(), Some("high"));
+        assert_eq!(entries[0].labels, vec!["data", "export"]);
+        assert_eq!(entries[0].description.as_deref(), Some("Users need CSV.\n\nWith headers."));
+        assert_eq!(entries[1], Entry { title: "Dark mode".into(), ..Entry::default() });
+    }
+
+    #[test]
+    fn test_import_is_idempotent_and_fills_prd() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        let path = dir.path().join("issues.json");
+        fs::write(
+            &path,
+            r#"[{"title": "Export CSV", "id": "FEAT-csv", "stack": "rust", "priority": "high", "labels": ["data"], "description": "Users can't get data out."},
+                {"title": "Dark mode", "body": "Too bright."}]"#,
+        )
+        .unwrap();
+        let entries = parse_file(&path).unwrap();
+
+        let dry = import(dir.path(), &entries, true).unwrap();
+        assert_eq!(dry, vec![Outcome::Created("FEAT-csv".into()), Outcome::Created("FEAT-dark-mode".into())]);
+        assert!(!dir.path().join(".pfm/work/FEAT-csv").exists());
+
+        import(dir.path(), &entries, false).unwrap();
+        let state = read_state(&dir.path().join(".pfm/work/FEAT-csv/state.json")).unwrap();
+        assert_eq!(state.priority.as_deref(), Some("high"));
+        assert_eq!(state.labels, vec!["data"]);
+        assert_eq!(state.commands.verify, "cargo test");
+        let prd = fs::read_to_string(dir.path().join(".pfm/work/FEAT-csv/prd.md")).unwrap();
+        assert!(prd.contains("## Problem Statement\n\nUsers can't get data out.\n\n## Requirements"));
+
+        let again = import(dir.path(), &entries, false).unwrap();
+        assert_eq!(again, vec![Outcome::Skipped("FEAT-csv".into()), Outcome::Skipped("FEAT-dark-mode".into())]);
+    }
+
+    #[test]
+    fn test_import_validates_before_creating() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        let entries = vec![
+            Entry { title: "Good".into(), ..Entry::default() },
+            Entry { title: "Bad".into(), stack: Some("cobol".into()), ..Entry::default() },
+        ];
+        assert!(import(dir.path(), &entries, false).unwrap_err().contains("entry 2 (Bad): unknown stack: cobol"));
+        assert!(!dir.path().join(".pfm/work/FEAT-good").exists());
+
+        let dupes = vec![Entry { title: "A".into(), id: Some("X".into()), ..Entry::default() }; 2];
+        assert!(import(dir.path(), &dupes, true).unwrap_err().contains("more than once"));
+    }
+}
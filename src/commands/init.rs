@@ -12,6 +12,7 @@ pub fn run(base: &Path) -> Result<(), String> {
         pfm_dir.join("roles"),
         pfm_dir.join("work"),
         pfm_dir.join("templates"),
+        pfm_dir.join("prompts"),
         pfm_dir.join("runtime"),
     ];
 
@@ -42,6 +43,21 @@ pub fn run(base: &Path) -> Result<(), String> {
         }
     }
 
+    // Write work type template sets if missing
+    for (set, filename, content) in templates::type_templates() {
+        let set_dir = pfm_dir.join("templates").join(set);
+        fs::create_dir_all(&set_dir)
+            .map_err(|e| format!("failed to create {}: {}", set_dir.display(), e))?;
+        let path = set_dir.join(filename);
+        if !path.exists() {
+            fs::write(&path, content)
+                .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+            println!("  created {}", path.display());
+        } else {
+            println!("  exists  {}", path.display());
+        }
+    }
+
     // Write role specs if missing
     for (filename, content) in templates::role_specs() {
         let path = pfm_dir.join("roles").join(filename);
@@ -54,6 +70,29 @@ pub fn run(base: &Path) -> Result<(), String> {
         }
     }
 
+    // Write prompt templates if missing, and refresh copies nobody has edited
+    for (filename, content) in templates::prompt_templates() {
+        let path = pfm_dir.join("prompts").join(filename);
+        if !path.exists() {
+            crate::prompt::write_stock(base, filename)?;
+            println!("  created {}", path.display());
+        } else if fs::read_to_string(&path).is_ok_and(|existing| {
+            existing != content && crate::prompt::is_stock(base, filename, &existing)
+        }) {
+            crate::prompt::write_stock(base, filename)?;
+            println!("  updated {}", path.display());
+        } else {
+            println!("  exists  {}", path.display());
+        }
+    }
+
+    // Keep machine-local config out of git
+    let pfm_gitignore_path = pfm_dir.join(".gitignore");
+    if !pfm_gitignore_path.exists() {
+        fs::write(&pfm_gitignore_path, "config.local.json\n")
+            .map_err(|e| format!("failed to write .gitignore: {}", e))?;
+    }
+
     // Write .gitignore for runtime dir
     let gitignore_path = pfm_dir.join("runtime").join(".gitignore");
     if !gitignore_path.exists() {
@@ -79,8 +118,11 @@ mod tests {
         assert!(dir.path().join(".pfm/roles").exists());
         assert!(dir.path().join(".pfm/work").exists());
         assert!(dir.path().join(".pfm/templates").exists());
+        assert!(dir.path().join(".pfm/templates/bug/prd.md").exists());
         assert!(dir.path().join(".pfm/runtime").exists());
         assert!(dir.path().join(".pfm/config.json").exists());
+        let gitignore = fs::read_to_string(dir.path().join(".pfm/.gitignore")).unwrap();
+        assert!(gitignore.contains("config.local.json"));
     }
 
     #[test]
@@ -103,6 +145,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_init_creates_prompt_templates() {
+        let dir = tempdir().unwrap();
+        run(dir.path()).unwrap();
+
+        for (filename, _) in templates::prompt_templates() {
+            assert!(dir.path().join(".pfm/prompts").join(filename).exists());
+        }
+    }
+
     #[test]
     fn test_init_idempotent() {
         let dir = tempdir().unwrap();
@@ -116,7 +168,8 @@ mod tests {
     fn test_init_config_valid_json() {
         let dir = tempdir().unwrap();
         run(dir.path()).unwrap();
-        let config = crate::config::read_config(&dir.path().join(".pfm/config.json")).unwrap();
+        let layer = crate::config::read_layer(&dir.path().join(".pfm/config.json")).unwrap();
+        let config: crate::config::PfmConfig = serde_json::from_value(layer).unwrap();
         assert_eq!(config.default_stack, "rails");
     }
 }
@@ -0,0 +1,341 @@
+use crate::runlog;
+use crate::state::{read_state, write_state, Transition, WorkStatus};
+use chrono::{Datelike, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A work item lifecycle command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Close,
+    Block,
+    Unblock,
+    Reopen,
+    Archive,
+    Remove,
+}
+
+impl Action {
+    fn name(self) -> &'static str {
+        match self {
+            Action::Close => "close",
+            Action::Block => "block",
+            Action::Unblock => "unblock",
+            Action::Reopen => "reopen",
+            Action::Archive => "archive",
+            Action::Remove => "rm",
+        }
+    }
+
+    /// Statuses the action applies to without `--force`
+    fn allowed_from(self) -> &'static [WorkStatus] {
+        match self {
+            Action::Close => &[WorkStatus::InProgress, WorkStatus::Blocked],
+            Action::Block => &[WorkStatus::InProgress],
+            Action::Unblock => &[WorkStatus::Blocked],
+            Action::Reopen => &[WorkStatus::Done],
+            Action::Archive | Action::Remove => &[WorkStatus::Done, WorkStatus::Blocked],
+        }
+    }
+
+    /// The status the item ends up in
+    fn target(self, from: &WorkStatus) -> WorkStatus {
+        match self {
+            Action::Close => WorkStatus::Done,
+            Action::Block => WorkStatus::Blocked,
+            Action::Unblock | Action::Reopen => WorkStatus::InProgress,
+            Action::Archive | Action::Remove => from.clone(),
+        }
+    }
+}
+
+/// Archived work item directories: `.pfm/archive/<year>/<id>`
+pub fn archived_dirs(base: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(base.join(".pfm/archive"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|year| year.path())
+        .filter(|year| year.is_dir())
+        .flat_map(|year| fs::read_dir(year).into_iter().flatten().flatten().map(|e| e.path()))
+        .filter(|dir| dir.join("state.json").exists())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Where a work item lives, and whether it is archived
+pub fn locate(base: &Path, work_id: &str) -> Option<(PathBuf, bool)> {
+    let active = base.join(".pfm/work").join(work_id);
+    if active.exists() {
+        return Some((active, false));
+    }
+    archived_dirs(base)
+        .into_iter()
+        .find(|dir| dir.file_name().map(|n| n == work_id).unwrap_or(false))
+        .map(|dir| (dir, true))
+}
+
+/// Apply a lifecycle action, recording it with its reason in the item's history and runlog
+pub fn run(base: &Path, work_id: &str, action: Action, reason: &str, force: bool) -> Result<(), String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(format!("`pfm work {}` needs a --reason", action.name()));
+    }
+    crate::ids::validate(work_id)?;
+    let (dir, archived) = locate(base, work_id).ok_or_else(|| format!("work item {} not found", work_id))?;
+    if archived && !matches!(action, Action::Reopen | Action::Remove) {
+        return Err(format!("work item {} is archived — reopen it first", work_id));
+    }
+
+    let state_path = dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    if state.id != work_id {
+        return Err(format!(
+            "cannot {} {}: {} belongs to work item '{}'",
+            action.name(),
+            work_id,
+            state_path.display(),
+            state.id
+        ));
+    }
+    let from = state.status.clone();
+    // Reopening an archived item restores it whatever its status
+    let restoring = archived && action == Action::Reopen;
+    if !force && !restoring && !action.allowed_from().contains(&from) {
+        let allowed: Vec<String> = action.allowed_from().iter().map(|s| s.to_string()).collect();
+        return Err(format!(
+            "cannot {} {}: it is {} (expected {}{})",
+            action.name(),
+            work_id,
+            from,
+            allowed.join(" or "),
+            if matches!(action, Action::Archive | Action::Remove) { "; use --force to override" } else { "" }
+        ));
+    }
+
+    // Archiving and restoring move the directory; check the way is clear before writing anything
+    let destination = match action {
+        Action::Archive => Some(base.join(".pfm/archive").join(Utc::now().year().to_string()).join(work_id)),
+        Action::Reopen if archived => Some(base.join(".pfm/work").join(work_id)),
+        _ => None,
+    };
+    if let Some(destination) = &destination {
+        if destination.exists() {
+            return Err(format!("cannot move {}: {} already exists", work_id, destination.display()));
+        }
+    }
+
+    let to = action.target(&from);
+    state.status = to.clone();
+    state.history.push(Transition {
+        at: Utc::now().to_rfc3339(),
+        action: action.name().to_string(),
+        from: from.clone(),
+        to: to.clone(),
+        reason: reason.to_string(),
+    });
+    state.touch();
+
+    if action == Action::Remove {
+        fs::remove_dir_all(&dir).map_err(|e| format!("failed to remove {}: {}", dir.display(), e))?;
+        let entry = format!(
+            "- {} — {} \"{}\" removed (was {}): {}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            work_id,
+            state.title,
+            from,
+            reason
+        );
+        append_line(&base.join(".pfm/archive/removed.md"), &entry)?;
+        println!("removed {} ({})", work_id, dir.display());
+        if let Some(parent) = &state.parent {
+            crate::commands::epic::rollup(base, parent)?;
+        }
+        return Ok(());
+    }
+
+    // Move first, so a failed move leaves the item exactly as it was
+    let dir = match &destination {
+        Some(destination) => {
+            fs::create_dir_all(destination.parent().expect("destination has a parent"))
+                .map_err(|e| format!("failed to create {}: {}", destination.display(), e))?;
+            fs::rename(&dir, destination)
+                .map_err(|e| format!("failed to move {} to {}: {}", dir.display(), destination.display(), e))?;
+            destination.clone()
+        }
+        None => dir,
+    };
+    write_state(&dir.join("state.json"), &state)?;
+    runlog::append(
+        &dir,
+        &format!(
+            "\n## Lifecycle: {} — {} ({} → {})\n\nReason: {}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            action.name(),
+            from,
+            to,
+            reason
+        ),
+    )?;
+
+    let change = if from == to { from.to_string() } else { format!("{} → {}", from, to) };
+    match destination {
+        Some(destination) => {
+            println!("{} {} ({}) — now at {}", past_tense(action), work_id, change, destination.display())
+        }
+        None => println!("{} {} ({})", past_tense(action), work_id, change),
+    }
+    if action == Action::Reopen {
+        for id in crate::deps::flag_downstream(base, work_id, reason)? {
+            println!("  flagged {} (depends on {})", id, work_id);
+        }
+    }
+    if let Some(parent) = &state.parent {
+        crate::commands::epic::rollup(base, parent)?;
+    }
+    Ok(())
+}
+
+fn past_tense(action: Action) -> &'static str {
+    match action {
+        Action::Close => "closed",
+        Action::Block => "blocked",
+        Action::Unblock => "unblocked",
+        Action::Reopen => "reopened",
+        Action::Archive => "archived",
+        Action::Remove => "removed",
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    fn setup(id: &str) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Lifecycle", Some(id), Some("rust")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_block_unblock_close_records_history() {
+        let dir = setup("FEAT-LC");
+        run(dir.path(), "FEAT-LC", Action::Block, "waiting on API keys", false).unwrap();
+        run(dir.path(), "FEAT-LC", Action::Unblock, "keys arrived", false).unwrap();
+        run(dir.path(), "FEAT-LC", Action::Close, "shipped", false).unwrap();
+
+        let work_dir = dir.path().join(".pfm/work/FEAT-LC");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.status, WorkStatus::Done);
+        let actions: Vec<&str> = state.history.iter().map(|t| t.action.as_str()).collect();
+        assert_eq!(actions, vec!["block", "unblock", "close"]);
+        assert_eq!(state.history[0].reason, "waiting on API keys");
+        assert_eq!(state.history[0].to, WorkStatus::Blocked);
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("— close (in_progress → done)\n\nReason: shipped"));
+    }
+
+    #[test]
+    fn test_invalid_transitions_rejected() {
+        let dir = setup("FEAT-LC");
+        let err = run(dir.path(), "FEAT-LC", Action::Unblock, "why not", false).unwrap_err();
+        assert!(err.contains("cannot unblock FEAT-LC: it is in_progress (expected blocked)"));
+        let err = run(dir.path(), "FEAT-LC", Action::Archive, "old", false).unwrap_err();
+        assert!(err.contains("use --force"));
+        assert!(run(dir.path(), "FEAT-LC", Action::Close, "  ", false).unwrap_err().contains("needs a --reason"));
+    }
+
+    #[test]
+    fn test_archive_and_reopen_move_the_item() {
+        let dir = setup("FEAT-LC");
+        run(dir.path(), "FEAT-LC", Action::Close, "done", false).unwrap();
+        run(dir.path(), "FEAT-LC", Action::Archive, "cleanup", false).unwrap();
+
+        let year = Utc::now().year().to_string();
+        let archived = dir.path().join(".pfm/archive").join(&year).join("FEAT-LC");
+        assert!(archived.join("state.json").exists());
+        assert!(archived.join("prd.md").exists());
+        assert!(!dir.path().join(".pfm/work/FEAT-LC").exists());
+        assert_eq!(locate(dir.path(), "FEAT-LC"), Some((archived, true)));
+        assert!(run(dir.path(), "FEAT-LC", Action::Block, "x", false).unwrap_err().contains("is archived"));
+        assert!(work::new_work(dir.path(), "Again", Some("FEAT-LC"), Some("rust")).unwrap_err().contains("archive"));
+
+        let mut filter = work::ListFilter::default();
+        assert!(work::summaries(dir.path(), &filter).unwrap().is_empty());
+        filter.archived = true;
+        let listed = work::summaries(dir.path(), &filter).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].archived);
+
+        run(dir.path(), "FEAT-LC", Action::Reopen, "regression found", false).unwrap();
+        let state = read_state(&dir.path().join(".pfm/work/FEAT-LC/state.json")).unwrap();
+        assert_eq!(state.status, WorkStatus::InProgress);
+        assert_eq!(state.history.len(), 3);
+    }
+
+    #[test]
+    fn test_blocked_move_leaves_item_untouched() {
+        let dir = setup("FEAT-LC");
+        run(dir.path(), "FEAT-LC", Action::Close, "done", false).unwrap();
+        let taken = dir.path().join(".pfm/archive").join(Utc::now().year().to_string()).join("FEAT-LC");
+        fs::create_dir_all(&taken).unwrap();
+
+        let work_dir = dir.path().join(".pfm/work/FEAT-LC");
+        let before = fs::read_to_string(work_dir.join("state.json")).unwrap();
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        let err = run(dir.path(), "FEAT-LC", Action::Archive, "cleanup", false).unwrap_err();
+        assert!(err.contains("already exists"));
+        assert_eq!(fs::read_to_string(work_dir.join("state.json")).unwrap(), before);
+        assert_eq!(fs::read_to_string(work_dir.join("runlog.md")).unwrap(), runlog);
+    }
+
+    #[test]
+    fn test_remove_logs_reason() {
+        let dir = setup("FEAT-LC");
+        assert!(run(dir.path(), "FEAT-LC", Action::Remove, "duplicate", false).is_err());
+        run(dir.path(), "FEAT-LC", Action::Remove, "duplicate of FEAT-X", true).unwrap();
+        assert!(locate(dir.path(), "FEAT-LC").is_none());
+        let log = fs::read_to_string(dir.path().join(".pfm/archive/removed.md")).unwrap();
+        assert!(log.contains("FEAT-LC \"Lifecycle\" removed (was in_progress): duplicate of FEAT-X"));
+    }
+
+    #[test]
+    fn test_actions_stay_inside_the_work_directory() {
+        let dir = setup("FEAT-LC");
+        run(dir.path(), "FEAT-LC", Action::Close, "done", false).unwrap();
+        run(dir.path(), "FEAT-LC", Action::Archive, "cleanup", false).unwrap();
+        let year = Utc::now().year().to_string();
+        let archived = dir.path().join(".pfm/archive").join(&year).join("FEAT-LC");
+
+        let traversal = format!("../archive/{}/FEAT-LC", year);
+        let err = run(dir.path(), &traversal, Action::Remove, "oops", true).unwrap_err();
+        assert!(err.contains("invalid work ID"), "{}", err);
+        assert!(archived.join("state.json").exists());
+
+        // A directory whose state.json names another item is not that item
+        let copy = dir.path().join(".pfm/work/FEAT-COPY");
+        fs::create_dir_all(&copy).unwrap();
+        fs::copy(archived.join("state.json"), copy.join("state.json")).unwrap();
+        let err = run(dir.path(), "FEAT-COPY", Action::Remove, "dup", true).unwrap_err();
+        assert!(err.contains("belongs to work item 'FEAT-LC'"), "{}", err);
+        assert!(copy.exists());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::lint::{check_artifact, Fill, LINTED_ARTIFACTS};
+use crate::output::Format;
+use crate::state::{gate_to_role, read_state, GateStatus};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// One linted artifact. `problem` is null when it's filled in; `required_by` names
+/// the passed gate whose contract lists it as an output.
+#[derive(Debug, Serialize)]
+pub struct ArtifactView {
+    pub file: String,
+    pub problem: Option<String>,
+    pub required_by: Option<String>,
+}
+
+/// What `pfm lint` reports
+#[derive(Debug, Serialize)]
+pub struct LintView {
+    pub errors: usize,
+    pub artifacts: Vec<ArtifactView>,
+}
+
+/// Report work artifacts still holding template content. Artifacts that a passed
+/// gate's role contract lists as outputs are errors; the rest are warnings.
+pub fn run(base: &Path, work_id: &str, format: Format) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let state = read_state(&work_dir.join("state.json"))?;
+
+    // Which passed gate (if any) requires each artifact
+    let mut required: Vec<(String, String)> = Vec::new();
+    for gate in &state.pipeline {
+        if state.gates.get(gate) != Some(&GateStatus::Pass) {
+            continue;
+        }
+        let Some(role) = gate_to_role(gate) else { continue };
+        if let Some(contract) = crate::role_spec::load(base, &role)? {
+            for output in contract.outputs {
+                required.push((output, gate.clone()));
+            }
+        }
+    }
+
+    let mut files: Vec<String> = LINTED_ARTIFACTS.iter().map(|f| f.to_string()).collect();
+    for (output, _) in &required {
+        if !files.contains(output) {
+            files.push(output.clone());
+        }
+    }
+
+    let artifacts: Vec<ArtifactView> = files
+        .into_iter()
+        .map(|file| {
+            let required_by = required.iter().find(|(o, _)| *o == file).map(|(_, g)| g.clone());
+            let problem = match fs::read_to_string(work_dir.join(&file)) {
+                Err(_) => Some("is missing".to_string()),
+                Ok(content) if content.trim().is_empty() => Some("is empty".to_string()),
+                Ok(content) => check_artifact(base, &work_dir, &file, &content)
+                    .filter(|fill| *fill != Fill::Filled)
+                    .map(|fill| fill.describe()),
+            };
+            ArtifactView { file, problem, required_by }
+        })
+        .collect();
+    let view = LintView {
+        errors: artifacts.iter().filter(|a| a.problem.is_some() && a.required_by.is_some()).count(),
+        artifacts,
+    };
+
+    format.emit(&view, print_report)?;
+    if view.errors > 0 {
+        Err(format!("{} artifact(s) behind passed gates are incomplete", view.errors))
+    } else {
+        Ok(())
+    }
+}
+
+fn print_report(view: &LintView) {
+    for artifact in &view.artifacts {
+        match (&artifact.problem, &artifact.required_by) {
+            (None, _) => println!("  [OK] {:<20} is filled in", artifact.file),
+            (Some(problem), Some(gate)) => {
+                println!("  [XX] {:<20} {} (required by {}=pass)", artifact.file, problem, gate)
+            }
+            (Some(problem), None) => println!("  [!!] {:<20} {}", artifact.file, problem),
+        }
+    }
+
+    println!();
+    if view.errors == 0 {
+        println!("no incomplete artifacts behind passed gates");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use crate::state::write_state;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_lint_flags_template_behind_passed_gate() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Lint", Some("FEAT-LINT"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-LINT");
+
+        // Nothing has passed yet, so untouched templates are only warnings
+        assert!(run(dir.path(), "FEAT-LINT", Format::Table).is_ok());
+
+        let state_path = work_dir.join("state.json");
+        let mut state = read_state(&state_path).unwrap();
+        state.gates.set("prd", GateStatus::Pass);
+        write_state(&state_path, &state).unwrap();
+        fs::write(work_dir.join("prd.md"), "# PRD\n\nUsers can export reports.\n").unwrap();
+        let err = run(dir.path(), "FEAT-LINT", Format::Table).unwrap_err();
+        assert!(err.starts_with("1 artifact(s)"));
+
+        fs::write(work_dir.join("acceptance.md"), "- [ ] Export produces CSV\n").unwrap();
+        assert!(run(dir.path(), "FEAT-LINT", Format::Table).is_ok());
+    }
+}
@@ -1,6 +1,20 @@
 pub mod init;
 pub mod work;
 pub mod agent;
+pub mod bundle;
 pub mod check;
+pub mod config;
+pub mod deps;
+pub mod doctor;
+pub mod epic;
+pub mod gate;
+pub mod git_gate;
+pub mod import;
+pub mod lifecycle;
+pub mod lint;
+pub mod note;
+pub mod prompt;
 pub mod run;
+pub mod stack;
 pub mod status;
+pub mod trace;
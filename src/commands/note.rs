@@ -0,0 +1,84 @@
+use crate::runlog;
+use crate::state::{read_state, write_state, Note, NoteKind};
+use chrono::Utc;
+use std::path::Path;
+use std::process::Command;
+
+/// Add a note to a work item. Role prompts pick it up on the next agent run.
+pub fn add(base: &Path, work_id: &str, text: &str, kind: NoteKind) -> Result<Note, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("note text is empty".into());
+    }
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+
+    let note = Note::new(text, &author(base), kind);
+    state.notes.push(note.clone());
+    state.touch();
+    write_state(&state_path, &state)?;
+    runlog::append(
+        &work_dir,
+        &format!(
+            "\n## Note: {} — {} from {}\n\n{}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            note.kind,
+            note.author,
+            note.text
+        ),
+    )?;
+    println!("{}: added {} note", work_id, note.kind);
+    Ok(note)
+}
+
+/// Who is writing the note: the agent pfm started, else the git user, else the login name
+fn author(base: &Path) -> String {
+    if let Ok(role) = std::env::var(crate::approval::AGENT_ENV) {
+        return format!("{} agent", role);
+    }
+    Command::new("git")
+        .args(["config", "user.name"])
+        .current_dir(base)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_add_records_kind_author_and_runlog() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Notes", Some("FEAT-NT"), Some("rust")).unwrap();
+
+        add(dir.path(), "FEAT-NT", "  keep the v1 API  ", NoteKind::Decision).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-NT");
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.notes.len(), 1);
+        assert_eq!(state.notes[0].text, "keep the v1 API");
+        assert_eq!(state.notes[0].kind, NoteKind::Decision);
+        assert!(!state.notes[0].author.is_empty());
+        assert!(!state.notes[0].at.is_empty());
+
+        let runlog = fs::read_to_string(work_dir.join("runlog.md")).unwrap();
+        assert!(runlog.contains("## Note:"));
+        assert!(runlog.contains("\n\nkeep the v1 API\n"));
+
+        assert!(add(dir.path(), "FEAT-NT", " ", NoteKind::Context).unwrap_err().contains("empty"));
+        assert!(add(dir.path(), "FEAT-XX", "x", NoteKind::Context).unwrap_err().contains("not found"));
+    }
+}
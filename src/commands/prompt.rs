@@ -0,0 +1,67 @@
+use crate::state::{read_state, Role};
+use std::path::Path;
+
+/// Preview a prompt: `role` is a role name, or `lead` for the teams lead prompt
+pub fn render(base: &Path, role: &str, work_id: &str, nudge: bool) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+
+    let state = read_state(&work_dir.join("state.json"))?;
+    let text = if role == "lead" {
+        let remaining = crate::commands::run::remaining_roles(&state, None);
+        crate::commands::run::render_teams_prompt(base, &work_dir, &remaining)
+    } else {
+        let role: Role = role.parse()?;
+        crate::commands::agent::require_role_for(base, &role, &state)?;
+        if nudge {
+            crate::commands::agent::render_nudge_prompt(&role, &work_dir, base)
+        } else {
+            crate::commands::agent::render_bootstrap_prompt(&role, &work_dir, base)
+        }
+    };
+
+    println!("{}", text);
+    Ok(())
+}
+
+/// Build `artifacts/context-<role>.md` on demand, whether or not packing is enabled
+pub fn context(base: &Path, role: &str, work_id: &str, budget: Option<usize>) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let role: Role = role.parse()?;
+    crate::commands::agent::require_role_for(base, &role, &read_state(&work_dir.join("state.json"))?)?;
+    let budget = match budget {
+        Some(budget) => budget,
+        None => crate::config::load(base)?.config.context.budget,
+    };
+
+    let path = crate::context::write(&work_dir, &role, budget)?;
+    println!("wrote context pack: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_prompts_need_a_role_in_the_pipeline() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Prompt", Some("FEAT-P"), Some("rust")).unwrap();
+
+        render(dir.path(), "qa", "FEAT-P", false).unwrap();
+        let err = render(dir.path(), "qa_revew", "FEAT-P", true).unwrap_err();
+        assert!(err.contains("custom role 'qa_revew' has no role spec"), "{}", err);
+        std::fs::write(dir.path().join(".pfm/roles/docs.md"), "# Role: Docs").unwrap();
+        let err = render(dir.path(), "docs", "FEAT-P", false).unwrap_err();
+        assert!(err.contains("not part of FEAT-P's pipeline"), "{}", err);
+        assert!(context(dir.path(), "docs", "FEAT-P", None).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::state::{self, GateStatus, Role, read_state, gate_to_role, GATE_ORDER};
+use crate::runlog;
+use crate::state::{self, GateStatus, Role, read_state, gate_to_role};
 use chrono::Utc;
 use std::env;
 use std::fs;
@@ -49,62 +50,76 @@ impl std::str::FromStr for RunMode {
     }
 }
 
-/// Run the pipeline for a work item
+/// Run the pipeline for a work item. With `wait`, first wait for its dependencies.
 pub fn run(
     base: &Path,
     work_id: &str,
     to_gate: Option<&str>,
     mode: RunMode,
+    wait: bool,
+    git: &crate::commands::git_gate::GitGateOptions,
 ) -> Result<(), String> {
     let work_dir = base.join(".pfm/work").join(work_id);
     if !work_dir.exists() {
         return Err(format!("work item {} not found", work_id));
     }
 
-    // Validate --to gate if provided
+    // Upstream work items must be done first
+    crate::commands::deps::ensure_ready(base, work_id, wait)?;
+
+    // Validate --to gate against this work item's pipeline
     if let Some(gate) = to_gate {
-        if !GATE_ORDER.contains(&gate) {
-            return Err(format!("unknown gate: {} (valid: {:?})", gate, GATE_ORDER));
+        let state = read_state(&work_dir.join("state.json"))?;
+        if state.gate_index(gate).is_none() {
+            return Err(format!("unknown gate: {} (valid: {:?})", gate, state.pipeline));
         }
     }
 
     let mode = mode.resolve();
 
-    match mode {
-        RunMode::Teams => return run_teams(base, work_id, to_gate),
-        _ => {}
+    if mode == RunMode::Teams {
+        return run_teams(base, work_id, to_gate);
     }
 
     println!("starting pipeline for {} (classic mode)", work_id);
     println!();
 
     loop {
-        let state = read_state(&work_dir.join("state.json"))?;
+        let state = crate::approval::enforce(base, &work_dir)?;
+        let approval_gates = crate::approval::required(base, &state);
 
         // Find next gate to process
         let next_gate = match determine_next_gate(&state) {
             Some(gate) => gate,
-            None => {
-                println!("all gates passed — work item complete!");
-                return Ok(());
-            }
+            None => return finish(base, work_id, &state),
         };
 
         // Check if we've reached the target gate (already passed)
         if let Some(target) = to_gate {
-            if gate_index(target) < gate_index(next_gate) {
+            if gate_index(&state, target) < gate_index(&state, next_gate) {
                 println!("reached target gate '{}' — stopping", target);
                 return Ok(());
             }
         }
 
+        // Approval gates wait for a person; nothing runs until they decide
+        if approval_gates.iter().any(|g| g == next_gate) {
+            return pause_for_approval(&work_dir, work_id, next_gate);
+        }
+
         let role = gate_to_role(next_gate)
             .ok_or_else(|| format!("no role for gate: {}", next_gate))?;
 
-        println!("=== gate: {} | role: {} ===", next_gate, role);
+        if next_gate == "git" {
+            // The git gate is executed by pfm itself — no agent needed
+            println!("=== gate: {} | built-in ===", next_gate);
+            crate::commands::git_gate::run(base, work_id, git)?;
+        } else {
+            println!("=== gate: {} | role: {} ===", next_gate, role);
 
-        // Start the agent — runs interactively, blocks until user exits
-        crate::commands::agent::start(base, &role, work_id)?;
+            // Start the agent — runs interactively, blocks until user exits
+            crate::commands::agent::start(base, &role, work_id)?;
+        }
 
         // Agent session ended — check what happened
         println!();
@@ -122,7 +137,7 @@ pub fn run(
         // Auto-run check after tests/impl gates
         if next_gate == "tests" || next_gate == "impl" {
             println!("running automatic checks...");
-            let _ = crate::commands::check::run(base, work_id);
+            let _ = crate::commands::check::run(base, work_id, crate::output::Format::Table);
         }
 
         // Handle reroute rules
@@ -152,88 +167,60 @@ pub fn run(
     }
 }
 
+/// Report a finished pipeline. An epic's plan is split into child work items.
+fn finish(base: &Path, work_id: &str, state: &state::WorkState) -> Result<(), String> {
+    if !state.epic {
+        println!("all gates passed — work item complete!");
+        return Ok(());
+    }
+    let created = crate::commands::epic::split(base, work_id)?;
+    println!("epic planned — {} new child work item(s); run each with `pfm run <id>`", created.len());
+    Ok(())
+}
+
+/// Ask for sign-off on an approval gate, or report the rejection that is holding it
+fn pause_for_approval(work_dir: &Path, work_id: &str, gate: &str) -> Result<(), String> {
+    let state = read_state(&work_dir.join("state.json"))?;
+    if state.gates.get(gate) == Some(&GateStatus::Fail) {
+        println!("=== gate: {} | rejected ===", gate);
+        println!("human intervention needed: {} was rejected — see the runlog for the comment", gate);
+        println!("  after the rework: pfm gate reset {} --from <gate> --reason \"...\"", work_id);
+        return Ok(());
+    }
+    let pending = crate::approval::request(work_dir, gate)?;
+    println!("=== gate: {} | approval ===", gate);
+    println!("waiting for sign-off: {}", pending.display());
+    println!("  approve: pfm approve {} {} --comment \"...\"", work_id, gate);
+    println!("  reject:  pfm reject {} {} --comment \"...\"", work_id, gate);
+    Ok(())
+}
+
 /// Run pipeline using Claude Code agent teams.
 /// Starts a single lead session that spawns teammates for each remaining role.
 fn run_teams(base: &Path, work_id: &str, to_gate: Option<&str>) -> Result<(), String> {
     let work_dir = base.join(".pfm/work").join(work_id);
-    let state = read_state(&work_dir.join("state.json"))?;
-
-    // Collect the gates/roles that still need to run
-    let mut remaining_roles: Vec<(&str, Role)> = Vec::new();
-    for gate_name in GATE_ORDER {
-        if let Some(status) = state.gates.get(gate_name) {
-            if *status != GateStatus::Pass {
-                if let Some(role) = gate_to_role(gate_name) {
-                    remaining_roles.push((gate_name, role));
-                }
-            }
-        }
-        if let Some(target) = to_gate {
-            if *gate_name == target {
-                break;
-            }
+    let state = crate::approval::enforce(base, &work_dir)?;
+
+    // Collect the gates/roles that still need to run, stopping at the first approval gate
+    let mut remaining_roles = remaining_roles(&state, to_gate);
+    let mut to_gate = to_gate;
+    let approval_gates = crate::approval::required(base, &state);
+    if let Some(pos) = remaining_roles.iter().position(|(gate, _)| approval_gates.contains(gate)) {
+        if pos == 0 {
+            return pause_for_approval(&work_dir, work_id, &remaining_roles[0].0);
         }
+        remaining_roles.truncate(pos);
+        to_gate = remaining_roles.last().map(|(gate, _)| gate.as_str());
     }
 
     if remaining_roles.is_empty() {
-        println!("all gates passed — work item complete!");
-        return Ok(());
+        return finish(base, work_id, &state);
     }
 
-    let roles_dir = base.join(".pfm/roles");
-    let role_list: Vec<String> = remaining_roles
-        .iter()
-        .map(|(gate, role)| {
-            format!(
-                "- **{}** (gate: `{}`): role spec at `{}`",
-                role,
-                gate,
-                roles_dir.join(format!("{}.md", role)).display()
-            )
-        })
-        .collect();
-
-    let prompt = format!(
-        r#"You are the PFM orchestrator lead agent running in teams mode.
-
-## Work Item
-- ID: {work_id}
-- Directory: {work_dir}
-- State: {work_dir}/state.json
-
-## Your Job
-Spawn a teammate for each role below. Each teammate must:
-1. Read their role spec and follow it exactly
-2. Read {work_dir}/state.json and {work_dir}/tasks.md before starting
-3. Read the most recent file in {work_dir}/handoffs/ for context from prior roles
-4. Update ONLY their own gate in {work_dir}/state.json
-5. Log commands and outputs in {work_dir}/runlog.md
-6. Write a handoff note to {work_dir}/handoffs/{{TIMESTAMP}}-{{ROLE}}.md when done
-
-## Roles to Spawn (in order)
-{roles}
-
-## Sequencing Rules
-- Roles must execute in the order listed above
-- Each role should wait for the prior role's gate to be `pass` before starting
-- After `tests` or `impl` gates complete, run the verify command: `{verify}`
-- After `impl` gate, run the security command: `{security}`
-
-## Reroute Rules
-- If `tests` gate = `fail` → have the implementation teammate fix and retry
-- If `review_security` gate = `changes_requested` → have the implementation teammate fix and retry
-- If `qa` gate = `fail` → have the implementation teammate fix, then re-run tests and qa
-
-## Completion
-When all gates are `pass` (or you reach the target gate), set work status to `done` in state.json.
-
-Start now by creating the team and spawning the first role."#,
-        work_id = work_id,
-        work_dir = work_dir.display(),
-        roles = role_list.join("\n"),
-        verify = state.commands.verify,
-        security = state.commands.security,
-    );
+    for (_, role) in &remaining_roles {
+        crate::context::write_if_enabled(base, &work_dir, role)?;
+    }
+    let prompt = render_teams_prompt(base, &work_dir, &remaining_roles);
 
     // Log the teams run start
     let now = Utc::now();
@@ -247,14 +234,7 @@ Start now by creating the team and spawning the first role."#,
             .collect::<Vec<_>>()
             .join(", "),
     );
-    let runlog_path = work_dir.join("runlog.md");
-    let mut file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&runlog_path)
-        .map_err(|e| format!("failed to open runlog: {}", e))?;
-    std::io::Write::write_all(&mut file, log_entry.as_bytes())
-        .map_err(|e| format!("failed to write runlog: {}", e))?;
+    runlog::append(&work_dir, &log_entry)?;
 
     println!("starting pipeline for {} (teams mode)", work_id);
     println!(
@@ -277,7 +257,11 @@ Start now by creating the team and spawning the first role."#,
     // Try tmux first, fall back to direct
     let session_name = format!("pfm-{}-lead", work_id);
     if crate::adapters::tmux::is_available() {
-        let claude_cmd = format!("claude --print \"{}\"", prompt.replace('"', "\\\""));
+        let claude_cmd = format!(
+            "{}=lead claude --print \"{}\"",
+            crate::approval::AGENT_ENV,
+            prompt.replace('"', "\\\"")
+        );
         match crate::adapters::tmux::new_session(&session_name, &cwd, &claude_cmd) {
             Ok(()) => {
                 println!("started lead agent in tmux session: {}", session_name);
@@ -286,7 +270,8 @@ Start now by creating the team and spawning the first role."#,
 
                 // Poll for completion of all remaining gates
                 let start_time = Utc::now();
-                let target = to_gate.unwrap_or(*GATE_ORDER.last().unwrap());
+                let last = state.pipeline.last().cloned().unwrap_or_default();
+                let target = to_gate.unwrap_or(&last);
                 return wait_for_all_gates(base, work_id, &remaining_roles, target, start_time);
             }
             Err(e) => {
@@ -302,6 +287,7 @@ Start now by creating the team and spawning the first role."#,
     let status = std::process::Command::new("claude")
         .args(["--print", &prompt])
         .current_dir(&cwd)
+        .env(crate::approval::AGENT_ENV, "lead")
         .status()
         .map_err(|e| format!("failed to start claude: {}", e))?;
 
@@ -316,33 +302,76 @@ Start now by creating the team and spawning the first role."#,
     Ok(())
 }
 
+/// Gates (and their roles) that are not yet passed or skipped, up to and including `to_gate`
+pub fn remaining_roles(state: &state::WorkState, to_gate: Option<&str>) -> Vec<(String, Role)> {
+    let mut remaining = Vec::new();
+    for gate_name in &state.pipeline {
+        if let Some(status) = state.gates.get(gate_name) {
+            if !status.is_satisfied() {
+                if let Some(role) = gate_to_role(gate_name) {
+                    remaining.push((gate_name.clone(), role));
+                }
+            }
+        }
+        if let Some(target) = to_gate {
+            if gate_name == target {
+                break;
+            }
+        }
+    }
+    remaining
+}
+
+/// Render the teams lead prompt from `.pfm/prompts/teams_lead.md` (or the built-in template)
+pub fn render_teams_prompt(base: &Path, work_dir: &Path, remaining_roles: &[(String, Role)]) -> String {
+    let roles_dir = base.join(".pfm/roles");
+    let mut context = crate::prompt::context(base, work_dir, None);
+    context["roles"] = remaining_roles
+        .iter()
+        .map(|(gate, role)| {
+            serde_json::json!({
+                "role": role.to_string(),
+                "gate": gate,
+                "spec": roles_dir.join(format!("{}.md", role)).display().to_string(),
+                "context": crate::prompt::context_pack(base, work_dir, role),
+            })
+        })
+        .collect();
+    crate::prompt::render_named(base, "teams_lead.md", &context)
+}
+
 fn wait_for_all_gates(
     base: &Path,
     work_id: &str,
-    remaining_roles: &[(&str, Role)],
+    remaining_roles: &[(String, Role)],
     target_gate: &str,
     _start_time: chrono::DateTime<Utc>,
 ) -> Result<(), String> {
     let work_dir = base.join(".pfm/work").join(work_id);
     let state_path = work_dir.join("state.json");
 
+    let mut tasks_seen = crate::tasks::load(base, &work_dir);
+    let cwd = crate::trace::workspace_dir(base, &read_state(&state_path)?);
+    let gates: Vec<String> = remaining_roles.iter().map(|(gate, _)| gate.clone()).collect();
     let max_polls = 360; // 30 minutes at 5s intervals
     for i in 0..max_polls {
+        crate::trace::observe_gates(&work_dir, &cwd, &gates)?;
         let state = read_state(&state_path)?;
+        crate::tasks::record_completed(base, &work_dir, &mut tasks_seen, "teams")?;
 
         // Check if all remaining gates up to target are terminal
         let all_done = remaining_roles.iter().all(|(gate_name, _)| {
-            if gate_index(gate_name) > gate_index(target_gate) {
+            if gate_index(&state, gate_name) > gate_index(&state, target_gate) {
                 return true; // past target, don't care
             }
             state
                 .gates
                 .get(gate_name)
-                .map(|s| *s == GateStatus::Pass)
+                .map(GateStatus::is_satisfied)
                 .unwrap_or(false)
         });
 
-        if all_done {
+        if all_done && !contracts_rejected(base, &work_dir, remaining_roles)? {
             println!("all target gates passed!");
             print_gate_summary(&state);
             return Ok(());
@@ -352,11 +381,11 @@ fn wait_for_all_gates(
         for (gate_name, _) in remaining_roles {
             if let Some(status) = state.gates.get(gate_name) {
                 if *status == GateStatus::Fail
-                    && *gate_name != "tests"
-                    && *gate_name != "qa"
+                    && gate_name != "tests"
+                    && gate_name != "qa"
                 {
                     // Non-reroutable failure
-                    if *gate_name != "review_security" {
+                    if gate_name != "review_security" {
                         println!("gate '{}' failed — teams agent should handle rerouting", gate_name);
                     }
                 }
@@ -367,7 +396,7 @@ fn wait_for_all_gates(
             let state = read_state(&state_path)?;
             let progress: Vec<String> = remaining_roles
                 .iter()
-                .filter(|(gate_name, _)| gate_index(gate_name) <= gate_index(target_gate))
+                .filter(|(gate_name, _)| gate_index(&state, gate_name) <= gate_index(&state, target_gate))
                 .map(|(gate_name, _)| {
                     let status = state
                         .gates
@@ -389,15 +418,26 @@ fn wait_for_all_gates(
     Ok(())
 }
 
+/// Validate the gates each role finished; true if any completion was rejected
+fn contracts_rejected(base: &Path, work_dir: &Path, remaining_roles: &[(String, Role)]) -> Result<bool, String> {
+    let mut rejected = false;
+    for (_, role) in remaining_roles {
+        let contract = crate::role_spec::load(base, role)?;
+        rejected |= crate::commands::agent::enforce_completion(base, work_dir, role, contract.as_ref())?;
+    }
+    Ok(rejected)
+}
+
 fn print_gate_summary(state: &state::WorkState) {
     println!();
-    for gate_name in GATE_ORDER {
+    for gate_name in &state.pipeline {
         if let Some(status) = state.gates.get(gate_name) {
             let icon = match status {
                 GateStatus::Pass => "OK",
                 GateStatus::Fail => "XX",
                 GateStatus::InProgress => ">>",
                 GateStatus::ChangesRequested => "CR",
+                GateStatus::Skipped => "--",
                 GateStatus::Todo => "  ",
             };
             println!("  [{}] {:<20} {}", icon, gate_name, status);
@@ -406,11 +446,11 @@ fn print_gate_summary(state: &state::WorkState) {
 }
 
 /// Determine the next gate, considering failures and reroute needs
-fn determine_next_gate(state: &state::WorkState) -> Option<&'static str> {
-    for gate_name in GATE_ORDER {
+fn determine_next_gate(state: &state::WorkState) -> Option<&str> {
+    for gate_name in &state.pipeline {
         let status = state.gates.get(gate_name)?;
         match status {
-            GateStatus::Pass => continue,
+            GateStatus::Pass | GateStatus::Skipped => continue,
             _ => return Some(gate_name),
         }
     }
@@ -528,8 +568,9 @@ fn has_recent_handoff(
     false
 }
 
-fn gate_index(gate: &str) -> usize {
-    GATE_ORDER.iter().position(|g| *g == gate).unwrap_or(usize::MAX)
+/// Position of a gate in the work item's pipeline (unknown gates sort last)
+fn gate_index(state: &state::WorkState, gate: &str) -> usize {
+    state.gate_index(gate).unwrap_or(usize::MAX)
 }
 
 #[cfg(test)]
@@ -620,9 +661,28 @@ mod tests {
 
     #[test]
     fn test_gate_index() {
-        assert_eq!(gate_index("prd"), 0);
-        assert_eq!(gate_index("git"), 7);
-        assert_eq!(gate_index("nonexistent"), usize::MAX);
+        let state = make_state();
+        assert_eq!(gate_index(&state, "prd"), 0);
+        assert_eq!(gate_index(&state, "git"), 7);
+        assert_eq!(gate_index(&state, "nonexistent"), usize::MAX);
+    }
+
+    #[test]
+    fn test_custom_gate_runs_its_custom_role() {
+        let mut state = make_state();
+        state.set_pipeline(
+            ["prd", "plan", "env", "tests", "impl", "docs", "review_security", "qa", "git"]
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+        );
+        for gate in ["prd", "plan", "env", "tests", "impl"] {
+            state.gates.set(gate, GateStatus::Pass);
+        }
+        assert_eq!(determine_next_gate(&state), Some("docs"));
+        let remaining = remaining_roles(&state, Some("docs"));
+        assert_eq!(remaining, vec![("docs".to_string(), Role::Custom("docs".into()))]);
+        assert_eq!(gate_index(&state, "docs"), 5);
     }
 
     #[test]
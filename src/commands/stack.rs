@@ -0,0 +1,155 @@
+use crate::output::Format;
+use crate::stack::{self, DetectRule};
+use serde::Serialize;
+use std::path::Path;
+
+/// One stack's detection result for a directory
+#[derive(Debug, Serialize)]
+pub struct EvaluationView {
+    pub stack: String,
+    pub priority: i32,
+    pub rules: Vec<DetectRule>,
+    /// The first rule that matched, null if none did
+    pub matched: Option<DetectRule>,
+    /// True when the rules are the built-in fallback rather than config
+    pub builtin: bool,
+}
+
+/// What `pfm stack detect` reports
+#[derive(Debug, Serialize)]
+pub struct DetectView {
+    pub path: String,
+    /// Null when no stack matched and the default stack would be used
+    pub stack: Option<String>,
+    pub default_stack: String,
+    /// Every stack, highest priority first
+    pub stacks: Vec<EvaluationView>,
+}
+
+/// A configured stack, as listed by `pfm stack list`
+#[derive(Debug, Serialize)]
+pub struct StackView {
+    pub name: String,
+    pub default: bool,
+    pub priority: i32,
+    pub verify: String,
+    pub security: String,
+    pub detect: Vec<DetectRule>,
+}
+
+/// Detect the stack for a directory (defaults to the repo root)
+pub fn detect(base: &Path, path: Option<&Path>, explain: bool, format: Format) -> Result<(), String> {
+    format.emit(&detect_view(base, path)?, |view| print_detect(view, explain))
+}
+
+/// Evaluate every stack's detection rules against a directory
+pub fn detect_view(base: &Path, path: Option<&Path>) -> Result<DetectView, String> {
+    let config = crate::config::load(base)?.config;
+    let target = path.map(|p| base.join(p)).unwrap_or_else(|| base.to_path_buf());
+    if !target.is_dir() {
+        return Err(format!("{} is not a directory", target.display()));
+    }
+
+    let stacks: Vec<EvaluationView> = stack::evaluate(&target, &config)
+        .into_iter()
+        .map(|e| EvaluationView {
+            stack: e.stack,
+            priority: e.priority,
+            rules: e.rules,
+            matched: e.matched,
+            builtin: e.builtin,
+        })
+        .collect();
+    Ok(DetectView {
+        path: path.map(|p| p.display().to_string()).unwrap_or_else(|| ".".into()),
+        stack: stacks.iter().find(|e| e.matched.is_some()).map(|e| e.stack.clone()),
+        default_stack: config.default_stack,
+        stacks,
+    })
+}
+
+fn print_detect(view: &DetectView, explain: bool) {
+    match &view.stack {
+        Some(stack) => println!("{}", stack),
+        None => println!("(none — default stack '{}' would be used)", view.default_stack),
+    }
+
+    if explain {
+        println!();
+        println!("{:<4} {:<16} {:>8}  RULE", "", "STACK", "PRIORITY");
+        for e in &view.stacks {
+            let marker = match &e.matched {
+                Some(_) if view.stack.as_ref() == Some(&e.stack) => "=>",
+                Some(_) => "ok",
+                None => "",
+            };
+            let rule = match &e.matched {
+                Some(rule) => format!("matched {}", rule),
+                None if e.rules.is_empty() => "no detection rules".to_string(),
+                None => "no rule matched".to_string(),
+            };
+            let source = if e.builtin { " (built-in)" } else { "" };
+            println!("{:<4} {:<16} {:>8}  {}{}", marker, e.stack, e.priority, rule, source);
+        }
+    }
+}
+
+/// List configured stacks with their commands and detection rules
+pub fn list(base: &Path, format: Format) -> Result<(), String> {
+    let config = crate::config::load(base)?.config;
+    let stacks: Vec<StackView> = stack::evaluate(base, &config)
+        .into_iter()
+        .map(|e| {
+            let stack_config = &config.stacks[&e.stack];
+            StackView {
+                default: e.stack == config.default_stack,
+                verify: stack_config.verify.clone(),
+                security: stack_config.security.clone(),
+                name: e.stack,
+                priority: e.priority,
+                detect: e.rules,
+            }
+        })
+        .collect();
+    format.emit(&stacks, |stacks| print_list(stacks))
+}
+
+fn print_list(stacks: &[StackView]) {
+    for stack in stacks {
+        let default = if stack.default { " (default)" } else { "" };
+        println!("{}{}  [priority {}]", stack.name, default, stack.priority);
+        println!("  verify:   {}", stack.verify);
+        println!("  security: {}", stack.security);
+        for rule in &stack.detect {
+            println!("  detect:   {}", rule);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::init;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_view_serializes_matched_rule() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join("api")).unwrap();
+        fs::write(dir.path().join("api/Cargo.toml"), "[package]\n").unwrap();
+
+        let view = detect_view(dir.path(), Some(Path::new("api"))).unwrap();
+        assert_eq!(view.stack.as_deref(), Some("rust"));
+        let json: serde_json::Value = serde_json::from_str(&crate::output::to_json(&view).unwrap()).unwrap();
+        assert_eq!(json["path"], "api");
+        let rust = json["stacks"].as_array().unwrap().iter().find(|s| s["stack"] == "rust").unwrap();
+        assert_eq!(rust["matched"], serde_json::json!({"file_exists": "Cargo.toml"}));
+
+        let view = detect_view(dir.path(), None).unwrap();
+        assert_eq!(view.stack, None);
+        assert!(view.stacks.iter().all(|e| e.matched.is_none()));
+        assert!(detect_view(dir.path(), Some(Path::new("missing"))).unwrap_err().contains("not a directory"));
+    }
+}
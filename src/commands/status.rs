@@ -1,76 +1,388 @@
-use crate::state::{self, read_state, GATE_ORDER};
+use crate::acceptance::Verdict;
+use crate::output::Format;
+use crate::state::{self, read_state, Commands, Component, GateStatus, Note, Role, WorkStatus, WorkType, Workspace};
+use crate::tasks::Progress;
+use serde::Serialize;
 use std::path::Path;
 
-/// Show status for a specific work item
-pub fn show(base: &Path, work_id: &str) -> Result<(), String> {
-    let work_dir = base.join(".pfm/work").join(work_id);
-    if !work_dir.exists() {
-        return Err(format!("work item {} not found", work_id));
+/// One gate in pipeline order
+#[derive(Debug, Serialize)]
+pub struct GateView {
+    pub name: String,
+    pub status: GateStatus,
+    /// Approval gates only: false when the decision record can't be verified with this
+    /// machine's key. Null for other gates and undecided approvals.
+    pub verified: Option<bool>,
+}
+
+/// One acceptance criterion and its latest QA result (null if none recorded)
+#[derive(Debug, Serialize)]
+pub struct CriterionView {
+    pub id: String,
+    pub text: String,
+    pub result: Option<Verdict>,
+}
+
+/// An upstream work item and its status (null if it doesn't exist)
+#[derive(Debug, Serialize)]
+pub struct DependencyView {
+    pub id: String,
+    pub status: Option<WorkStatus>,
+}
+
+/// A child of an epic, with its own children if it is an epic too
+#[derive(Debug, Serialize)]
+pub struct ChildView {
+    pub id: String,
+    pub title: String,
+    pub status: WorkStatus,
+    pub gates_passed: usize,
+    pub gates_total: usize,
+    pub tasks: Option<Progress>,
+    pub children: Vec<ChildView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcceptanceView {
+    pub total: usize,
+    pub with_results: usize,
+    pub pass: usize,
+    pub fail: usize,
+    pub criteria: Vec<CriterionView>,
+}
+
+/// Everything `pfm status` reports about a work item
+#[derive(Debug, Serialize)]
+pub struct StatusView {
+    pub id: String,
+    pub title: String,
+    pub repo: String,
+    pub branch: String,
+    pub status: WorkStatus,
+    #[serde(rename = "type")]
+    pub work_type: WorkType,
+    pub owner: Role,
+    pub updated_at: String,
+    /// Where the item is archived; null while it is active
+    pub archived: Option<String>,
+    pub priority: Option<String>,
+    pub labels: Vec<String>,
+    pub epic: bool,
+    /// The epic this item was split from
+    pub parent: Option<String>,
+    /// Child progress tree (epics only)
+    pub children: Vec<ChildView>,
+    pub depends_on: Vec<DependencyView>,
+    pub gates: Vec<GateView>,
+    /// Null when `tasks.md` has no tasks yet
+    pub tasks: Option<Progress>,
+    pub workspace: Workspace,
+    pub commands: Commands,
+    pub components: Vec<Component>,
+    /// Null when `acceptance.md` lists no criteria
+    pub acceptance: Option<AcceptanceView>,
+    pub notes: Vec<Note>,
+}
+
+/// Collect the status view for a work item
+pub fn view(base: &Path, work_id: &str) -> Result<StatusView, String> {
+    let (work_dir, archived) = crate::commands::lifecycle::locate(base, work_id)
+        .ok_or_else(|| format!("work item {} not found", work_id))?;
+
+    let mut state = read_state(&work_dir.join("state.json"))?;
+    // Approval gates show what their sign-off records say, not what state.json claims
+    let approval_gates = crate::approval::required(base, &state);
+    crate::approval::apply(&work_dir, &mut state, &approval_gates);
+    let key = crate::approval::read_key();
+    let gates = state
+        .pipeline
+        .iter()
+        .filter_map(|name| {
+            state.gates.get(name).map(|status| GateView {
+                name: name.clone(),
+                status: status.clone(),
+                verified: approval_gates
+                    .contains(name)
+                    .then(|| crate::approval::verification(&work_dir, &state.id, name, key.as_deref()))
+                    .flatten(),
+            })
+        })
+        .collect();
+
+    let graph = crate::deps::Graph::load(base)?;
+    let depends_on = state
+        .depends_on
+        .iter()
+        .map(|id| DependencyView {
+            id: id.clone(),
+            status: graph.nodes.get(id).map(|n| n.status.clone()),
+        })
+        .collect();
+
+    let mut visited = vec![state.id.clone()];
+    let children = child_views(base, &state, &mut visited);
+
+    let coverage = crate::acceptance::coverage(&work_dir);
+    let acceptance = (coverage.total() > 0).then(|| AcceptanceView {
+        total: coverage.total(),
+        with_results: coverage.total() - coverage.missing().len(),
+        pass: coverage.count(Verdict::Pass),
+        fail: coverage.count(Verdict::Fail),
+        criteria: coverage
+            .criteria
+            .iter()
+            .map(|(criterion, verdict)| CriterionView {
+                id: criterion.id.clone(),
+                text: criterion.text.clone(),
+                result: *verdict,
+            })
+            .collect(),
+    });
+
+    Ok(StatusView {
+        tasks: crate::tasks::progress(base, &work_dir),
+        id: state.id,
+        title: state.title,
+        repo: state.repo,
+        branch: state.branch,
+        status: state.status,
+        work_type: state.work_type,
+        owner: state.owner,
+        updated_at: state.updated_at,
+        archived: archived.then(|| work_dir.strip_prefix(base).unwrap_or(&work_dir).display().to_string()),
+        priority: state.priority,
+        labels: state.labels,
+        epic: state.epic,
+        parent: state.parent,
+        children,
+        depends_on,
+        gates,
+        workspace: state.workspace,
+        commands: state.commands,
+        components: state.components,
+        acceptance,
+        notes: state.notes,
+    })
+}
+
+/// The progress tree below an epic. `visited` guards against hand-edited loops.
+fn child_views(base: &Path, epic: &state::WorkState, visited: &mut Vec<String>) -> Vec<ChildView> {
+    let mut views = Vec::new();
+    for child in crate::commands::epic::children(base, epic) {
+        if visited.contains(&child.id) {
+            continue;
+        }
+        visited.push(child.id.clone());
+        let dir = crate::commands::lifecycle::locate(base, &child.id).map(|(dir, _)| dir);
+        views.push(ChildView {
+            gates_passed: child.pipeline.iter().filter(|g| child.gates.get(g).is_some_and(GateStatus::is_satisfied)).count(),
+            gates_total: child.pipeline.len(),
+            tasks: dir.and_then(|dir| crate::tasks::progress(base, &dir)),
+            children: child_views(base, &child, visited),
+            id: child.id,
+            title: child.title,
+            status: child.status,
+        });
     }
+    views
+}
 
-    let state = read_state(&work_dir.join("state.json"))?;
+/// Show status for a specific work item
+pub fn show(base: &Path, work_id: &str, format: Format) -> Result<(), String> {
+    format.emit(&view(base, work_id)?, print_table)
+}
 
-    println!("Work Item: {}", state.id);
-    println!("Title:     {}", state.title);
-    println!("Repo:      {}", state.repo);
-    println!("Branch:    {}", state.branch);
-    println!("Status:    {}", state.status);
-    println!("Owner:     {}", state.owner);
-    println!("Updated:   {}", state.updated_at);
+fn print_table(view: &StatusView) {
+    println!("Work Item: {}", view.id);
+    println!("Title:     {}", view.title);
+    println!("Repo:      {}", view.repo);
+    println!("Branch:    {}", view.branch);
+    match &view.archived {
+        Some(archive) => println!("Status:    {} (archived at {})", view.status, archive),
+        None => println!("Status:    {}", view.status),
+    }
+    println!("Type:      {}", view.work_type);
+    println!("Owner:     {}", view.owner);
+    println!("Updated:   {}", view.updated_at);
+    if let Some(priority) = &view.priority {
+        println!("Priority:  {}", priority);
+    }
+    if !view.labels.is_empty() {
+        println!("Labels:    {}", view.labels.join(", "));
+    }
+    if let Some(parent) = &view.parent {
+        println!("Epic:      {}", parent);
+    }
+    if !view.depends_on.is_empty() {
+        let deps: Vec<String> = view
+            .depends_on
+            .iter()
+            .map(|d| format!("{} ({})", d.id, d.status.as_ref().map(|s| s.to_string()).unwrap_or_else(|| "missing".into())))
+            .collect();
+        println!("Depends:   {}", deps.join(", "));
+    }
     println!();
 
     println!("Gates:");
-    for gate_name in GATE_ORDER {
-        if let Some(status) = state.gates.get(gate_name) {
-            let indicator = match status {
-                state::GateStatus::Todo => "  ",
-                state::GateStatus::InProgress => ">>",
-                state::GateStatus::Pass => "OK",
-                state::GateStatus::Fail => "XX",
-                state::GateStatus::ChangesRequested => "CR",
-            };
-            println!("  [{}] {:<20} {}", indicator, gate_name, status);
+    for gate in &view.gates {
+        let indicator = match gate.status {
+            state::GateStatus::Todo => "  ",
+            state::GateStatus::InProgress => ">>",
+            state::GateStatus::Pass => "OK",
+            state::GateStatus::Fail => "XX",
+            state::GateStatus::ChangesRequested => "CR",
+            state::GateStatus::Skipped => "--",
+        };
+        let unverified = if gate.verified == Some(false) { "  (unverified — signed with another key)" } else { "" };
+        println!("  [{}] {:<20} {}{}", indicator, gate.name, gate.status, unverified);
+    }
+
+    if let Some(progress) = &view.tasks {
+        println!();
+        println!("Tasks:     {}", progress);
+    }
+
+    if view.epic {
+        println!();
+        let done = view.children.iter().filter(|c| c.status == WorkStatus::Done).count();
+        if view.children.is_empty() {
+            println!("Children:  none yet — `pfm work split {}` once tasks.md is written", view.id);
+        } else {
+            println!("Children:  {}/{} done", done, view.children.len());
+            print_tree(&view.children, "  ");
         }
     }
 
-    if !state.workspace.worktree.is_empty()
-        || !state.workspace.tmux_session.is_empty()
-        || !state.workspace.container.is_empty()
-    {
+    let workspace = &view.workspace;
+    if !workspace.worktree.is_empty() || !workspace.tmux_session.is_empty() || !workspace.container.is_empty() {
         println!();
         println!("Workspace:");
-        if !state.workspace.worktree.is_empty() {
-            println!("  worktree: {}", state.workspace.worktree);
+        if !workspace.worktree.is_empty() {
+            println!("  worktree: {}", workspace.worktree);
         }
-        if !state.workspace.tmux_session.is_empty() {
-            println!("  tmux:     {}", state.workspace.tmux_session);
+        if !workspace.tmux_session.is_empty() {
+            println!("  tmux:     {}", workspace.tmux_session);
         }
-        if !state.workspace.container.is_empty() {
-            println!("  container: {}", state.workspace.container);
+        if !workspace.container.is_empty() {
+            println!("  container: {}", workspace.container);
         }
     }
 
-    if !state.commands.verify.is_empty() || !state.commands.security.is_empty() {
+    if !view.commands.verify.is_empty() || !view.commands.security.is_empty() {
         println!();
         println!("Commands:");
-        if !state.commands.verify.is_empty() {
-            println!("  verify:   {}", state.commands.verify);
+        if !view.commands.verify.is_empty() {
+            println!("  verify:   {}", view.commands.verify);
+        }
+        if !view.commands.security.is_empty() {
+            println!("  security: {}", view.commands.security);
         }
-        if !state.commands.security.is_empty() {
-            println!("  security: {}", state.commands.security);
+        if !view.commands.qa_smoke.is_empty() {
+            println!("  qa_smoke: {}", view.commands.qa_smoke);
         }
-        if !state.commands.qa_smoke.is_empty() {
-            println!("  qa_smoke: {}", state.commands.qa_smoke);
+    }
+
+    if !view.components.is_empty() {
+        println!();
+        println!("Components:");
+        for component in &view.components {
+            println!("  {:<16} {:<24} {}", component.name, component.path, component.stack);
+            if !component.commands.verify.is_empty() {
+                println!("    verify:   {}", component.commands.verify);
+            }
+            if !component.commands.security.is_empty() {
+                println!("    security: {}", component.commands.security);
+            }
+        }
+    }
+
+    if let Some(acceptance) = &view.acceptance {
+        println!();
+        println!(
+            "Acceptance: {}/{} with results ({} pass, {} fail)",
+            acceptance.with_results, acceptance.total, acceptance.pass, acceptance.fail,
+        );
+        for criterion in &acceptance.criteria {
+            let indicator = match criterion.result {
+                Some(Verdict::Pass) => "OK",
+                Some(Verdict::Fail) => "XX",
+                None => "  ",
+            };
+            println!("  [{}] {:<6} {}", indicator, criterion.id, criterion.text);
         }
     }
 
-    if !state.notes.is_empty() {
+    if !view.notes.is_empty() {
         println!();
         println!("Notes:");
-        for note in &state.notes {
+        for note in &view.notes {
             println!("  - {}", note);
         }
     }
+}
+
+fn print_tree(children: &[ChildView], indent: &str) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let indicator = match child.status {
+            WorkStatus::Done => "OK",
+            WorkStatus::Blocked => "!!",
+            WorkStatus::InProgress => ">>",
+        };
+        let mut detail = format!("{}/{} gates", child.gates_passed, child.gates_total);
+        if let Some(tasks) = child.tasks {
+            detail.push_str(&format!(", {}/{} tasks", tasks.done, tasks.total));
+        }
+        println!(
+            "{}{} [{}] {:<16} {:<12} {}  ({})",
+            indent,
+            if last { "└─" } else { "├─" },
+            indicator,
+            child.id,
+            child.status.to_string(),
+            child.title,
+            detail
+        );
+        print_tree(&child.children, &format!("{}{}", indent, if last { "   " } else { "│  " }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::lifecycle::{self, Action};
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
 
-    Ok(())
+    #[test]
+    fn test_status_view_serializes_gates_in_pipeline_order() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Export", Some("FEAT-ST"), Some("rust")).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&crate::output::to_json(&view(dir.path(), "FEAT-ST").unwrap()).unwrap()).unwrap();
+        assert_eq!(json["id"], "FEAT-ST");
+        assert_eq!(json["status"], "in_progress");
+        assert_eq!(json["gates"][0], serde_json::json!({"name": "prd", "status": "todo", "verified": null}));
+        assert_eq!(json["gates"][7]["name"], "git");
+        assert_eq!(json["tasks"], serde_json::Value::Null);
+        assert_eq!(json["commands"]["verify"], "cargo test");
+        assert_eq!(json["archived"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_status_finds_archived_items() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Export", Some("FEAT-AR"), Some("rust")).unwrap();
+        lifecycle::run(dir.path(), "FEAT-AR", Action::Close, "shipped", false).unwrap();
+        lifecycle::run(dir.path(), "FEAT-AR", Action::Archive, "cleanup", false).unwrap();
+
+        let archived = view(dir.path(), "FEAT-AR").unwrap();
+        assert_eq!(archived.status, WorkStatus::Done);
+        let archive = archived.archived.unwrap();
+        assert!(archive.starts_with(".pfm/archive/") && archive.ends_with("/FEAT-AR"));
+        assert!(view(dir.path(), "FEAT-XX").unwrap_err().contains("not found"));
+    }
 }
@@ -0,0 +1,79 @@
+use crate::output::Format;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// One row of the traceability matrix
+#[derive(Debug, Serialize)]
+pub struct RequirementView {
+    pub id: String,
+    pub text: String,
+    pub criteria: Vec<String>,
+    pub tests: Vec<String>,
+    pub sources: Vec<String>,
+}
+
+/// What `pfm trace` reports
+#[derive(Debug, Serialize)]
+pub struct TraceView {
+    /// Where the markdown report was written
+    pub report: String,
+    pub requirements: Vec<RequirementView>,
+    /// Test file → requirements it traces to
+    pub tests: BTreeMap<String, Vec<String>>,
+    pub untested: Vec<String>,
+    pub orphan_tests: Vec<String>,
+}
+
+/// Build the traceability report for a work item and write it to `artifacts/trace.md`
+pub fn run(base: &Path, work_id: &str, format: Format) -> Result<(), String> {
+    let work_dir = base.join(".pfm/work").join(work_id);
+    if !work_dir.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+
+    let (path, trace) = crate::trace::write(base, &work_dir)?;
+    let owned = |items: Vec<&str>| items.into_iter().map(str::to_string).collect::<Vec<_>>();
+    let view = TraceView {
+        report: path.display().to_string(),
+        requirements: trace
+            .requirements
+            .iter()
+            .map(|(id, text)| RequirementView {
+                id: id.clone(),
+                text: text.clone(),
+                criteria: trace.criteria.get(id).cloned().unwrap_or_default(),
+                tests: owned(trace.tests_for(id)),
+                sources: owned(trace.sources_for(id)),
+            })
+            .collect(),
+        tests: trace
+            .tests
+            .iter()
+            .map(|(file, reqs)| (file.clone(), reqs.iter().cloned().collect()))
+            .collect(),
+        untested: owned(trace.untested()),
+        orphan_tests: owned(trace.orphan_tests()),
+    };
+    format.emit(&view, |view| print_summary(view, trace.sources.len()))
+}
+
+fn print_summary(view: &TraceView, sources: usize) {
+    println!(
+        "{} requirement(s), {} test file(s), {} source file(s)",
+        view.requirements.len(),
+        view.tests.len(),
+        sources
+    );
+    for id in &view.untested {
+        println!("  [!!] {} has no test", id);
+    }
+    for file in &view.orphan_tests {
+        println!("  [!!] {} traces to no requirement", file);
+    }
+    if view.untested.is_empty() && view.orphan_tests.is_empty() {
+        println!("  [OK] every requirement has a test and every test has a requirement");
+    }
+    println!();
+    println!("wrote {}", view.report);
+}
@@ -1,7 +1,11 @@
-use crate::config::read_config;
-use crate::state::{Commands, WorkState, write_state};
+use crate::config::PfmConfig;
+use crate::output::Format;
+use crate::state::{gate_to_role, Commands, Component, GateStatus, Role, WorkState, WorkStatus, WorkType, read_state, write_state};
+use crate::tasks::Progress;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Create a new work item
@@ -10,37 +14,34 @@ pub fn new_work(
     title: &str,
     id: Option<&str>,
     stack: Option<&str>,
+) -> Result<String, String> {
+    new_typed(base, title, id, stack, WorkType::Feature)
+}
+
+/// Create a work item of the given type, with that type's pipeline, templates and ID prefix
+pub fn new_typed(
+    base: &Path,
+    title: &str,
+    id: Option<&str>,
+    stack: Option<&str>,
+    work_type: WorkType,
 ) -> Result<String, String> {
     let pfm_dir = base.join(".pfm");
     if !pfm_dir.exists() {
         return Err("not initialized — run `pfm init` first".into());
     }
 
-    // Generate ID if not provided
-    let work_id = match id {
-        Some(id) => id.to_string(),
-        None => {
-            let short = title
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == ' ')
-                .collect::<String>()
-                .split_whitespace()
-                .take(3)
-                .collect::<Vec<_>>()
-                .join("-")
-                .to_lowercase();
-            format!("FEAT-{}", if short.is_empty() { "work".to_string() } else { short })
-        }
-    };
+    // Read effective config (global → repo → local → env → CLI) for ID scheme and stack commands
+    let config = crate::config::load(base)?.config;
 
-    let work_dir = pfm_dir.join("work").join(&work_id);
-    if work_dir.exists() {
-        return Err(format!("work item {} already exists", work_id));
+    // Explicit IDs must be safe and unused
+    if let Some(id) = id {
+        crate::ids::validate(id)?;
+        if let Some((archived, true)) = crate::commands::lifecycle::locate(base, id) {
+            return Err(format!("work item {} already exists in the archive ({})", id, archived.display()));
+        }
     }
-
-    // Read config for stack commands
-    let config = read_config(&pfm_dir.join("config.json"))?;
-    let detected = detect_stack(base);
+    let detected = detect_stack(base, &config);
     let stack_name = stack
         .or(detected.as_deref())
         .unwrap_or(&config.default_stack);
@@ -53,24 +54,51 @@ pub fn new_work(
         qa_smoke: String::new(),
     };
 
+    // Every custom gate in the pipeline needs a role spec to hand its agent, unless a person signs it off
+    let pipeline = config.pipeline_for(work_type);
+    for gate in pipeline.iter().filter(|g| !config.approval_gates.contains(g)) {
+        let role = gate_to_role(gate).ok_or_else(|| format!("invalid gate in pipeline: {}", gate))?;
+        crate::commands::agent::require_role_spec(base, &role)?;
+    }
+
     // Detect repo name
     let repo = detect_repo_name(base);
 
-    // Create work directory and subdirs
+    // Everything is checked: claim the ID by creating its work directory. Generated IDs
+    // move past collisions; an explicit ID someone else just took is an error.
+    let work_id = match id {
+        Some(id) => {
+            if !crate::ids::claim(base, id)? {
+                return Err(format!("work item {} already exists", id));
+            }
+            id.to_string()
+        }
+        None => crate::ids::allocate(base, &config.ids, &config.prefix_for(work_type), title)?,
+    };
+    let work_dir = pfm_dir.join("work").join(&work_id);
+
+    // Create subdirs
     fs::create_dir_all(work_dir.join("handoffs"))
         .map_err(|e| format!("failed to create work dir: {}", e))?;
     fs::create_dir_all(work_dir.join("artifacts"))
         .map_err(|e| format!("failed to create artifacts dir: {}", e))?;
 
     // Write state.json
-    let state = WorkState::new(&work_id, title, &repo, commands);
+    let mut state = WorkState::new(&work_id, title, &repo, commands);
+    state.work_type = work_type;
+    state.approval_gates = pipeline.iter().filter(|g| config.approval_gates.contains(g)).cloned().collect();
+    state.set_pipeline(pipeline);
+    // The first gate's role starts the work, which isn't the PRD agent for every type
+    if let Some(role) = state.pipeline.first().and_then(|gate| gate_to_role(gate)) {
+        state.owner = role;
+    }
     write_state(&work_dir.join("state.json"), &state)?;
 
-    // Copy templates (with placeholder replacement)
-    let templates_dir = pfm_dir.join("templates");
+    // Copy templates (with placeholder replacement), preferring the type's template set
+    let template_set = config.templates_for(work_type);
     let template_files = ["prd.md", "acceptance.md", "plan.md", "tasks.md", "runlog.md", "qa.md"];
     for filename in &template_files {
-        let template_path = templates_dir.join(filename);
+        let template_path = crate::templates::template_path(base, template_set.as_deref(), filename);
         if template_path.exists() {
             let content = fs::read_to_string(&template_path)
                 .map_err(|e| format!("failed to read template {}: {}", filename, e))?;
@@ -102,6 +130,9 @@ pub fn new_work(
         "default"
     };
     println!("created work item: {}", work_id);
+    if work_type != WorkType::Feature {
+        println!("  type: {} (pipeline {})", work_type, state.pipeline.join(" → "));
+    }
     println!("  directory: {}", work_dir.display());
     println!("  branch: {}", branch);
     println!("  stack: {} ({})", stack_name, how);
@@ -109,93 +140,427 @@ pub fn new_work(
     Ok(work_id)
 }
 
-/// List all work items
-pub fn list_work(base: &Path) -> Result<(), String> {
-    let work_dir = base.join(".pfm/work");
-    if !work_dir.exists() {
-        println!("no work items found");
-        return Ok(());
+/// Add a monorepo component (sub-project with its own stack and checks) to a work item
+pub fn add_component(
+    base: &Path,
+    work_id: &str,
+    name: &str,
+    path: &str,
+    stack: Option<&str>,
+) -> Result<(), String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let mut state = read_state(&state_path)?;
+    if state.components.iter().any(|c| c.name == name) {
+        return Err(format!("component {} already exists on {}", name, work_id));
     }
 
-    let mut entries: Vec<_> = fs::read_dir(&work_dir)
-        .map_err(|e| format!("failed to read work dir: {}", e))?
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_dir())
-        .collect();
+    let component_dir = base.join(path);
+    if !component_dir.is_dir() {
+        return Err(format!("component path {} is not a directory", component_dir.display()));
+    }
+
+    let config = crate::config::load(base)?.config;
+    let detected = detect_stack(&component_dir, &config);
+    let stack_name = stack
+        .or(detected.as_deref())
+        .unwrap_or(&config.default_stack)
+        .to_string();
+    let stack_config = config.stacks.get(&stack_name)
+        .ok_or_else(|| format!("unknown stack: {}", stack_name))?;
+
+    state.components.push(Component {
+        name: name.to_string(),
+        path: path.trim_end_matches('/').to_string(),
+        stack: stack_name.clone(),
+        commands: Commands {
+            verify: stack_config.verify.clone(),
+            security: stack_config.security.clone(),
+            qa_smoke: String::new(),
+        },
+    });
+    state.touch();
+    write_state(&state_path, &state)?;
+
+    let how = if stack.is_some() {
+        "specified"
+    } else if detected.is_some() {
+        "detected"
+    } else {
+        "default"
+    };
+    println!("added component {} to {}", name, work_id);
+    println!("  path: {}", path);
+    println!("  stack: {} ({})", stack_name, how);
+    Ok(())
+}
 
-    if entries.is_empty() {
-        println!("no work items found");
-        return Ok(());
+/// Remove a component from a work item
+pub fn remove_component(base: &Path, work_id: &str, name: &str) -> Result<(), String> {
+    let state_path = base.join(".pfm/work").join(work_id).join("state.json");
+    if !state_path.exists() {
+        return Err(format!("work item {} not found", work_id));
+    }
+    let mut state = read_state(&state_path)?;
+    let before = state.components.len();
+    state.components.retain(|c| c.name != name);
+    if state.components.len() == before {
+        return Err(format!("component {} not found on {}", name, work_id));
     }
+    state.touch();
+    write_state(&state_path, &state)?;
+    println!("removed component {} from {}", name, work_id);
+    Ok(())
+}
 
-    entries.sort_by_key(|e| e.file_name());
+/// One row of `pfm work list`. Status, owner and updated_at are null if `state.json` can't be read.
+#[derive(Debug, Serialize)]
+pub struct WorkSummary {
+    pub id: String,
+    pub title: String,
+    pub status: Option<WorkStatus>,
+    pub owner: Option<Role>,
+    /// Null when `tasks.md` has no tasks yet
+    pub tasks: Option<Progress>,
+    pub updated_at: Option<String>,
+    /// True for items under `.pfm/archive/`
+    pub archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
 
-    println!("{:<20} {:<15} {:<15} {}", "ID", "STATUS", "OWNER", "TITLE");
-    println!("{}", "-".repeat(70));
+/// Order of `pfm work list` rows
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SortKey {
+    #[default]
+    Id,
+    /// Most recently updated first
+    Updated,
+    Status,
+    Title,
+}
 
-    for entry in entries {
-        let state_path = entry.path().join("state.json");
-        if state_path.exists() {
-            match crate::state::read_state(&state_path) {
-                Ok(state) => {
-                    println!(
-                        "{:<20} {:<15} {:<15} {}",
-                        state.id, state.status, state.owner, state.title
-                    );
-                }
-                Err(_) => {
-                    println!(
-                        "{:<20} {:<15} {:<15} {}",
-                        entry.file_name().to_string_lossy(),
-                        "???",
-                        "???",
-                        "(invalid state.json)"
-                    );
-                }
-            }
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(SortKey::Id),
+            "updated" => Ok(SortKey::Updated),
+            "status" => Ok(SortKey::Status),
+            "title" => Ok(SortKey::Title),
+            other => Err(format!("unknown sort key '{}' (expected id, updated, status or title)", other)),
         }
     }
+}
 
-    Ok(())
+/// Which work items `pfm work list` shows, and in what order
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    pub status: Option<WorkStatus>,
+    pub owner: Option<Role>,
+    /// Gate statuses that must all match
+    pub gates: Vec<(String, GateStatus)>,
+    /// Only items updated at or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub sort: SortKey,
+    /// Include archived items (hidden by default)
+    pub archived: bool,
 }
 
-/// Auto-detect stack from repo contents.
-/// Checks for marker files in priority order:
-///   1. Gemfile + config/routes.rb (or bin/rails) → rails
-///   2. package.json with react-native dep → react_native
-///   3. package.json → cli_node
-///   4. Cargo.toml → rust
-///   5. Gemfile → cli_ruby
-fn detect_stack(base: &Path) -> Option<String> {
-    let has_gemfile = base.join("Gemfile").exists();
-    let has_package_json = base.join("package.json").exists();
-    let has_cargo_toml = base.join("Cargo.toml").exists();
-    let has_rails = base.join("config/routes.rb").exists()
-        || base.join("bin/rails").exists()
-        || base.join("config/application.rb").exists();
-
-    if has_gemfile && has_rails {
-        return Some("rails".into());
-    }
-
-    if has_package_json {
-        if let Ok(content) = fs::read_to_string(base.join("package.json")) {
-            if content.contains("react-native") {
-                return Some("react_native".into());
-            }
+impl ListFilter {
+    /// Build a filter from command-line values (`--gate qa=fail`, `--since 7d`)
+    pub fn parse(
+        status: Option<&str>,
+        owner: Option<&str>,
+        gates: &[String],
+        since: Option<&str>,
+        sort: &str,
+    ) -> Result<Self, String> {
+        let gates = gates
+            .iter()
+            .map(|pair| {
+                let (gate, status) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("invalid --gate '{}' (expected GATE=STATUS, e.g. qa=fail)", pair))?;
+                Ok((gate.to_string(), parse_enum(status, "gate status")?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ListFilter {
+            status: status.map(|s| parse_enum(s, "status")).transpose()?,
+            owner: owner.map(str::parse).transpose()?,
+            gates,
+            since: since.map(|s| parse_since(s, Utc::now())).transpose()?,
+            sort: sort.parse()?,
+            archived: false,
+        })
+    }
+
+    /// True if any condition narrows the list
+    fn narrows(&self) -> bool {
+        self.status.is_some() || self.owner.is_some() || !self.gates.is_empty() || self.since.is_some()
+    }
+
+    fn matches(&self, state: &WorkState) -> bool {
+        self.status.as_ref().map(|s| *s == state.status).unwrap_or(true)
+            && self.owner.as_ref().map(|o| *o == state.owner).unwrap_or(true)
+            && self.gates.iter().all(|(gate, status)| state.gates.get(gate) == Some(status))
+            && self.since.map(|since| updated_at(state).map(|t| t >= since).unwrap_or(false)).unwrap_or(true)
+    }
+}
+
+/// Parse a snake_case enum value the way it appears in `state.json`
+fn parse_enum<T: serde::de::DeserializeOwned>(value: &str, what: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("unknown {} '{}'", what, value))
+}
+
+/// `--since` as a duration back from `now` (`30m`, `12h`, `7d`, `2w`) or a date (`2026-03-01`)
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc());
+    }
+    let invalid = || format!("invalid --since '{}' (expected e.g. 30m, 12h, 7d, 2w or YYYY-MM-DD)", value);
+    let split = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let amount: i64 = value[..split].parse().map_err(|_| invalid())?;
+    let span = match &value[split..] {
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        "w" => chrono::Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    span.and_then(|span| now.checked_sub_signed(span))
+        .ok_or_else(|| format!("invalid --since '{}': too far in the past", value))
+}
+
+fn updated_at(state: &WorkState) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&state.updated_at).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// A work item directory and its state, or why `state.json` couldn't be read
+pub type LoadedItem = (PathBuf, Result<WorkState, String>);
+
+/// Every work item directory with its parsed state, sorted by directory name,
+/// followed by archived items if `archived` is set
+pub fn load_all(base: &Path, archived: bool) -> Result<Vec<LoadedItem>, String> {
+    let work_dir = base.join(".pfm/work");
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if work_dir.exists() {
+        dirs = fs::read_dir(&work_dir)
+            .map_err(|e| format!("failed to read work dir: {}", e))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.join("state.json").exists())
+            .collect();
+        dirs.sort();
+    }
+    if archived {
+        dirs.extend(crate::commands::lifecycle::archived_dirs(base));
+    }
+    Ok(dirs
+        .into_iter()
+        .map(|dir| {
+            let state = read_state(&dir.join("state.json"));
+            (dir, state)
+        })
+        .collect())
+}
+
+/// Summaries of the work items that pass `filter`, in its sort order. Items with an
+/// unreadable `state.json` are listed (last) only when nothing narrows the list.
+pub fn summaries(base: &Path, filter: &ListFilter) -> Result<Vec<WorkSummary>, String> {
+    let archive_dir = base.join(".pfm/archive");
+    let mut valid: Vec<(PathBuf, WorkState)> = Vec::new();
+    let mut invalid: Vec<WorkSummary> = Vec::new();
+    for (dir, state) in load_all(base, filter.archived)? {
+        match state {
+            Ok(state) if filter.matches(&state) => valid.push((dir, state)),
+            Ok(_) => {}
+            Err(_) if filter.narrows() => {}
+            Err(e) => invalid.push(WorkSummary {
+                id: dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                title: String::new(),
+                status: None,
+                owner: None,
+                tasks: None,
+                updated_at: None,
+                archived: dir.starts_with(&archive_dir),
+                error: Some(e),
+            }),
         }
-        return Some("cli_node".into());
     }
 
-    if has_cargo_toml {
-        return Some("rust".into());
+    match filter.sort {
+        SortKey::Id => valid.sort_by(|(_, a), (_, b)| a.id.cmp(&b.id)),
+        SortKey::Updated => valid.sort_by_key(|(_, s)| std::cmp::Reverse(updated_at(s))),
+        SortKey::Status => valid.sort_by_key(|(_, s)| (s.status.to_string(), s.id.clone())),
+        SortKey::Title => valid.sort_by_key(|(_, s)| (s.title.to_lowercase(), s.id.clone())),
+    }
+
+    let mut items: Vec<WorkSummary> = valid
+        .into_iter()
+        .map(|(dir, state)| WorkSummary {
+            tasks: crate::tasks::progress(base, &dir),
+            id: state.id,
+            title: state.title,
+            status: Some(state.status),
+            owner: Some(state.owner),
+            updated_at: Some(state.updated_at),
+            archived: dir.starts_with(&archive_dir),
+            error: None,
+        })
+        .collect();
+    items.extend(invalid);
+    Ok(items)
+}
+
+/// List work items
+pub fn list_work(base: &Path, filter: &ListFilter, format: Format) -> Result<(), String> {
+    let items = summaries(base, filter)?;
+    format.emit(&items, |items| print_list(items, filter.narrows()))
+}
+
+fn print_list(items: &[WorkSummary], filtered: bool) {
+    if items.is_empty() {
+        println!("{}", if filtered { "no work items match" } else { "no work items found" });
+        return;
+    }
+
+    println!("{:<20} {:<15} {:<15} {:<8} TITLE", "ID", "STATUS", "OWNER", "TASKS");
+    println!("{}", "-".repeat(80));
+    for item in items {
+        let unknown = || "???".to_string();
+        println!(
+            "{:<20} {:<15} {:<15} {:<8} {}",
+            item.id,
+            item.status.as_ref().map(|s| s.to_string()).unwrap_or_else(unknown),
+            item.owner.as_ref().map(|o| o.to_string()).unwrap_or_else(unknown),
+            item.tasks.map(|p| format!("{}/{}", p.done, p.total)).unwrap_or_else(|| "-".into()),
+            match (&item.error, item.archived) {
+                (Some(_), _) => "(invalid state.json)".to_string(),
+                (None, true) => format!("{} (archived)", item.title),
+                (None, false) => item.title.clone(),
+            },
+        );
+    }
+}
+
+/// One line that matched a search. `line` is 1-based and null for the title.
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    pub source: String,
+    pub line: Option<usize>,
+    pub text: String,
+}
+
+/// A work item with at least one match
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub id: String,
+    pub title: String,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Case-insensitive search across titles, notes, PRDs and handoff notes
+pub fn search(base: &Path, query: &str) -> Result<Vec<SearchHit>, String> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Err("search text is empty".into());
+    }
+    let found = |text: &str| text.to_lowercase().contains(&needle);
+
+    let mut hits = Vec::new();
+    for (dir, state) in load_all(base, false)? {
+        let Ok(state) = state else { continue };
+        let mut matches = Vec::new();
+        if found(&state.title) {
+            matches.push(SearchMatch {
+                source: "title".into(),
+                line: None,
+                text: state.title.clone(),
+            });
+        }
+        for (i, note) in state.notes.iter().enumerate() {
+            if found(&note.text) {
+                matches.push(SearchMatch {
+                    source: "notes".into(),
+                    line: Some(i + 1),
+                    text: note.text.clone(),
+                });
+            }
+        }
+
+        let mut files = vec!["prd.md".to_string()];
+        let mut handoffs: Vec<String> = fs::read_dir(dir.join("handoffs"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| format!("handoffs/{}", e.file_name().to_string_lossy()))
+                    .filter(|name| name.ends_with(".md"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        handoffs.sort();
+        files.extend(handoffs);
+        for file in files {
+            let Ok(content) = fs::read_to_string(dir.join(&file)) else { continue };
+            for (i, line) in content.lines().enumerate() {
+                if found(line) {
+                    matches.push(SearchMatch {
+                        source: file.clone(),
+                        line: Some(i + 1),
+                        text: line.trim().to_string(),
+                    });
+                }
+            }
+        }
+
+        if !matches.is_empty() {
+            hits.push(SearchHit {
+                id: state.id,
+                title: state.title,
+                matches,
+            });
+        }
     }
+    Ok(hits)
+}
 
-    if has_gemfile {
-        return Some("cli_ruby".into());
+/// Print search results
+pub fn search_work(base: &Path, query: &str, format: Format) -> Result<(), String> {
+    let hits = search(base, query)?;
+    format.emit(&hits, |hits| print_hits(hits, query))
+}
+
+fn print_hits(hits: &[SearchHit], query: &str) {
+    if hits.is_empty() {
+        println!("no matches for '{}'", query);
+        return;
+    }
+    for hit in hits {
+        println!("{:<20} {}", hit.id, hit.title);
+        for m in &hit.matches {
+            let location = match m.line {
+                Some(line) => format!("{}:{}", m.source, line),
+                None => m.source.clone(),
+            };
+            let text: String = m.text.chars().take(100).collect();
+            println!("  {}: {}", location, text);
+        }
     }
+    let total: usize = hits.iter().map(|h| h.matches.len()).sum();
+    println!();
+    println!("{} match(es) in {} work item(s)", total, hits.len());
+}
 
-    None
+/// Auto-detect stack from repo contents using each stack's detection rules
+/// (see `stack::builtin_rules` for the defaults), highest priority first.
+fn detect_stack(base: &Path, config: &PfmConfig) -> Option<String> {
+    crate::stack::detect(base, config).map(|e| e.stack)
 }
 
 fn detect_repo_name(base: &Path) -> String {
@@ -244,6 +609,7 @@ fn create_branch(base: &Path, branch: &str) -> Result<(), String> {
 mod tests {
     use super::*;
     use crate::commands::init;
+    use crate::state::{Note, NoteKind};
     use tempfile::tempdir;
 
     fn init_test_repo(dir: &Path) {
@@ -294,6 +660,28 @@ mod tests {
         assert_eq!(id, "FEAT-add-login-page");
     }
 
+    #[test]
+    fn test_new_typed_uses_type_pipeline_templates_and_prefix() {
+        let dir = tempdir().unwrap();
+        init_test_repo(dir.path());
+        let id = new_typed(dir.path(), "Crash on empty cart", None, None, WorkType::Bug).unwrap();
+        assert_eq!(id, "BUG-crash-on-empty");
+        let work_dir = dir.path().join(".pfm/work").join(&id);
+        let state = read_state(&work_dir.join("state.json")).unwrap();
+        assert_eq!(state.work_type, WorkType::Bug);
+        assert_eq!(state.pipeline, vec!["prd", "tests", "impl", "review_security", "qa", "git"]);
+        let prd = fs::read_to_string(work_dir.join("prd.md")).unwrap();
+        assert!(prd.starts_with("# Bug Report") && prd.contains("Crash on empty cart"));
+        // Lint compares against the bug template, so the fresh PRD counts as unfilled
+        let fill = crate::lint::check_artifact(dir.path(), &work_dir, "prd.md", &prd).unwrap();
+        assert_eq!(fill, crate::lint::Fill::Unfilled);
+        assert!(fs::read_to_string(work_dir.join("plan.md")).unwrap().starts_with("# Implementation Plan"));
+
+        let id = new_typed(dir.path(), "Bump deps", None, None, WorkType::Chore).unwrap();
+        let state = read_state(&dir.path().join(".pfm/work").join(&id).join("state.json")).unwrap();
+        assert_eq!((id.as_str(), state.owner), ("CHORE-bump-deps", Role::Implementation));
+    }
+
     #[test]
     fn test_new_work_duplicate_fails() {
         let dir = tempdir().unwrap();
@@ -311,6 +699,43 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_failed_new_work_does_not_use_up_an_id() {
+        let dir = tempdir().unwrap();
+        init_test_repo(dir.path());
+        fs::write(
+            dir.path().join(".pfm/config.local.json"),
+            r#"{"ids": {"scheme": "sequential"}}"#,
+        ).unwrap();
+
+        assert!(new_work(dir.path(), "Test", None, Some("unknown_stack")).is_err());
+        assert!(!dir.path().join(".pfm/counter").exists());
+        assert!(!dir.path().join(".pfm/work/FEAT-001").exists());
+        assert!(new_work(dir.path(), "Test", Some("FEAT-BAD"), Some("unknown_stack")).is_err());
+        assert!(!dir.path().join(".pfm/work/FEAT-BAD").exists());
+
+        assert_eq!(new_work(dir.path(), "Test", None, None).unwrap(), "FEAT-001");
+    }
+
+    #[test]
+    fn test_new_work_snapshots_custom_pipeline() {
+        let dir = tempdir().unwrap();
+        init_test_repo(dir.path());
+        fs::write(
+            dir.path().join(".pfm/config.local.json"),
+            r#"{"pipeline": ["prd", "plan", "impl", "docs", "git"]}"#,
+        ).unwrap();
+
+        let result = new_work(dir.path(), "Test", Some("FEAT-NOSPEC"), None);
+        assert!(result.unwrap_err().contains("custom role 'docs' has no role spec"));
+
+        fs::write(dir.path().join(".pfm/roles/docs.md"), "# Role: Docs").unwrap();
+        new_work(dir.path(), "Test", Some("FEAT-DOCS"), None).unwrap();
+        let state = read_state(&dir.path().join(".pfm/work/FEAT-DOCS/state.json")).unwrap();
+        assert_eq!(state.pipeline, vec!["prd", "plan", "impl", "docs", "git"]);
+        assert_eq!(state.gates.get("docs"), Some(&crate::state::GateStatus::Todo));
+    }
+
     #[test]
     fn test_new_work_without_init_fails() {
         let dir = tempdir().unwrap();
@@ -318,13 +743,48 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_add_component_detects_stack_per_path() {
+        let dir = tempdir().unwrap();
+        init_test_repo(dir.path());
+        fs::create_dir_all(dir.path().join("api/config")).unwrap();
+        fs::write(dir.path().join("api/Gemfile"), "gem 'rails'").unwrap();
+        fs::write(dir.path().join("api/config/routes.rb"), "").unwrap();
+        fs::create_dir_all(dir.path().join("app")).unwrap();
+        fs::write(dir.path().join("app/package.json"), r#"{"dependencies":{"react-native":"0.72"}}"#).unwrap();
+
+        new_work(dir.path(), "Cross-cutting", Some("FEAT-MONO"), Some("rails")).unwrap();
+        add_component(dir.path(), "FEAT-MONO", "api", "api/", None).unwrap();
+        add_component(dir.path(), "FEAT-MONO", "app", "app", None).unwrap();
+
+        let state = read_state(&dir.path().join(".pfm/work/FEAT-MONO/state.json")).unwrap();
+        assert_eq!(state.components.len(), 2);
+        assert_eq!(state.components[0].path, "api");
+        assert_eq!(state.components[0].stack, "rails");
+        assert_eq!(state.components[1].stack, "react_native");
+        assert_eq!(state.components[1].commands.verify, "npm test");
+    }
+
+    #[test]
+    fn test_add_component_rejects_duplicates_and_missing_paths() {
+        let dir = tempdir().unwrap();
+        init_test_repo(dir.path());
+        fs::create_dir_all(dir.path().join("api")).unwrap();
+        new_work(dir.path(), "Test", Some("FEAT-C2"), None).unwrap();
+        add_component(dir.path(), "FEAT-C2", "api", "api", Some("rust")).unwrap();
+        assert!(add_component(dir.path(), "FEAT-C2", "api", "api", None).is_err());
+        assert!(add_component(dir.path(), "FEAT-C2", "web", "web", None).is_err());
+        remove_component(dir.path(), "FEAT-C2", "api").unwrap();
+        assert!(remove_component(dir.path(), "FEAT-C2", "api").is_err());
+    }
+
     #[test]
     fn test_detect_stack_rails() {
         let dir = tempdir().unwrap();
         fs::write(dir.path().join("Gemfile"), "gem 'rails'").unwrap();
         fs::create_dir_all(dir.path().join("config")).unwrap();
         fs::write(dir.path().join("config/routes.rb"), "").unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("rails".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("rails".into()));
     }
 
     #[test]
@@ -333,7 +793,7 @@ mod tests {
         fs::write(dir.path().join("Gemfile"), "gem 'rails'").unwrap();
         fs::create_dir_all(dir.path().join("bin")).unwrap();
         fs::write(dir.path().join("bin/rails"), "").unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("rails".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("rails".into()));
     }
 
     #[test]
@@ -343,7 +803,7 @@ mod tests {
             dir.path().join("package.json"),
             r#"{"dependencies":{"react-native":"0.72"}}"#,
         ).unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("react_native".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("react_native".into()));
     }
 
     #[test]
@@ -353,27 +813,27 @@ mod tests {
             dir.path().join("package.json"),
             r#"{"dependencies":{"express":"4"}}"#,
         ).unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("cli_node".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("cli_node".into()));
     }
 
     #[test]
     fn test_detect_stack_rust() {
         let dir = tempdir().unwrap();
         fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"foo\"").unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("rust".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("rust".into()));
     }
 
     #[test]
     fn test_detect_stack_cli_ruby() {
         let dir = tempdir().unwrap();
         fs::write(dir.path().join("Gemfile"), "gem 'thor'").unwrap();
-        assert_eq!(detect_stack(dir.path()), Some("cli_ruby".into()));
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), Some("cli_ruby".into()));
     }
 
     #[test]
     fn test_detect_stack_unknown() {
         let dir = tempdir().unwrap();
-        assert_eq!(detect_stack(dir.path()), None);
+        assert_eq!(detect_stack(dir.path(), &PfmConfig::default()), None);
     }
 
     #[test]
@@ -388,4 +848,102 @@ mod tests {
         ).unwrap();
         assert_eq!(state.commands.verify, "npm test");
     }
+
+    /// Three items: FEAT-A (blocked, qa=fail, a week old), FEAT-B (in progress), FEAT-C (done)
+    fn seed_items(dir: &Path) {
+        init_test_repo(dir);
+        for (id, title) in [("FEAT-A", "Zebra export"), ("FEAT-B", "Audit log"), ("FEAT-C", "Billing")] {
+            new_work(dir, title, Some(id), Some("rust")).unwrap();
+        }
+        let update = |id: &str, f: &dyn Fn(&mut WorkState)| {
+            let path = dir.join(".pfm/work").join(id).join("state.json");
+            let mut state = read_state(&path).unwrap();
+            f(&mut state);
+            write_state(&path, &state).unwrap();
+        };
+        update("FEAT-A", &|s| {
+            s.status = WorkStatus::Blocked;
+            s.gates.set("qa", GateStatus::Fail);
+            s.updated_at = (Utc::now() - chrono::Duration::days(8)).to_rfc3339();
+        });
+        update("FEAT-B", &|s| s.owner = Role::Implementation);
+        update("FEAT-C", &|s| {
+            s.status = WorkStatus::Done;
+            s.updated_at = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        });
+    }
+
+    fn ids(items: &[WorkSummary]) -> Vec<&str> {
+        items.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_list_filters() {
+        let dir = tempdir().unwrap();
+        seed_items(dir.path());
+        let list = |status, owner, gates: &[&str], since| {
+            let gates: Vec<String> = gates.iter().map(|g| g.to_string()).collect();
+            let filter = ListFilter::parse(status, owner, &gates, since, "id").unwrap();
+            summaries(dir.path(), &filter).unwrap()
+        };
+
+        assert_eq!(ids(&list(None, None, &[], None)), vec!["FEAT-A", "FEAT-B", "FEAT-C"]);
+        assert_eq!(ids(&list(Some("blocked"), None, &[], None)), vec!["FEAT-A"]);
+        assert_eq!(ids(&list(None, Some("implementation"), &[], None)), vec!["FEAT-B"]);
+        assert_eq!(ids(&list(None, None, &["qa=fail"], None)), vec!["FEAT-A"]);
+        assert_eq!(ids(&list(None, None, &["qa=fail", "prd=pass"], None)), Vec::<&str>::new());
+        assert_eq!(ids(&list(None, None, &[], Some("7d"))), vec!["FEAT-B", "FEAT-C"]);
+    }
+
+    #[test]
+    fn test_list_sort_and_invalid_filters() {
+        let dir = tempdir().unwrap();
+        seed_items(dir.path());
+        let sorted = |key| summaries(dir.path(), &ListFilter::parse(None, None, &[], None, key).unwrap()).unwrap();
+        assert_eq!(ids(&sorted("updated")), vec!["FEAT-B", "FEAT-C", "FEAT-A"]);
+        assert_eq!(ids(&sorted("title")), vec!["FEAT-B", "FEAT-C", "FEAT-A"]);
+        assert_eq!(ids(&sorted("status")), vec!["FEAT-A", "FEAT-C", "FEAT-B"]);
+
+        assert!(ListFilter::parse(Some("stuck"), None, &[], None, "id").unwrap_err().contains("unknown status 'stuck'"));
+        assert!(ListFilter::parse(None, None, &["qa".into()], None, "id").unwrap_err().contains("GATE=STATUS"));
+        assert!(ListFilter::parse(None, None, &[], None, "owner").unwrap_err().contains("unknown sort key"));
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("7d", now).unwrap(), now - chrono::Duration::days(7));
+        assert_eq!(parse_since("2w", now).unwrap(), now - chrono::Duration::weeks(2));
+        assert_eq!(parse_since("2026-03-01", now).unwrap().to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert!(parse_since("7", now).is_err());
+        assert!(parse_since("7y", now).is_err());
+        assert!(parse_since("99999999999999d", now).unwrap_err().contains("too far in the past"));
+        assert!(parse_since("99999999999999999999d", now).is_err());
+        assert!(parse_since("999999999w", now).unwrap_err().contains("too far in the past"));
+    }
+
+    #[test]
+    fn test_search_across_title_notes_prd_and_handoffs() {
+        let dir = tempdir().unwrap();
+        seed_items(dir.path());
+        let work = dir.path().join(".pfm/work");
+        fs::write(work.join("FEAT-B/prd.md"), "# PRD\n\nKeep an audit trail of CSV exports.\n").unwrap();
+        fs::create_dir_all(work.join("FEAT-C/handoffs")).unwrap();
+        fs::write(work.join("FEAT-C/handoffs/20260101-000000-qa.md"), "Invoices export as csv.\n").unwrap();
+        let path = work.join("FEAT-A/state.json");
+        let mut state = read_state(&path).unwrap();
+        state.notes.push(Note::new("customer wants CSV", "Ada", NoteKind::Context));
+        write_state(&path, &state).unwrap();
+
+        let hits = search(dir.path(), "csv").unwrap();
+        assert_eq!(hits.iter().map(|h| h.id.as_str()).collect::<Vec<_>>(), vec!["FEAT-A", "FEAT-B", "FEAT-C"]);
+        assert_eq!(hits[0].matches[0].source, "notes");
+        assert_eq!(hits[1].matches[0].source, "prd.md");
+        assert_eq!(hits[1].matches[0].line, Some(3));
+        assert_eq!(hits[2].matches[0].source, "handoffs/20260101-000000-qa.md");
+
+        let hits = search(dir.path(), "ZEBRA").unwrap();
+        assert_eq!(hits[0].matches[0].source, "title");
+        assert!(search(dir.path(), "  ").is_err());
+    }
 }
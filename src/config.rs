@@ -1,57 +1,765 @@
+use crate::ids::IdConfig;
+use crate::stack::{builtin_rules, DetectRule};
+use crate::state::WorkType;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StackConfig {
     pub verify: String,
     pub security: String,
+    /// Rules that identify a repo using this stack (any one matching is enough)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detect: Vec<DetectRule>,
+    /// Higher priority stacks are checked first during detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+}
+
+impl StackConfig {
+    /// A preset with pfm's built-in detection rules for `name` (if any)
+    fn preset(name: &str, verify: &str, security: &str) -> Self {
+        let (detect, priority) = match builtin_rules(name) {
+            Some((rules, priority)) => (rules, Some(priority)),
+            None => (vec![], None),
+        };
+        StackConfig {
+            verify: verify.into(),
+            security: security.into(),
+            detect,
+            priority,
+        }
+    }
+}
+
+/// Settings for the context pack pfm writes for each role agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextConfig {
+    /// Build `artifacts/context-<role>.md` before starting an agent
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum characters of artifact content inlined into a pack
+    #[serde(default = "default_context_budget")]
+    pub budget: usize,
+}
+
+fn default_context_budget() -> usize {
+    24_000
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        ContextConfig {
+            enabled: false,
+            budget: default_context_budget(),
+        }
+    }
+}
+
+/// How the built-in git gate commits and pushes, for both `pfm git` and `pfm run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Squash the work branch into one commit
+    #[serde(default)]
+    pub squash: bool,
+    /// Branch the work branch is squashed against
+    #[serde(default = "default_git_base")]
+    pub base: String,
+    /// Push the work branch when a remote is configured
+    #[serde(default = "default_git_push")]
+    pub push: bool,
+}
+
+fn default_git_base() -> String {
+    "main".into()
+}
+
+fn default_git_push() -> bool {
+    true
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            squash: false,
+            base: default_git_base(),
+            push: default_git_push(),
+        }
+    }
+}
+
+/// Pipeline, templates and ID prefix for one work type. Unset fields fall back to the
+/// top-level `pipeline`, the default templates and `ids.prefix`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Subdirectory of `.pfm/templates/` whose files replace the default templates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<String>,
+}
+
+fn default_types() -> BTreeMap<WorkType, TypeConfig> {
+    let typed = |pipeline: &[&str], prefix: &str, templates: &str| TypeConfig {
+        pipeline: Some(pipeline.iter().map(|g| g.to_string()).collect()),
+        prefix: Some(prefix.into()),
+        templates: Some(templates.into()),
+    };
+    BTreeMap::from([
+        (WorkType::Feature, TypeConfig::default()),
+        (WorkType::Bug, typed(&["prd", "tests", "impl", "review_security", "qa", "git"], "BUG", "bug")),
+        (WorkType::Chore, typed(&["impl", "git"], "CHORE", "chore")),
+        (WorkType::Spike, typed(&["prd", "plan", "impl"], "SPIKE", "spike")),
+    ])
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PfmConfig {
     pub default_stack: String,
     pub stacks: HashMap<String, StackConfig>,
+    #[serde(default)]
+    pub context: ContextConfig,
+    /// Gate order for new work items. Gates outside the built-in eight are owned by a
+    /// custom role of the same name, specified in `.pfm/roles/<name>.md`.
+    #[serde(default = "crate::state::default_pipeline")]
+    pub pipeline: Vec<String>,
+    /// How IDs are generated when `pfm work new` gets no `--id`
+    #[serde(default)]
+    pub ids: IdConfig,
+    /// Per-type overrides for `pfm work new --type`
+    #[serde(default = "default_types")]
+    pub types: BTreeMap<WorkType, TypeConfig>,
+    /// Pipeline gates that only a person can pass, with `pfm approve`
+    #[serde(default)]
+    pub approval_gates: Vec<String>,
+    #[serde(default)]
+    pub git: GitConfig,
+}
+
+impl PfmConfig {
+    fn type_config(&self, work_type: WorkType) -> TypeConfig {
+        self.types.get(&work_type).cloned().unwrap_or_default()
+    }
+
+    /// Gate order for new work items of this type
+    pub fn pipeline_for(&self, work_type: WorkType) -> Vec<String> {
+        self.type_config(work_type).pipeline.unwrap_or_else(|| self.pipeline.clone())
+    }
+
+    /// ID prefix for new work items of this type
+    pub fn prefix_for(&self, work_type: WorkType) -> String {
+        self.type_config(work_type).prefix.unwrap_or_else(|| self.ids.prefix.clone())
+    }
+
+    /// Template set for new work items of this type, if it has its own
+    pub fn templates_for(&self, work_type: WorkType) -> Option<String> {
+        self.type_config(work_type).templates
+    }
+}
+
+/// Keys allowed at the top level of a config file
+pub const CONFIG_KEYS: &[&str] = &["default_stack", "stacks", "context", "pipeline", "ids", "types", "approval_gates", "git"];
+
+/// Keys allowed inside the `ids` section
+pub const IDS_KEYS: &[&str] = &["scheme", "prefix", "width"];
+
+/// Keys allowed inside a `types.<type>` entry
+pub const TYPE_KEYS: &[&str] = &["pipeline", "prefix", "templates"];
+
+/// Keys allowed inside the `context` section
+pub const CONTEXT_KEYS: &[&str] = &["enabled", "budget"];
+
+/// Keys allowed inside the `git` section
+pub const GIT_KEYS: &[&str] = &["squash", "base", "push"];
+
+/// Keys allowed inside a `stacks.<name>` entry, and which of them are required
+pub const STACK_KEYS: &[(&str, bool)] = &[
+    ("verify", true),
+    ("security", true),
+    ("detect", false),
+    ("priority", false),
+];
+
+/// Validate one raw config layer against the config schema.
+/// Layers may be partial, so only a stack's own keys are required — not the top-level ones.
+pub fn validate_layer(value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(map) = value.as_object() else {
+        return vec!["config must be a JSON object".into()];
+    };
+
+    for (key, v) in map {
+        match key.as_str() {
+            "default_stack" => {
+                if !v.is_string() {
+                    errors.push("default_stack must be a string".into());
+                }
+            }
+            "stacks" => match v.as_object() {
+                Some(stacks) => {
+                    for (name, stack) in stacks {
+                        errors.extend(validate_stack(name, stack));
+                    }
+                }
+                None => errors.push("stacks must be an object".into()),
+            },
+            "context" => errors.extend(validate_context(v)),
+            "pipeline" => errors.extend(validate_pipeline(v)),
+            "ids" => errors.extend(validate_ids(v)),
+            "types" => errors.extend(validate_types(v)),
+            "approval_gates" => errors.extend(validate_approval_gates(v)),
+            "git" => errors.extend(validate_git(v)),
+            other => errors.push(format!(
+                "unknown key '{}'{}",
+                other,
+                suggest(other, CONFIG_KEYS).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+            )),
+        }
+    }
+    errors
+}
+
+fn validate_pipeline(pipeline: &Value) -> Vec<String> {
+    let Some(items) = pipeline.as_array() else {
+        return vec!["pipeline must be a list of gate names".into()];
+    };
+    let mut errors = Vec::new();
+    let mut seen = Vec::new();
+    for item in items {
+        let Some(gate) = item.as_str() else {
+            errors.push(format!("pipeline entries must be strings, got {}", item));
+            continue;
+        };
+        if crate::state::gate_to_role(gate).is_none() {
+            let hint = gate
+                .parse::<crate::state::Role>()
+                .ok()
+                .map(|role| format!(" ('{}' is a role; its gate is '{}')", gate, crate::state::role_to_gate(&role)))
+                .unwrap_or_else(|| " (use lowercase letters, digits and underscores)".into());
+            errors.push(format!("pipeline: invalid gate '{}'{}", gate, hint));
+        }
+        if seen.contains(&gate) {
+            errors.push(format!("pipeline: gate '{}' appears more than once", gate));
+        }
+        seen.push(gate);
+    }
+    if items.is_empty() {
+        errors.push("pipeline must not be empty".into());
+    }
+    errors
+}
+
+fn validate_ids(ids: &Value) -> Vec<String> {
+    let Some(map) = ids.as_object() else {
+        return vec!["ids must be an object".into()];
+    };
+    let mut errors = Vec::new();
+    for (key, v) in map {
+        match key.as_str() {
+            "scheme" => {
+                if serde_json::from_value::<crate::ids::IdScheme>(v.clone()).is_err() {
+                    errors.push("ids.scheme must be \"slug\", \"sequential\" or \"slug-hash\"".into());
+                }
+            }
+            "prefix" => {
+                if !is_plain_name(v) {
+                    errors.push("ids.prefix must be letters, digits, '-' or '_' (e.g. \"FEAT\")".into());
+                }
+            }
+            "width" => {
+                if !v.as_u64().map(|n| (1..=9).contains(&n)).unwrap_or(false) {
+                    errors.push("ids.width must be an integer from 1 to 9".into());
+                }
+            }
+            other => errors.push(format!(
+                "unknown key 'ids.{}'{}",
+                other,
+                suggest(other, IDS_KEYS).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+            )),
+        }
+    }
+    errors
+}
+
+fn validate_approval_gates(gates: &Value) -> Vec<String> {
+    let Some(items) = gates.as_array() else {
+        return vec!["approval_gates must be a list of gate names".into()];
+    };
+    let mut errors = Vec::new();
+    for item in items {
+        match item.as_str() {
+            Some(gate) if crate::state::GATE_ORDER.contains(&gate) => errors.push(format!(
+                "approval_gates: '{}' is run by an agent; add a gate of its own (e.g. \"signoff\") to the pipeline",
+                gate
+            )),
+            Some(gate) if !crate::state::is_valid_name(gate) => errors.push(format!(
+                "approval_gates: invalid gate '{}' (use lowercase letters, digits and underscores)",
+                gate
+            )),
+            Some(_) => {}
+            None => errors.push(format!("approval_gates entries must be strings, got {}", item)),
+        }
+    }
+    errors
+}
+
+/// ID prefixes and template set names: letters, digits, `-` and `_`
+fn is_plain_name(v: &Value) -> bool {
+    v.as_str().map(|p| crate::ids::validate(p).is_ok() && !p.contains('.')).unwrap_or(false)
+}
+
+fn validate_types(types: &Value) -> Vec<String> {
+    let Some(map) = types.as_object() else {
+        return vec!["types must be an object".into()];
+    };
+    let mut errors = Vec::new();
+    for (name, entry) in map {
+        if name.parse::<WorkType>().is_err() {
+            errors.push(format!(
+                "unknown work type 'types.{}'{}",
+                name,
+                suggest(name, crate::state::WORK_TYPES)
+                    .map(|s| format!(" (did you mean '{}'?)", s))
+                    .unwrap_or_else(|| format!(" (use {})", crate::state::WORK_TYPES.join(", ")))
+            ));
+            continue;
+        }
+        let Some(fields) = entry.as_object() else {
+            errors.push(format!("types.{} must be an object", name));
+            continue;
+        };
+        for (key, v) in fields {
+            match key.as_str() {
+                "pipeline" => {
+                    errors.extend(validate_pipeline(v).into_iter().map(|e| format!("types.{}.{}", name, e)))
+                }
+                "prefix" => {
+                    if !is_plain_name(v) {
+                        errors.push(format!("types.{}.prefix must be letters, digits, '-' or '_' (e.g. \"BUG\")", name));
+                    }
+                }
+                "templates" => {
+                    if !is_plain_name(v) {
+                        errors.push(format!(
+                            "types.{}.templates must name a directory in .pfm/templates (letters, digits, '-' or '_')",
+                            name
+                        ));
+                    }
+                }
+                other => errors.push(format!(
+                    "unknown key 'types.{}.{}'{}",
+                    name,
+                    other,
+                    suggest(other, TYPE_KEYS).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+                )),
+            }
+        }
+    }
+    errors
+}
+
+fn validate_context(context: &Value) -> Vec<String> {
+    let Some(map) = context.as_object() else {
+        return vec!["context must be an object".into()];
+    };
+    let mut errors = Vec::new();
+    for (key, v) in map {
+        match key.as_str() {
+            "enabled" => {
+                if !v.is_boolean() {
+                    errors.push("context.enabled must be true or false".into());
+                }
+            }
+            "budget" => {
+                if !v.as_u64().map(|n| n > 0).unwrap_or(false) {
+                    errors.push("context.budget must be a positive integer".into());
+                }
+            }
+            other => errors.push(format!(
+                "unknown key 'context.{}'{}",
+                other,
+                suggest(other, CONTEXT_KEYS).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+            )),
+        }
+    }
+    errors
+}
+
+fn validate_git(git: &Value) -> Vec<String> {
+    let Some(map) = git.as_object() else {
+        return vec!["git must be an object".into()];
+    };
+    let mut errors = Vec::new();
+    for (key, v) in map {
+        match key.as_str() {
+            "squash" | "push" => {
+                if !v.is_boolean() {
+                    errors.push(format!("git.{} must be true or false", key));
+                }
+            }
+            "base" => {
+                if v.as_str().is_none_or(|b| b.trim().is_empty()) {
+                    errors.push("git.base must be a branch name".into());
+                }
+            }
+            other => errors.push(format!(
+                "unknown key 'git.{}'{}",
+                other,
+                suggest(other, GIT_KEYS).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+            )),
+        }
+    }
+    errors
+}
+
+fn validate_stack(name: &str, stack: &Value) -> Vec<String> {
+    let Some(map) = stack.as_object() else {
+        return vec![format!("stacks.{} must be an object", name)];
+    };
+    let known: Vec<&str> = STACK_KEYS.iter().map(|(k, _)| *k).collect();
+    let mut errors = Vec::new();
+    for (key, required) in STACK_KEYS {
+        let Some(v) = map.get(*key) else {
+            if *required {
+                errors.push(format!("stacks.{} is missing '{}'", name, key));
+            }
+            continue;
+        };
+        let problem = match *key {
+            "detect" => serde_json::from_value::<Vec<DetectRule>>(v.clone())
+                .err()
+                .map(|e| format!("stacks.{}.detect is invalid: {}", name, e)),
+            "priority" => (!v.is_i64()).then(|| format!("stacks.{}.priority must be an integer", name)),
+            _ => (!v.is_string()).then(|| format!("stacks.{}.{} must be a string", name, key)),
+        };
+        errors.extend(problem);
+    }
+    for key in map.keys().filter(|k| !known.contains(&k.as_str())) {
+        errors.push(format!(
+            "unknown key 'stacks.{}.{}'{}",
+            name,
+            key,
+            suggest(key, &known).map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+        ));
+    }
+    errors
+}
+
+/// Closest candidate by edit distance, if it is plausibly a typo
+pub fn suggest<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|c| (edit_distance(input, c), *c))
+        .filter(|(d, c)| *d <= 2.max(c.len() / 3))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Environment variables that override config keys
+pub const ENV_VARS: &[(&str, &str)] = &[("PFM_STACK", "default_stack")];
+
+/// `-c key=value` overrides from the command line (set once by main)
+static CLI_OVERRIDES: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    Default,
+    Global(PathBuf),
+    Repo(PathBuf),
+    Local(PathBuf),
+    Env(String),
+    Cli,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Global(p) => write!(f, "global ({})", p.display()),
+            Origin::Repo(p) => write!(f, "repo ({})", p.display()),
+            Origin::Local(p) => write!(f, "local ({})", p.display()),
+            Origin::Env(var) => write!(f, "env ({})", var),
+            Origin::Cli => write!(f, "cli (-c)"),
+        }
+    }
+}
+
+/// The effective config plus the origin of every leaf value
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: PfmConfig,
+    pub origins: BTreeMap<String, Origin>,
+    merged: Value,
+}
+
+impl LayeredConfig {
+    /// Look up a dotted key (e.g. `stacks.rails.verify`) in the effective config
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        key.split('.').try_fold(&self.merged, |v, part| v.get(part))
+    }
+
+    /// All leaf values as (key, value, origin), sorted by key
+    pub fn entries(&self) -> Vec<(String, Value, Origin)> {
+        self.origins
+            .iter()
+            .filter_map(|(k, o)| self.get(k).map(|v| (k.clone(), v.clone(), o.clone())))
+            .collect()
+    }
+}
+
+/// Config file layers, lowest precedence first
+pub struct LayerPaths {
+    pub global: Option<PathBuf>,
+    pub repo: PathBuf,
+    pub local: PathBuf,
+}
+
+impl LayerPaths {
+    pub fn for_repo(base: &Path) -> Self {
+        LayerPaths {
+            global: global_config_path(),
+            repo: base.join(".pfm/config.json"),
+            local: base.join(".pfm/config.local.json"),
+        }
+    }
+}
+
+/// `~/.config/pfm/config.json` (honours `PFM_CONFIG_HOME` and `XDG_CONFIG_HOME`)
+pub fn global_config_path() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("PFM_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("config.json"));
+    }
+    if let Ok(dir) = env::var("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("pfm/config.json"));
+    }
+    env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join(".config/pfm/config.json"))
+}
+
+/// Record `-c key=value` overrides from the command line
+pub fn set_cli_overrides(overrides: Vec<(String, String)>) {
+    let _ = CLI_OVERRIDES.set(overrides);
+}
+
+/// Load the effective config for a repo:
+/// defaults → global → `.pfm/config.json` → `.pfm/config.local.json` → env vars → CLI flags
+pub fn load(base: &Path) -> Result<LayeredConfig, String> {
+    let env_values: Vec<(String, String)> = ENV_VARS
+        .iter()
+        .filter_map(|(var, _)| env::var(var).ok().map(|v| (var.to_string(), v)))
+        .collect();
+    let cli = CLI_OVERRIDES.get().cloned().unwrap_or_default();
+    load_layers(&LayerPaths::for_repo(base), &env_values, &cli)
+}
+
+/// Merge the given layers; `env_values` are (VAR, value) pairs, `cli` are (key, value) pairs
+pub fn load_layers(
+    paths: &LayerPaths,
+    env_values: &[(String, String)],
+    cli: &[(String, String)],
+) -> Result<LayeredConfig, String> {
+    let mut merged = serde_json::to_value(PfmConfig::default())
+        .map_err(|e| format!("failed to serialize config: {}", e))?;
+    let mut origins = BTreeMap::new();
+    record_origins(&merged, "", &Origin::Default, &mut origins);
+
+    let file_layers = [
+        (paths.global.clone(), Origin::Global as fn(PathBuf) -> Origin),
+        (Some(paths.repo.clone()), Origin::Repo),
+        (Some(paths.local.clone()), Origin::Local),
+    ];
+    for (path, origin) in file_layers {
+        let Some(path) = path else { continue };
+        if !path.exists() {
+            continue;
+        }
+        let layer = read_layer(&path)?;
+        merge_layer(&mut merged, layer, "", &origin(path), &mut origins);
+    }
+
+    for (var, value) in env_values {
+        if let Some((_, key)) = ENV_VARS.iter().find(|(v, _)| v == var) {
+            set_path(&mut merged, key, parse_value(value));
+            origins.insert(key.to_string(), Origin::Env(var.clone()));
+        }
+    }
+
+    for (key, value) in cli {
+        let value = parse_value(value);
+        set_path(&mut merged, key, value.clone());
+        origins.retain(|k, _| k != key && !k.starts_with(&format!("{}.", key)));
+        record_origins(&value, key, &Origin::Cli, &mut origins);
+    }
+
+    let config: PfmConfig = serde_json::from_value(merged.clone())
+        .map_err(|e| format!("invalid effective config: {}", e))?;
+    Ok(LayeredConfig { config, origins, merged })
+}
+
+/// Read one config layer as raw JSON (partial files are allowed)
+pub fn read_layer(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    if !value.is_object() {
+        return Err(format!("{} must contain a JSON object", path.display()));
+    }
+    Ok(value)
+}
+
+/// Write one config layer, creating parent directories as needed
+pub fn write_layer(path: &Path, value: &Value) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("failed to serialize config: {}", e))?;
+    fs::write(path, content)
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// Set a dotted key inside a JSON object, creating intermediate objects
+pub fn set_path(root: &mut Value, key: &str, value: Value) {
+    let mut current = root;
+    let parts: Vec<&str> = key.split('.').collect();
+    for (i, part) in parts.iter().enumerate() {
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        let map = current.as_object_mut().expect("object");
+        if i == parts.len() - 1 {
+            map.insert(part.to_string(), value);
+            return;
+        }
+        current = map.entry(part.to_string()).or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+/// Interpret a CLI/env value: JSON literals (numbers, bools, arrays, objects) or a plain string
+pub fn parse_value(raw: &str) -> Value {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(v) if !v.is_string() => v,
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+fn merge_layer(
+    target: &mut Value,
+    layer: Value,
+    prefix: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    match (target, layer) {
+        (Value::Object(target_map), Value::Object(layer_map)) => {
+            for (k, v) in layer_map {
+                let key = join_key(prefix, &k);
+                match target_map.get_mut(&k) {
+                    Some(existing) if existing.is_object() && v.is_object() => {
+                        merge_layer(existing, v, &key, origin, origins);
+                    }
+                    _ => {
+                        origins.retain(|o, _| o != &key && !o.starts_with(&format!("{}.", key)));
+                        record_origins(&v, &key, origin, origins);
+                        target_map.insert(k, v);
+                    }
+                }
+            }
+        }
+        (target, layer) => {
+            origins.insert(prefix.to_string(), origin.clone());
+            *target = layer;
+        }
+    }
+}
+
+fn record_origins(value: &Value, prefix: &str, origin: &Origin, origins: &mut BTreeMap<String, Origin>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                record_origins(v, &join_key(prefix, k), origin, origins);
+            }
+        }
+        _ => {
+            origins.insert(prefix.to_string(), origin.clone());
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
 }
 
 impl Default for PfmConfig {
     fn default() -> Self {
         let mut stacks = HashMap::new();
-        stacks.insert("rails".into(), StackConfig {
-            verify: "bundle exec rspec".into(),
-            security: "bundle exec brakeman -q".into(),
-        });
-        stacks.insert("react_native".into(), StackConfig {
-            verify: "npm test".into(),
-            security: "npm audit".into(),
-        });
-        stacks.insert("cli_node".into(), StackConfig {
-            verify: "npm test".into(),
-            security: "npm audit".into(),
-        });
-        stacks.insert("cli_ruby".into(), StackConfig {
-            verify: "bundle exec rspec".into(),
-            security: "bundle exec brakeman -q".into(),
-        });
-        stacks.insert("rust".into(), StackConfig {
-            verify: "cargo test".into(),
-            security: "cargo audit".into(),
-        });
+        stacks.insert("rails".into(), StackConfig::preset(
+            "rails",
+            "bundle exec rspec",
+            "bundle exec brakeman -q",
+        ));
+        stacks.insert("react_native".into(), StackConfig::preset(
+            "react_native",
+            "npm test",
+            "npm audit",
+        ));
+        stacks.insert("cli_node".into(), StackConfig::preset(
+            "cli_node",
+            "npm test",
+            "npm audit",
+        ));
+        stacks.insert("cli_ruby".into(), StackConfig::preset(
+            "cli_ruby",
+            "bundle exec rspec",
+            "bundle exec brakeman -q",
+        ));
+        stacks.insert("rust".into(), StackConfig::preset(
+            "rust",
+            "cargo test",
+            "cargo audit",
+        ));
         PfmConfig {
             default_stack: "rails".into(),
             stacks,
+            context: ContextConfig::default(),
+            git: GitConfig::default(),
+            pipeline: crate::state::default_pipeline(),
+            ids: IdConfig::default(),
+            types: default_types(),
+            approval_gates: vec![],
         }
     }
 }
 
-pub fn read_config(path: &Path) -> Result<PfmConfig, String> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))
-}
-
 pub fn write_config(path: &Path, config: &PfmConfig) -> Result<(), String> {
     let content = serde_json::to_string_pretty(config)
         .map_err(|e| format!("failed to serialize config: {}", e))?;
@@ -82,13 +790,177 @@ mod tests {
         assert_eq!(parsed.stacks["rails"].verify, "bundle exec rspec");
     }
 
+    fn layer_paths(dir: &Path) -> LayerPaths {
+        LayerPaths {
+            global: Some(dir.join("global/config.json")),
+            repo: dir.join(".pfm/config.json"),
+            local: dir.join(".pfm/config.local.json"),
+        }
+    }
+
+    #[test]
+    fn test_layers_defaults_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let layered = load_layers(&layer_paths(dir.path()), &[], &[]).unwrap();
+        assert_eq!(layered.config.default_stack, "rails");
+        assert_eq!(layered.origins["default_stack"], Origin::Default);
+    }
+
+    #[test]
+    fn test_layers_precedence_and_origin() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = layer_paths(dir.path());
+        write_layer(paths.global.as_ref().unwrap(), &serde_json::json!({
+            "default_stack": "rust",
+            "stacks": { "rust": { "verify": "cargo nextest run" } }
+        })).unwrap();
+        write_layer(&paths.repo, &serde_json::json!({ "default_stack": "cli_node" })).unwrap();
+        write_layer(&paths.local, &serde_json::json!({
+            "stacks": { "rails": { "verify": "bin/rspec" } }
+        })).unwrap();
+
+        let layered = load_layers(&paths, &[], &[]).unwrap();
+        assert_eq!(layered.config.default_stack, "cli_node");
+        assert_eq!(layered.origins["default_stack"], Origin::Repo(paths.repo.clone()));
+        assert_eq!(layered.config.stacks["rust"].verify, "cargo nextest run");
+        assert_eq!(layered.config.stacks["rust"].security, "cargo audit");
+        assert_eq!(layered.origins["stacks.rust.security"], Origin::Default);
+        assert_eq!(layered.config.stacks["rails"].verify, "bin/rspec");
+        assert_eq!(layered.origins["stacks.rails.verify"], Origin::Local(paths.local.clone()));
+    }
+
+    #[test]
+    fn test_layers_env_and_cli_override_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = layer_paths(dir.path());
+        write_layer(&paths.repo, &serde_json::json!({ "default_stack": "cli_node" })).unwrap();
+
+        let env_values = vec![("PFM_STACK".to_string(), "rust".to_string())];
+        let layered = load_layers(&paths, &env_values, &[]).unwrap();
+        assert_eq!(layered.config.default_stack, "rust");
+        assert_eq!(layered.origins["default_stack"], Origin::Env("PFM_STACK".into()));
+
+        let cli = vec![("default_stack".to_string(), "cli_ruby".to_string())];
+        let layered = load_layers(&paths, &env_values, &cli).unwrap();
+        assert_eq!(layered.config.default_stack, "cli_ruby");
+        assert_eq!(layered.origins["default_stack"], Origin::Cli);
+    }
+
+    #[test]
+    fn test_layers_invalid_effective_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = layer_paths(dir.path());
+        write_layer(&paths.repo, &serde_json::json!({ "default_stack": 42 })).unwrap();
+        assert!(load_layers(&paths, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_set_path_and_get() {
+        let mut value = serde_json::json!({});
+        set_path(&mut value, "stacks.go.verify", Value::String("go test ./...".into()));
+        assert_eq!(value["stacks"]["go"]["verify"], "go test ./...");
+        assert_eq!(parse_value("true"), Value::Bool(true));
+        assert_eq!(parse_value("npm test"), Value::String("npm test".into()));
+    }
+
+    #[test]
+    fn test_validate_layer_accepts_defaults() {
+        let value = serde_json::to_value(PfmConfig::default()).unwrap();
+        assert!(validate_layer(&value).is_empty());
+    }
+
+    #[test]
+    fn test_validate_layer_reports_typos() {
+        let value = serde_json::json!({
+            "default_stak": "rails",
+            "stacks": { "go": { "verify": "go test ./...", "securty": "gosec ./..." } }
+        });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("'default_stak'") && e.contains("default_stack")));
+        assert!(errors.iter().any(|e| e.contains("stacks.go is missing 'security'")));
+        assert!(errors.iter().any(|e| e.contains("stacks.go.securty") && e.contains("'security'")));
+    }
+
+    #[test]
+    fn test_validate_layer_checks_context() {
+        let value = serde_json::json!({ "context": { "enabled": "yes", "budgt": 100, "budget": 0 } });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("context.enabled must be true or false")));
+        assert!(errors.iter().any(|e| e.contains("context.budgt") && e.contains("'budget'")));
+        assert!(errors.iter().any(|e| e.contains("context.budget must be a positive integer")));
+    }
+
+    #[test]
+    fn test_validate_layer_checks_git() {
+        let value = serde_json::json!({ "git": { "squash": "yes", "base": " ", "psuh": false } });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("git.squash must be true or false")));
+        assert!(errors.iter().any(|e| e.contains("git.base must be a branch name")));
+        assert!(errors.iter().any(|e| e.contains("git.psuh") && e.contains("'push'")));
+    }
+
+    #[test]
+    fn test_validate_layer_checks_pipeline() {
+        let value = serde_json::json!({ "pipeline": ["prd", "plan", "orchestrator", "docs", "docs", "Perf"] });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("'orchestrator' is a role; its gate is 'plan'")));
+        assert!(errors.iter().any(|e| e.contains("'docs' appears more than once")));
+        assert!(errors.iter().any(|e| e.contains("invalid gate 'Perf'")));
+        assert!(validate_layer(&serde_json::json!({ "pipeline": ["prd", "docs", "git"] })).is_empty());
+    }
+
+    #[test]
+    fn test_validate_layer_checks_types() {
+        let value = serde_json::json!({ "types": {
+            "bugfix": {},
+            "chore": { "pipeline": ["impl", "Git"], "prefx": "CH" },
+            "spike": { "templates": "../x" },
+        } });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("'types.bugfix'")), "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("types.chore.pipeline: invalid gate 'Git'")));
+        assert!(errors.iter().any(|e| e.contains("'types.chore.prefx' (did you mean 'prefix'?)")));
+        assert!(errors.iter().any(|e| e.contains("types.spike.templates must name")));
+
+        let config = PfmConfig::default();
+        assert_eq!(config.pipeline_for(WorkType::Feature), config.pipeline);
+        assert_eq!(config.pipeline_for(WorkType::Chore), vec!["impl", "git"]);
+        assert_eq!(config.prefix_for(WorkType::Feature), "FEAT");
+        assert_eq!(config.prefix_for(WorkType::Bug), "BUG");
+    }
+
+    #[test]
+    fn test_validate_layer_checks_approval_gates() {
+        let errors = validate_layer(&serde_json::json!({ "approval_gates": ["git", "Sign-off", "signoff"] }));
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("'git' is run by an agent"));
+        assert!(errors[1].contains("invalid gate 'Sign-off'"));
+    }
+
+    #[test]
+    fn test_validate_layer_checks_ids() {
+        let value = serde_json::json!({ "ids": { "scheme": "random", "width": 0, "prefx": "BUG", "prefix": "my feat" } });
+        let errors = validate_layer(&value);
+        assert!(errors.iter().any(|e| e.contains("ids.scheme must be")));
+        assert!(errors.iter().any(|e| e.contains("ids.width must be")));
+        assert!(errors.iter().any(|e| e.contains("'ids.prefx' (did you mean 'prefix'?)")));
+        assert!(errors.iter().any(|e| e.contains("ids.prefix must be")));
+        assert!(validate_layer(&serde_json::json!({ "ids": { "scheme": "sequential", "prefix": "PAY", "width": 4 } })).is_empty());
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("rials", &["rails", "rust"]), Some("rails"));
+        assert_eq!(suggest("python", &["rails", "rust"]), None);
+    }
+
     #[test]
     fn test_config_file_roundtrip() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("config.json");
         let config = PfmConfig::default();
         write_config(&path, &config).unwrap();
-        let loaded = read_config(&path).unwrap();
+        let loaded: PfmConfig = serde_json::from_value(read_layer(&path).unwrap()).unwrap();
         assert_eq!(loaded.default_stack, config.default_stack);
         assert_eq!(loaded.stacks.len(), config.stacks.len());
     }
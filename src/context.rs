@@ -0,0 +1,261 @@
+use crate::state::Role;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One section of a context pack
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A file in the work item directory
+    File(&'static str),
+    /// The newest note in `handoffs/`
+    LatestHandoff,
+    /// Failing commands (with output) from the last `pfm check`
+    FailedChecks,
+}
+
+/// The artifacts inlined for each role, most important first
+pub fn sources_for(role: &Role) -> Vec<Source> {
+    use Source::*;
+    match role {
+        Role::Prd => vec![File("state.json"), File("prd.md"), File("acceptance.md")],
+        Role::Orchestrator => vec![File("prd.md"), File("acceptance.md"), LatestHandoff],
+        Role::Env => vec![File("plan.md"), File("state.json"), LatestHandoff],
+        Role::Test => vec![File("plan.md"), File("tasks.md"), File("acceptance.md"), LatestHandoff],
+        Role::Implementation => vec![
+            File("plan.md"),
+            File("tasks.md"),
+            FailedChecks,
+            LatestHandoff,
+        ],
+        Role::ReviewSecurity => vec![File("acceptance.md"), File("plan.md"), FailedChecks, LatestHandoff],
+        Role::Qa => vec![File("acceptance.md"), File("qa.md"), FailedChecks, LatestHandoff],
+        Role::Git => vec![File("prd.md"), File("acceptance.md"), LatestHandoff],
+        Role::Custom(_) => vec![File("prd.md"), File("plan.md"), File("tasks.md"), LatestHandoff],
+    }
+}
+
+/// Where a role's context pack is written
+pub fn pack_path(work_dir: &Path, role: &Role) -> PathBuf {
+    work_dir.join("artifacts").join(format!("context-{}.md", role))
+}
+
+/// A section's title, the file it came from, and its full content
+struct Section {
+    title: String,
+    path: Option<PathBuf>,
+    content: String,
+}
+
+fn load_section(work_dir: &Path, source: &Source) -> Option<Section> {
+    match source {
+        Source::File(name) => {
+            let path = work_dir.join(name);
+            let content = fs::read_to_string(&path).ok()?;
+            Some(Section {
+                title: name.to_string(),
+                path: Some(path),
+                content,
+            })
+        }
+        Source::LatestHandoff => {
+            let path = crate::prompt::latest_handoff(&work_dir.join("handoffs"))?;
+            let content = fs::read_to_string(&path).ok()?;
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some(Section {
+                title: format!("Latest handoff ({})", name),
+                path: Some(path),
+                content,
+            })
+        }
+        Source::FailedChecks => {
+            let path = work_dir.join("artifacts/check.json");
+            let recorded: Value = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            let content = failed_checks(&recorded)?;
+            Some(Section {
+                title: format!("Failing checks ({})", recorded["at"].as_str().unwrap_or("?")),
+                path: Some(path),
+                content,
+            })
+        }
+    }
+}
+
+/// Render failing commands from a check.json record; None if everything passed
+fn failed_checks(recorded: &Value) -> Option<String> {
+    let mut out = String::new();
+    for suite in recorded["suites"].as_array().into_iter().flatten() {
+        for result in suite["results"].as_array().into_iter().flatten() {
+            if result["passed"] != Value::Bool(false) {
+                continue;
+            }
+            let kind = result["kind"].as_str().unwrap_or("?");
+            match suite["component"].as_str() {
+                Some(component) => out.push_str(&format!("### {} [{}]\n\n", kind, component)),
+                None => out.push_str(&format!("### {}\n\n", kind)),
+            }
+            out.push_str("```\n");
+            out.push_str(result["output"].as_str().unwrap_or("(no output recorded)").trim_end());
+            out.push_str("\n```\n\n");
+        }
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+/// Split `budget` characters across sections. Sections smaller than an even share
+/// keep their full size and hand the remainder to the larger ones.
+fn allocate(sizes: &[usize], budget: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+
+    let mut allocation = vec![0; sizes.len()];
+    let mut remaining = budget;
+    for (n, &i) in order.iter().enumerate() {
+        let share = remaining / (sizes.len() - n);
+        allocation[i] = sizes[i].min(share);
+        remaining -= allocation[i];
+    }
+    allocation
+}
+
+/// Cut `content` to at most `max` characters, preferring a line boundary
+fn truncate(content: &str, max: usize) -> String {
+    let cut: String = content.chars().take(max).collect();
+    match cut.rfind('\n') {
+        Some(pos) if pos > cut.len() / 2 => cut[..=pos].to_string(),
+        _ => cut,
+    }
+}
+
+/// Build the context pack for a role, keeping inlined content within `budget` characters
+pub fn build(work_dir: &Path, role: &Role, budget: usize) -> String {
+    let sections: Vec<Section> = sources_for(role)
+        .iter()
+        .filter_map(|source| load_section(work_dir, source))
+        .collect();
+    let sizes: Vec<usize> = sections.iter().map(|s| s.content.chars().count()).collect();
+    let allocation = allocate(&sizes, budget);
+
+    let mut out = format!(
+        "# Context Pack: {}\n\nWork item: {}\nBudget: {} characters\n",
+        role,
+        work_dir.display(),
+        budget
+    );
+    for ((section, size), allowed) in sections.iter().zip(&sizes).zip(&allocation) {
+        out.push_str(&format!("\n---\n\n## {}\n\n", section.title));
+        let source = section
+            .path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        if *allowed == 0 {
+            out.push_str(&format!("[omitted: over the context budget — read {}]\n", source));
+            continue;
+        }
+        let text = if allowed < size {
+            truncate(&section.content, *allowed)
+        } else {
+            section.content.clone()
+        };
+        out.push_str(text.trim_end());
+        out.push('\n');
+        if allowed < size {
+            out.push_str(&format!(
+                "\n[truncated: {} of {} characters omitted — read {} for the rest]\n",
+                size - text.chars().count(),
+                size,
+                source
+            ));
+        }
+    }
+    out
+}
+
+/// Build and write `artifacts/context-<role>.md`, returning its path
+pub fn write(work_dir: &Path, role: &Role, budget: usize) -> Result<PathBuf, String> {
+    let path = pack_path(work_dir, role);
+    fs::create_dir_all(work_dir.join("artifacts"))
+        .map_err(|e| format!("failed to create artifacts dir: {}", e))?;
+    fs::write(&path, build(work_dir, role, budget))
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Write the pack if context packing is enabled in config
+pub fn write_if_enabled(base: &Path, work_dir: &Path, role: &Role) -> Result<Option<PathBuf>, String> {
+    let settings = crate::config::load(base)?.config.context;
+    if !settings.enabled {
+        return Ok(None);
+    }
+    write(work_dir, role, settings.budget).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_allocate_gives_leftovers_to_large_sections() {
+        assert_eq!(allocate(&[10, 500, 20], 100), vec![10, 70, 20]);
+        assert_eq!(allocate(&[10, 20], 100), vec![10, 20]);
+        assert_eq!(allocate(&[300, 300], 100), vec![50, 50]);
+    }
+
+    #[test]
+    fn test_orchestrator_pack_inlines_prd_and_acceptance() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("prd.md"), "# PRD\nLogin with SSO\n").unwrap();
+        fs::write(dir.path().join("acceptance.md"), "- [ ] SSO works\n").unwrap();
+        fs::write(dir.path().join("plan.md"), "not for the orchestrator").unwrap();
+
+        let pack = build(dir.path(), &Role::Orchestrator, 10_000);
+        assert!(pack.contains("## prd.md"));
+        assert!(pack.contains("Login with SSO"));
+        assert!(pack.contains("- [ ] SSO works"));
+        assert!(!pack.contains("not for the orchestrator"));
+        assert!(!pack.contains("[truncated"));
+    }
+
+    #[test]
+    fn test_pack_truncates_to_budget() {
+        let dir = tempdir().unwrap();
+        let long_plan: String = (0..200).map(|i| format!("step {}\n", i)).collect();
+        fs::write(dir.path().join("plan.md"), &long_plan).unwrap();
+        fs::write(dir.path().join("tasks.md"), "- [ ] one\n").unwrap();
+
+        let pack = build(dir.path(), &Role::Implementation, 200);
+        assert!(pack.contains("- [ ] one"));
+        assert!(pack.contains("step 0"));
+        assert!(!pack.contains("step 199"));
+        assert!(pack.contains("[truncated:"));
+        assert!(pack.contains("plan.md for the rest]"));
+    }
+
+    #[test]
+    fn test_implementation_pack_includes_failing_output() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("artifacts")).unwrap();
+        let record = serde_json::json!({
+            "at": "2026-01-01T00:00:00Z",
+            "passed": false,
+            "suites": [{
+                "component": null,
+                "results": [
+                    {"kind": "verify", "passed": false, "output": "assertion failed: left == right"},
+                    {"kind": "security", "passed": true}
+                ]
+            }]
+        });
+        fs::write(dir.path().join("artifacts/check.json"), record.to_string()).unwrap();
+
+        let path = write(dir.path(), &Role::Implementation, 10_000).unwrap();
+        assert_eq!(path, dir.path().join("artifacts/context-implementation.md"));
+        let pack = fs::read_to_string(path).unwrap();
+        assert!(pack.contains("## Failing checks"));
+        assert!(pack.contains("### verify"));
+        assert!(pack.contains("assertion failed: left == right"));
+        assert!(!pack.contains("### security"));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::runlog;
+use crate::state::{read_state, write_state, Note, NoteKind, WorkStatus};
+use chrono::Utc;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// A work item in the dependency graph
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub title: String,
+    pub status: WorkStatus,
+    pub depends_on: Vec<String>,
+    pub archived: bool,
+    pub dir: PathBuf,
+}
+
+/// Every readable work item, active and archived, keyed by ID
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub nodes: BTreeMap<String, Node>,
+}
+
+impl Graph {
+    pub fn load(base: &Path) -> Result<Self, String> {
+        let archive_dir = base.join(".pfm/archive");
+        let mut nodes = BTreeMap::new();
+        for (dir, state) in crate::commands::work::load_all(base, true)? {
+            let Ok(state) = state else { continue };
+            nodes.entry(state.id.clone()).or_insert(Node {
+                title: state.title,
+                status: state.status,
+                depends_on: state.depends_on,
+                archived: dir.starts_with(&archive_dir),
+                dir,
+            });
+        }
+        Ok(Graph { nodes })
+    }
+
+    /// Upstream items of `id` that aren't done, with their status (None if the item doesn't exist)
+    pub fn unmet(&self, id: &str) -> Vec<(String, Option<WorkStatus>)> {
+        let Some(node) = self.nodes.get(id) else {
+            return Vec::new();
+        };
+        node.depends_on
+            .iter()
+            .filter_map(|dep| match self.nodes.get(dep) {
+                Some(up) if up.status == WorkStatus::Done => None,
+                Some(up) => Some((dep.clone(), Some(up.status.clone()))),
+                None => Some((dep.clone(), None)),
+            })
+            .collect()
+    }
+
+    /// Items that depend on `id`, directly or through other items, nearest first
+    pub fn downstream(&self, id: &str) -> Vec<String> {
+        let mut seen = BTreeSet::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([id.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            for (other, node) in &self.nodes {
+                if node.depends_on.contains(&current) && other != id && seen.insert(other.clone()) {
+                    order.push(other.clone());
+                    queue.push_back(other.clone());
+                }
+            }
+        }
+        order
+    }
+
+    /// The dependency path `from` → … → `to`, if `to` is upstream of `from`
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut parent: BTreeMap<String, String> = BTreeMap::new();
+        let mut queue = VecDeque::from([from.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![current];
+                while let Some(prev) = parent.get(path.last().expect("path is not empty")) {
+                    path.push(prev.clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for dep in self.nodes.get(&current).map(|n| n.depends_on.as_slice()).unwrap_or_default() {
+                if dep != from && !parent.contains_key(dep) {
+                    parent.insert(dep.clone(), current.clone());
+                    queue.push_back(dep.clone());
+                }
+            }
+        }
+        None
+    }
+
+    /// Each dependency cycle once, as `A → B → … → A`
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles: Vec<Vec<String>> = Vec::new();
+        let mut covered: BTreeSet<String> = BTreeSet::new();
+        for (id, node) in &self.nodes {
+            if covered.contains(id) {
+                continue;
+            }
+            for dep in &node.depends_on {
+                if let Some(mut path) = self.path(dep, id) {
+                    path.insert(0, id.clone());
+                    covered.extend(path.iter().cloned());
+                    cycles.push(path);
+                    break;
+                }
+            }
+        }
+        cycles
+    }
+
+    /// IDs with every upstream item before its dependents. Items in cycles come last.
+    pub fn order(&self) -> Vec<String> {
+        let mut placed: BTreeSet<String> = BTreeSet::new();
+        let mut order = Vec::new();
+        loop {
+            let ready: Vec<String> = self
+                .nodes
+                .iter()
+                .filter(|(id, node)| {
+                    !placed.contains(*id)
+                        && node.depends_on.iter().all(|d| placed.contains(d) || !self.nodes.contains_key(d))
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+            placed.extend(ready.iter().cloned());
+            order.extend(ready);
+        }
+        order.extend(self.nodes.keys().filter(|id| !placed.contains(*id)).cloned());
+        order
+    }
+}
+
+/// Describe unmet dependencies, e.g. `FEAT-a (in_progress), FEAT-z (missing)`
+pub fn describe(unmet: &[(String, Option<WorkStatus>)]) -> String {
+    unmet
+        .iter()
+        .map(|(id, status)| match status {
+            Some(status) => format!("{} ({})", id, status),
+            None => format!("{} (missing)", id),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Flag every item downstream of a reopened item with a note and a runlog entry.
+/// Returns the flagged IDs.
+pub fn flag_downstream(base: &Path, work_id: &str, reason: &str) -> Result<Vec<String>, String> {
+    let graph = Graph::load(base)?;
+    let mut flagged = Vec::new();
+    for id in graph.downstream(work_id) {
+        let node = &graph.nodes[&id];
+        if node.archived {
+            continue;
+        }
+        let state_path = node.dir.join("state.json");
+        let mut state = read_state(&state_path)?;
+        state.notes.push(Note::new(
+            &format!("upstream {} was reopened: {}", work_id, reason),
+            "pfm",
+            NoteKind::Blocker,
+        ));
+        state.touch();
+        write_state(&state_path, &state)?;
+
+        runlog::append(
+            &node.dir,
+            &format!(
+                "\n## Upstream Reopened: {} — {}\n\nReason: {}\n",
+                Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+                work_id,
+                reason
+            ),
+        )?;
+        flagged.push(id);
+    }
+    Ok(flagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> Graph {
+        let nodes = edges
+            .iter()
+            .map(|(id, deps)| {
+                let node = Node {
+                    title: id.to_string(),
+                    status: WorkStatus::InProgress,
+                    depends_on: deps.iter().map(|d| d.to_string()).collect(),
+                    archived: false,
+                    dir: PathBuf::new(),
+                };
+                (id.to_string(), node)
+            })
+            .collect();
+        Graph { nodes }
+    }
+
+    #[test]
+    fn test_order_and_downstream() {
+        let g = graph(&[("C", &["A", "B"]), ("B", &["A"]), ("A", &[]), ("D", &[])]);
+        assert_eq!(g.order(), vec!["A", "D", "B", "C"]);
+        assert_eq!(g.downstream("A"), vec!["B", "C"]);
+        assert!(g.downstream("C").is_empty());
+        assert!(g.cycles().is_empty());
+        assert_eq!(g.path("C", "A"), Some(vec!["C".to_string(), "A".to_string()]));
+    }
+
+    #[test]
+    fn test_cycles_detected_once() {
+        let g = graph(&[("A", &["B"]), ("B", &["C"]), ("C", &["A"]), ("D", &["A"])]);
+        assert_eq!(g.cycles(), vec![vec!["A", "B", "C", "A"]]);
+        assert_eq!(g.order(), vec!["A", "B", "C", "D"]);
+        let self_loop = graph(&[("A", &["A"])]);
+        assert_eq!(self_loop.cycles(), vec![vec!["A", "A"]]);
+    }
+
+    #[test]
+    fn test_unmet() {
+        let mut g = graph(&[("A", &[]), ("B", &["A", "Z"])]);
+        assert_eq!(describe(&g.unmet("B")), "A (in_progress), Z (missing)");
+        g.nodes.get_mut("A").unwrap().status = WorkStatus::Done;
+        assert_eq!(describe(&g.unmet("B")), "Z (missing)");
+    }
+}
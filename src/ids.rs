@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdScheme {
+    /// `FEAT-add-login-page`: the first three words of the title
+    #[default]
+    Slug,
+    /// `FEAT-042`: a per-prefix counter kept in `.pfm/counter`
+    Sequential,
+    /// `FEAT-add-login-page-3f9a1c`: slug plus a short hash of the title
+    SlugHash,
+}
+
+/// How new work IDs are generated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdConfig {
+    #[serde(default)]
+    pub scheme: IdScheme,
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+    /// Zero-padded digits for sequential IDs
+    #[serde(default = "default_width")]
+    pub width: usize,
+}
+
+fn default_prefix() -> String {
+    "FEAT".into()
+}
+
+fn default_width() -> usize {
+    3
+}
+
+impl Default for IdConfig {
+    fn default() -> Self {
+        IdConfig {
+            scheme: IdScheme::default(),
+            prefix: default_prefix(),
+            width: default_width(),
+        }
+    }
+}
+
+/// Longest accepted work ID
+const MAX_LEN: usize = 64;
+
+/// Check an explicit work ID: ASCII letters, digits, `-`, `_` and `.`, starting with a
+/// letter or digit. IDs become directory and branch names, so nothing else is allowed.
+pub fn validate(id: &str) -> Result<(), String> {
+    let invalid = |why: &str| Err(format!("invalid work ID '{}': {}", id, why));
+    if id.is_empty() {
+        return invalid("it is empty");
+    }
+    if id.len() > MAX_LEN {
+        return invalid(&format!("it is longer than {} characters", MAX_LEN));
+    }
+    if !id.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return invalid("it must start with a letter or digit");
+    }
+    if let Some(c) = id.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))) {
+        return invalid(&format!("'{}' is not allowed (use letters, digits, '-', '_' and '.')", c));
+    }
+    if id.ends_with('.') || id.contains("..") || id.ends_with(".lock") {
+        return invalid("it can't be used as a git branch name");
+    }
+    Ok(())
+}
+
+/// ASCII for common accented Latin letters; None for characters with no ASCII spelling
+fn fold(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// Lowercase ASCII slug of the first three words of a title. ASCII punctuation is
+/// dropped ("don't" → `dont`), accented letters are folded to ASCII, and other non-ASCII
+/// characters separate words. Empty if nothing is left.
+pub fn slugify(title: &str) -> String {
+    let mut ascii = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            ascii.push(c);
+        } else if let Some(folded) = fold(c) {
+            ascii.push_str(folded);
+        } else if c.is_whitespace() || !c.is_ascii() {
+            ascii.push(' ');
+        }
+    }
+    ascii.split_whitespace().take(3).collect::<Vec<_>>().join("-")
+}
+
+/// First six hex digits of the title's SHA-256
+fn short_hash(title: &str) -> String {
+    format!("{:x}", Sha256::digest(title.trim().as_bytes()))[..6].to_string()
+}
+
+/// True if an active or archived work item already uses this ID
+fn taken(base: &Path, id: &str) -> bool {
+    crate::commands::lifecycle::locate(base, id).is_some()
+}
+
+fn counter_path(base: &Path) -> std::path::PathBuf {
+    base.join(".pfm/counter")
+}
+
+/// Last sequential number used per prefix
+fn read_counters(base: &Path) -> BTreeMap<String, u64> {
+    fs::read_to_string(counter_path(base))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// `stem` followed by `suffix`, with `stem` cut short so the ID stays within [`MAX_LEN`].
+/// A cut never leaves the stem ending in `-` or `.`.
+pub fn with_suffix(stem: &str, suffix: &str) -> String {
+    let room = MAX_LEN.saturating_sub(suffix.len());
+    let cut = stem.char_indices().nth(room).map(|(i, _)| i).unwrap_or(stem.len());
+    format!("{}{}", stem[..cut].trim_end_matches(['-', '.']), suffix)
+}
+
+/// The ID the next work item titled `title` would get, without reserving it.
+/// IDs in `reserved` are treated as taken. Long slugs are shortened to fit [`MAX_LEN`];
+/// an error means the prefix itself can't make a valid ID.
+pub fn propose(
+    base: &Path,
+    config: &IdConfig,
+    prefix: &str,
+    title: &str,
+    reserved: &BTreeSet<String>,
+) -> Result<String, String> {
+    let unavailable = |id: &str| reserved.contains(id) || taken(base, id);
+    let id = if config.scheme == IdScheme::Sequential {
+        let last = read_counters(base).get(prefix).copied().unwrap_or(0);
+        (last + 1..)
+            .map(|n| format!("{}-{:0width$}", prefix, n, width = config.width))
+            .find(|id| !unavailable(id))
+            .expect("some sequential ID is free")
+    } else {
+        let slug = slugify(title);
+        // The hash and the collision suffix survive shortening; the slug gives way
+        let (stem, hash) = match (config.scheme, slug.is_empty()) {
+            // Titles with no ASCII spelling still get a stable, distinct ID
+            (_, true) => (prefix.to_string(), format!("-{}", short_hash(title))),
+            (IdScheme::SlugHash, false) => (format!("{}-{}", prefix, slug), format!("-{}", short_hash(title))),
+            _ => (format!("{}-{}", prefix, slug), String::new()),
+        };
+        std::iter::once(with_suffix(&stem, &hash))
+            .chain((2..).map(|n| with_suffix(&stem, &format!("{}-{}", hash, n))))
+            .find(|id| !unavailable(id))
+            .expect("some suffixed ID is free")
+    };
+    validate(&id).map_err(|e| format!("cannot generate an ID with prefix '{}': {}", prefix, e))?;
+    Ok(id)
+}
+
+/// Generate an ID for a new work item and claim it by creating its work directory.
+/// If another process claims the proposed ID first, the next free one is tried.
+pub fn allocate(base: &Path, config: &IdConfig, prefix: &str, title: &str) -> Result<String, String> {
+    let mut lost = BTreeSet::new();
+    loop {
+        let id = propose(base, config, prefix, title, &lost)?;
+        if claim(base, &id)? {
+            reserve(base, config, prefix, &id)?;
+            return Ok(id);
+        }
+        lost.insert(id);
+    }
+}
+
+/// Create the work directory for `id`. `fs::create_dir` is atomic, so of two processes
+/// claiming the same ID exactly one gets true; the other gets false.
+pub fn claim(base: &Path, id: &str) -> Result<bool, String> {
+    let work = base.join(".pfm/work");
+    fs::create_dir_all(&work).map_err(|e| format!("failed to create {}: {}", work.display(), e))?;
+    match fs::create_dir(work.join(id)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(format!("failed to create work dir for {}: {}", id, e)),
+    }
+}
+
+/// Record a claimed ID as used. For sequential schemes this advances the counter.
+/// The counter only says where to start looking: uniqueness comes from [`claim`], so
+/// two processes racing here at worst leave it lower than the highest number in use.
+pub fn reserve(base: &Path, config: &IdConfig, prefix: &str, id: &str) -> Result<(), String> {
+    if config.scheme != IdScheme::Sequential {
+        return Ok(());
+    }
+    let Some(number) = id.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('-')?.parse::<u64>().ok()) else {
+        return Ok(());
+    };
+    let mut counters = read_counters(base);
+    let last = counters.entry(prefix.to_string()).or_insert(0);
+    *last = (*last).max(number);
+    let json = serde_json::to_string_pretty(&counters).map_err(|e| format!("failed to serialize counter: {}", e))?;
+    // Write then rename, so a concurrent reader never sees a half-written counter
+    let tmp = base.join(format!(".pfm/counter.{}.tmp", uuid::Uuid::new_v4()));
+    fs::write(&tmp, json + "\n").map_err(|e| format!("failed to write .pfm/counter: {}", e))?;
+    fs::rename(&tmp, counter_path(base)).map_err(|e| format!("failed to write .pfm/counter: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_slugify_folds_and_drops_non_ascii() {
+        assert_eq!(slugify("Add login page now"), "add-login-page");
+        assert_eq!(slugify("Größe der Überschrift ändern"), "grosse-der-uberschrift");
+        assert_eq!(slugify("Fix: crash (on iOS 17)!"), "fix-crash-on");
+        assert_eq!(slugify("Don't re-send e-mails"), "dont-resend-emails");
+        assert_eq!(slugify("日本語のタイトル"), "");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("FEAT-login_2.1").is_ok());
+        assert!(validate("FEAT login").unwrap_err().contains("' ' is not allowed"));
+        assert!(validate("../etc").unwrap_err().contains("start with a letter"));
+        assert!(validate("FEAT-ü").is_err());
+        assert!(validate("FEAT..x").is_err());
+        assert!(validate(&"x".repeat(65)).unwrap_err().contains("longer than 64"));
+    }
+
+    #[test]
+    fn test_slug_collisions_get_suffixes() {
+        let dir = tempdir().unwrap();
+        let config = IdConfig::default();
+        assert_eq!(allocate(dir.path(), &config, "FEAT", "Add login page").unwrap(), "FEAT-add-login-page");
+        fs::create_dir_all(dir.path().join(".pfm/work/FEAT-add-login-page")).unwrap();
+        assert_eq!(allocate(dir.path(), &config, "FEAT", "Add login page").unwrap(), "FEAT-add-login-page-2");
+
+        let reserved = BTreeSet::from(["FEAT-add-login-page-2".to_string()]);
+        assert_eq!(propose(dir.path(), &config, "FEAT", "Add login page", &reserved).unwrap(), "FEAT-add-login-page-3");
+
+        let id = allocate(dir.path(), &config, "FEAT", "日本語").unwrap();
+        assert_eq!(id.len(), "FEAT-".len() + 6);
+    }
+
+    #[test]
+    fn test_sequential_counter_per_prefix() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".pfm/work/BUG-002")).unwrap();
+        let config = IdConfig { scheme: IdScheme::Sequential, ..IdConfig::default() };
+        assert_eq!(allocate(dir.path(), &config, "FEAT", "a").unwrap(), "FEAT-001");
+        assert_eq!(allocate(dir.path(), &config, "FEAT", "b").unwrap(), "FEAT-002");
+        assert_eq!(allocate(dir.path(), &config, "BUG", "c").unwrap(), "BUG-001");
+        assert_eq!(allocate(dir.path(), &config, "BUG", "d").unwrap(), "BUG-003");
+        assert_eq!(read_counters(dir.path())["FEAT"], 2);
+    }
+
+    #[test]
+    fn test_claim_is_exclusive() {
+        let dir = tempdir().unwrap();
+        assert!(claim(dir.path(), "FEAT-a").unwrap());
+        assert!(!claim(dir.path(), "FEAT-a").unwrap());
+        assert!(dir.path().join(".pfm/work/FEAT-a").is_dir());
+
+        // Racing allocations of the same title each end up with their own ID
+        let base = dir.path().to_path_buf();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let base = base.clone();
+                std::thread::spawn(move || allocate(&base, &IdConfig::default(), "FEAT", "Same title").unwrap())
+            })
+            .collect();
+        let ids: BTreeSet<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(ids.len(), 8);
+    }
+
+    #[test]
+    fn test_slug_hash_is_stable() {
+        let dir = tempdir().unwrap();
+        let config = IdConfig { scheme: IdScheme::SlugHash, ..IdConfig::default() };
+        let id = propose(dir.path(), &config, "FEAT", "Add login page", &BTreeSet::new()).unwrap();
+        assert!(id.starts_with("FEAT-add-login-page-"));
+        assert_eq!(id, propose(dir.path(), &config, "FEAT", "Add login page", &BTreeSet::new()).unwrap());
+    }
+
+    #[test]
+    fn test_long_ids_are_shortened() {
+        let dir = tempdir().unwrap();
+        let title = format!("{} {} {}", "a".repeat(40), "b".repeat(40), "c".repeat(40));
+        let id = allocate(dir.path(), &IdConfig::default(), "FEAT", &title).unwrap();
+        assert_eq!(id.len(), MAX_LEN);
+        assert!(validate(&id).is_ok());
+        let second = allocate(dir.path(), &IdConfig::default(), "FEAT", &title).unwrap();
+        assert!(second.ends_with("-2") && second.len() <= MAX_LEN, "{}", second);
+
+        let config = IdConfig { scheme: IdScheme::SlugHash, ..IdConfig::default() };
+        let hashed = propose(dir.path(), &config, "FEAT", &title, &BTreeSet::new()).unwrap();
+        assert!(hashed.ends_with(&format!("-{}", short_hash(&title))) && hashed.len() <= MAX_LEN);
+        // A cut never leaves a dangling "-" in front of the suffix
+        assert_eq!(with_suffix("FEAT-ab-cd", "-2").len(), "FEAT-ab-cd-2".len());
+        assert_eq!(with_suffix(&format!("{}-x", "a".repeat(62)), "-2"), format!("{}-2", "a".repeat(62)));
+
+        let config = IdConfig { scheme: IdScheme::Sequential, ..IdConfig::default() };
+        let err = allocate(dir.path(), &config, &"P".repeat(70), "t").unwrap_err();
+        assert!(err.contains("cannot generate an ID with prefix") && err.contains("longer than 64"), "{}", err);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Work artifacts created from templates that agents are expected to fill in
+pub const LINTED_ARTIFACTS: &[&str] = &["prd.md", "acceptance.md", "plan.md", "tasks.md", "qa.md"];
+
+/// How much of an artifact has been written over its template
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fill {
+    /// Nothing beyond template content
+    Unfilled,
+    /// Some content written, but placeholder lines remain
+    Partial { placeholders: Vec<String>, unchanged_percent: usize },
+    Filled,
+}
+
+impl Fill {
+    pub fn is_filled(&self) -> bool {
+        *self == Fill::Filled
+    }
+
+    /// Short description, phrased to follow the file name
+    pub fn describe(&self) -> String {
+        match self {
+            Fill::Unfilled => "is still the template".into(),
+            Fill::Partial { placeholders, unchanged_percent } => format!(
+                "is mostly the template ({}% unchanged) — placeholders remain: {}",
+                unchanged_percent,
+                placeholders.iter().map(|p| format!("`{}`", p)).collect::<Vec<_>>().join(", ")
+            ),
+            Fill::Filled => "is filled in".into(),
+        }
+    }
+}
+
+/// Lines that carry content: not blank, not a heading, not an HTML comment
+fn content_lines(text: &str) -> Vec<String> {
+    let is_comment = |l: &str| l.starts_with("<!--") && l.ends_with("-->");
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !is_comment(l))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Compare an artifact against the template it was created from. Headings, blank lines and
+/// `<!-- -->` comments are structure; every other template line is a placeholder.
+pub fn fingerprint(template: &str, content: &str) -> Fill {
+    let placeholders: HashSet<String> = content_lines(template).into_iter().collect();
+    let lines = content_lines(content);
+    let remaining: Vec<String> = lines.iter().filter(|l| placeholders.contains(*l)).cloned().collect();
+
+    if remaining.len() == lines.len() {
+        Fill::Unfilled
+    } else if !remaining.is_empty() {
+        Fill::Partial {
+            unchanged_percent: remaining.len() * 100 / lines.len(),
+            placeholders: remaining,
+        }
+    } else {
+        Fill::Filled
+    }
+}
+
+/// The repo's template for an artifact, with the work item's placeholders substituted
+pub fn rendered_template(base: &Path, work_dir: &Path, filename: &str) -> Option<String> {
+    let state = crate::state::read_state(&work_dir.join("state.json")).ok();
+    let set = state.as_ref().and_then(|state| {
+        crate::config::load(base).ok().and_then(|layered| layered.config.templates_for(state.work_type))
+    });
+    let template = fs::read_to_string(crate::templates::template_path(base, set.as_deref(), filename)).ok()?;
+    let (id, title) = state.map(|s| (s.id, s.title)).unwrap_or_default();
+    Some(template.replace("{WORK_ID}", &id).replace("{TITLE}", &title))
+}
+
+/// Fingerprint one artifact of a work item. None if there's no template to compare against.
+pub fn check_artifact(base: &Path, work_dir: &Path, filename: &str, content: &str) -> Option<Fill> {
+    rendered_template(base, work_dir, filename).map(|template| fingerprint(&template, content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::ACCEPTANCE_MD;
+
+    #[test]
+    fn test_identical_template_is_unfilled() {
+        assert_eq!(fingerprint(ACCEPTANCE_MD, ACCEPTANCE_MD), Fill::Unfilled);
+    }
+
+    #[test]
+    fn test_headings_only_is_unfilled() {
+        let prd = crate::templates::PRD_MD;
+        let stripped: String = prd.lines().filter(|l| !l.contains("<!--")).collect::<Vec<_>>().join("\n");
+        assert_eq!(fingerprint(prd, &stripped), Fill::Unfilled);
+    }
+
+    #[test]
+    fn test_leftover_placeholders_are_partial() {
+        let content = ACCEPTANCE_MD.replace("- [ ] Criterion 2", "- [ ] Users can reset their password");
+        match fingerprint(ACCEPTANCE_MD, &content) {
+            Fill::Partial { placeholders, unchanged_percent } => {
+                assert_eq!(placeholders, vec!["- [ ] Criterion 1", "1. Step 1", "2. Step 2"]);
+                assert_eq!(unchanged_percent, 75);
+            }
+            other => panic!("expected partial, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_written_artifact_is_filled() {
+        let content = "# Acceptance Criteria\n\n<!-- keep -->\n- [ ] Login works\n\n1. Open /login\n";
+        assert!(fingerprint(ACCEPTANCE_MD, content).is_filled());
+    }
+}
@@ -1,16 +1,38 @@
+mod acceptance;
 mod adapters;
+mod approval;
 mod commands;
 mod config;
+mod context;
+mod deps;
+mod ids;
+mod lint;
+mod output;
+mod prompt;
+mod role_spec;
+mod runlog;
+mod stack;
 mod state;
+mod tasks;
 mod templates;
+mod trace;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::env;
 use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "pfm", version, about = "Production Flow Manager — orchestrates Claude Code role agents")]
 struct Cli {
+    /// Override a config value for this invocation (e.g. -c default_stack=rust)
+    #[arg(short = 'c', long = "config", global = true, value_name = "KEY=VALUE")]
+    config_overrides: Vec<String>,
+
+    /// Output format for the reporting commands: status, work list/search/graph, check,
+    /// doctor, lint, trace, config get/show and stack detect/list
+    #[arg(long, global = true, value_enum, default_value_t = output::Format::Table)]
+    format: output::Format,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -28,6 +50,67 @@ enum Commands {
     #[command(subcommand)]
     Agent(AgentCommands),
 
+    /// Inspect and change layered configuration
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Preview agent prompts rendered from .pfm/prompts/
+    #[command(subcommand)]
+    Prompt(PromptCommands),
+
+    /// Stack presets and detection
+    #[command(subcommand)]
+    Stack(StackCommands),
+
+    /// Override gate statuses by hand
+    #[command(subcommand)]
+    Gate(GateCommands),
+
+    /// Leave notes on a work item for the people and agents working on it
+    #[command(subcommand)]
+    Note(NoteCommands),
+
+    /// Sign off an approval gate (people only; recorded with your git identity)
+    Approve {
+        /// Work item ID
+        work_id: String,
+
+        /// Approval gate
+        gate: String,
+
+        /// Note recorded with the approval
+        #[arg(long, default_value = "")]
+        comment: String,
+    },
+
+    /// Reject an approval gate, saying what needs to change
+    Reject {
+        /// Work item ID
+        work_id: String,
+
+        /// Approval gate
+        gate: String,
+
+        /// What needs to change
+        #[arg(long)]
+        comment: String,
+    },
+
+    /// Validate config, tools, role specs, templates and work items
+    Doctor,
+
+    /// Report work artifacts that still hold template placeholder content
+    Lint {
+        /// Work item ID
+        work_id: String,
+    },
+
+    /// Trace PRD requirements to acceptance criteria, tests and changed files
+    Trace {
+        /// Work item ID
+        work_id: String,
+    },
+
     /// Run verification and security checks
     Check {
         /// Work item ID
@@ -46,6 +129,13 @@ enum Commands {
         /// Execution mode (auto, classic, teams)
         #[arg(long, default_value = "auto")]
         mode: String,
+
+        /// Wait for dependencies to be done instead of refusing to start
+        #[arg(long)]
+        wait: bool,
+
+        #[command(flatten)]
+        git: GitArgs,
     },
 
     /// Show work item status
@@ -53,6 +143,31 @@ enum Commands {
         /// Work item ID
         work_id: String,
     },
+
+    /// Run the built-in git gate: commit, write artifacts/pr.md, push if a remote exists
+    Git {
+        /// Work item ID
+        work_id: String,
+
+        #[command(flatten)]
+        git: GitArgs,
+    },
+}
+
+/// How the built-in git gate commits and pushes; unset flags fall back to the `git` config
+#[derive(Args)]
+struct GitArgs {
+    /// Squash all commits since the merge-base with --base into one
+    #[arg(long)]
+    squash: bool,
+
+    /// Base branch used when squashing (default: git.base, else main)
+    #[arg(long)]
+    base: Option<String>,
+
+    /// Do not push even if a remote is configured
+    #[arg(long)]
+    no_push: bool,
 }
 
 #[derive(Subcommand)]
@@ -69,12 +184,366 @@ enum WorkCommands {
         /// Technology stack
         #[arg(long)]
         stack: Option<String>,
+
+        /// Create an epic: plan only, then split into child work items
+        #[arg(long)]
+        epic: bool,
+
+        /// Work type: feature, bug, chore or spike (sets pipeline, templates and ID prefix)
+        #[arg(long = "type", default_value = "feature")]
+        work_type: String,
+    },
+
+    /// List work items, optionally filtered and sorted
+    List {
+        /// Only items with this status (in_progress, blocked, done)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only items owned by this role
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Only items with this gate status, e.g. qa=fail (repeatable)
+        #[arg(long = "gate", value_name = "GATE=STATUS")]
+        gates: Vec<String>,
+
+        /// Only items updated within this span (30m, 12h, 7d, 2w) or since a date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Sort by id, updated (newest first), status or title
+        #[arg(long, default_value = "id")]
+        sort: String,
+
+        /// Include archived items
+        #[arg(long)]
+        archived: bool,
+    },
+
+    /// Search titles, notes, PRDs and handoff notes
+    Search {
+        /// Text to find (case-insensitive)
+        text: String,
+    },
+
+    /// Mark a work item done
+    Close {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Mark a work item blocked
+    Block {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Return a blocked work item to in_progress
+    Unblock {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Return a done or archived work item to in_progress
+    Reopen {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Move a done or blocked work item to .pfm/archive/<year>/
+    Archive {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+
+        /// Archive whatever the status
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Delete a work item (logged to .pfm/archive/removed.md)
+    Rm {
+        /// Work item ID
+        work_id: String,
+
+        /// Why (recorded in the item's history and runlog)
+        #[arg(long, short)]
+        reason: String,
+
+        /// Delete even if in progress
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create work items from an issues file (.json, .csv or .md), skipping existing IDs
+    Import {
+        /// Issues file
+        file: PathBuf,
+
+        /// Show what would be created without creating anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write a work item and a checksummed manifest to a portable tar bundle
+    Export {
+        /// Work item ID
+        work_id: String,
+
+        /// Bundle file to write (default: <work_id>.tar)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Create a work item from a bundle written by `pfm work export`
+    ImportBundle {
+        /// Bundle file
+        file: PathBuf,
+    },
+
+    /// Create a child work item for each task in an epic's tasks.md
+    Split {
+        /// Epic work item ID
+        epic_id: String,
+    },
+
+    /// Print the dependency graph between work items and report cycles
+    Graph,
+
+    /// Manage dependencies between work items
+    #[command(subcommand)]
+    Dep(DepCommands),
+
+    /// Manage monorepo components (sub-projects with their own stack and checks)
+    #[command(subcommand)]
+    Component(ComponentCommands),
+}
+
+#[derive(Subcommand)]
+enum DepCommands {
+    /// Make a work item depend on another
+    Add {
+        /// Work item ID
+        work_id: String,
+
+        /// Work item that must be done first
+        upstream: String,
+    },
+
+    /// Remove a dependency
+    Rm {
+        /// Work item ID
+        work_id: String,
+
+        /// Upstream work item ID
+        upstream: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ComponentCommands {
+    /// Add a component to a work item
+    Add {
+        /// Work item ID
+        work_id: String,
+
+        /// Component name (e.g., api, mobile)
+        name: String,
+
+        /// Directory relative to the repo root
+        path: String,
+
+        /// Technology stack (detected from the directory if omitted)
+        #[arg(long)]
+        stack: Option<String>,
+    },
+
+    /// Remove a component from a work item
+    Rm {
+        /// Work item ID
+        work_id: String,
+
+        /// Component name
+        name: String,
     },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective value of a key (e.g. stacks.rails.verify)
+    Get {
+        /// Dotted config key
+        key: String,
 
-    /// List all work items
+        /// Show which layer the value came from
+        #[arg(long)]
+        origin: bool,
+
+        /// Read per-work command overrides from this work item
+        #[arg(long)]
+        work: Option<String>,
+    },
+
+    /// Set a key (defaults to the repo's .pfm/config.json)
+    Set {
+        /// Dotted config key
+        key: String,
+
+        /// New value (JSON literals are parsed; anything else is a string)
+        value: String,
+
+        /// Write to ~/.config/pfm/config.json
+        #[arg(long, conflicts_with_all = ["local", "work"])]
+        global: bool,
+
+        /// Write to .pfm/config.local.json (gitignored)
+        #[arg(long, conflicts_with = "work")]
+        local: bool,
+
+        /// Write a command override (commands.verify, ...) into a work item's state.json
+        #[arg(long)]
+        work: Option<String>,
+    },
+
+    /// Print all effective values
+    Show {
+        /// Show which layer each value came from
+        #[arg(long)]
+        origin: bool,
+
+        /// Include per-work command overrides for this work item
+        #[arg(long)]
+        work: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PromptCommands {
+    /// Render the prompt a role agent would receive (use role `lead` for the teams lead)
+    Render {
+        /// Role name, or `lead`
+        role: String,
+
+        /// Work item ID
+        work_id: String,
+
+        /// Render the nudge message instead of the bootstrap prompt
+        #[arg(long)]
+        nudge: bool,
+    },
+
+    /// Build the context pack for a role (artifacts/context-<role>.md)
+    Context {
+        /// Role name
+        role: String,
+
+        /// Work item ID
+        work_id: String,
+
+        /// Character budget (defaults to context.budget from config)
+        #[arg(long)]
+        budget: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StackCommands {
+    /// Detect which stack preset applies to the repo (or a sub-directory)
+    Detect {
+        /// Directory to inspect, relative to the repo root
+        path: Option<PathBuf>,
+
+        /// Show every stack's rules and which one matched
+        #[arg(long)]
+        explain: bool,
+    },
+
+    /// List configured stacks with commands and detection rules
     List,
 }
 
+#[derive(Subcommand)]
+enum GateCommands {
+    /// Set a gate's status (todo, in_progress, pass, fail, changes_requested, skipped)
+    Set {
+        /// Work item ID
+        work_id: String,
+
+        /// Gate name
+        gate: String,
+
+        /// New status
+        status: String,
+
+        /// Why (recorded in the runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Skip a gate: the pipeline treats it as satisfied without running its role
+    Skip {
+        /// Work item ID
+        work_id: String,
+
+        /// Gate name
+        gate: String,
+
+        /// Why (recorded in the runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+
+    /// Put a gate and every gate after it back to todo
+    Reset {
+        /// Work item ID
+        work_id: String,
+
+        /// First gate to reset
+        #[arg(long)]
+        from: String,
+
+        /// Why (recorded in the runlog)
+        #[arg(long, short)]
+        reason: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum NoteCommands {
+    /// Add a note; role prompts surface it on the next agent run
+    Add {
+        /// Work item ID
+        work_id: String,
+
+        /// Note text
+        text: String,
+
+        /// context (PRD and orchestrator), decision or blocker (every role)
+        #[arg(long, short, default_value = "context")]
+        kind: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum AgentCommands {
     /// Start a role agent for a work item
@@ -111,9 +580,47 @@ fn find_repo_root() -> Result<PathBuf, String> {
     }
 }
 
+fn parse_config_overrides(raw: &[String]) -> Result<Vec<(String, String)>, String> {
+    raw.iter()
+        .map(|kv| {
+            kv.split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                .ok_or_else(|| format!("invalid -c value '{}' (expected KEY=VALUE)", kv))
+        })
+        .collect()
+}
+
 fn main() {
     let cli = Cli::parse();
 
+    match parse_config_overrides(&cli.config_overrides) {
+        Ok(overrides) => config::set_cli_overrides(overrides),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let format = cli.format;
+    let structured = matches!(
+        cli.command,
+        Commands::Status { .. }
+            | Commands::Work(WorkCommands::List { .. })
+            | Commands::Work(WorkCommands::Search { .. })
+            | Commands::Work(WorkCommands::Graph)
+            | Commands::Check { .. }
+            | Commands::Doctor
+            | Commands::Lint { .. }
+            | Commands::Trace { .. }
+            | Commands::Config(ConfigCommands::Get { .. })
+            | Commands::Config(ConfigCommands::Show { .. })
+            | Commands::Stack(_)
+    );
+    if !format.is_table() && !structured {
+        eprintln!("error: --format {} is not supported by this command", format);
+        std::process::exit(1);
+    }
+
     let result = match cli.command {
         Commands::Init => {
             let base = find_repo_root().unwrap_or_else(|_| {
@@ -122,21 +629,173 @@ fn main() {
             commands::init::run(&base)
         }
 
-        Commands::Work(WorkCommands::New { title, id, stack }) => {
+        Commands::Work(WorkCommands::New { title, id, stack, epic, work_type }) => {
             let base = find_repo_root().unwrap_or_else(|e| {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
             });
-            commands::work::new_work(&base, &title, id.as_deref(), stack.as_deref())
+            let work_type: state::WorkType = work_type.parse().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            if epic && work_type != state::WorkType::Feature {
+                eprintln!("error: --epic only applies to features");
+                std::process::exit(1);
+            }
+            commands::work::new_typed(&base, &title, id.as_deref(), stack.as_deref(), work_type).and_then(|id| {
+                if epic {
+                    commands::epic::make_epic(&base, &id)
+                } else {
+                    Ok(())
+                }
+            })
+        }
+
+        Commands::Work(WorkCommands::Import { file, dry_run }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::import::parse_file(&file)
+                .and_then(|entries| commands::import::import(&base, &entries, dry_run))
                 .map(|_| ())
         }
 
-        Commands::Work(WorkCommands::List) => {
+        Commands::Work(WorkCommands::Export { work_id, output }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.tar", work_id)));
+            commands::bundle::export(&base, &work_id, &output).map(|_| ())
+        }
+
+        Commands::Work(WorkCommands::ImportBundle { file }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::bundle::import_bundle(&base, &file).map(|_| ())
+        }
+
+        Commands::Work(WorkCommands::Split { epic_id }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::epic::split(&base, &epic_id).map(|created| {
+                if created.is_empty() {
+                    println!("every task in {} already has a child work item", epic_id);
+                }
+            })
+        }
+
+        Commands::Work(WorkCommands::List { status, owner, gates, since, sort, archived }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::work::ListFilter::parse(status.as_deref(), owner.as_deref(), &gates, since.as_deref(), &sort)
+                .and_then(|filter| {
+                    let filter = commands::work::ListFilter { archived, ..filter };
+                    commands::work::list_work(&base, &filter, format)
+                })
+        }
+
+        Commands::Work(WorkCommands::Search { text }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::work::search_work(&base, &text, format)
+        }
+
+        Commands::Work(WorkCommands::Close { work_id, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Close, &reason, false)
+        }
+
+        Commands::Work(WorkCommands::Block { work_id, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Block, &reason, false)
+        }
+
+        Commands::Work(WorkCommands::Unblock { work_id, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Unblock, &reason, false)
+        }
+
+        Commands::Work(WorkCommands::Reopen { work_id, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Reopen, &reason, false)
+        }
+
+        Commands::Work(WorkCommands::Archive { work_id, reason, force }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Archive, &reason, force)
+        }
+
+        Commands::Work(WorkCommands::Rm { work_id, reason, force }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lifecycle::run(&base, &work_id, commands::lifecycle::Action::Remove, &reason, force)
+        }
+
+        Commands::Work(WorkCommands::Graph) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::deps::graph(&base, format)
+        }
+
+        Commands::Work(WorkCommands::Dep(DepCommands::Add { work_id, upstream })) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::deps::add(&base, &work_id, &upstream)
+        }
+
+        Commands::Work(WorkCommands::Dep(DepCommands::Rm { work_id, upstream })) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::deps::remove(&base, &work_id, &upstream)
+        }
+
+        Commands::Work(WorkCommands::Component(ComponentCommands::Add { work_id, name, path, stack })) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::work::add_component(&base, &work_id, &name, &path, stack.as_deref())
+        }
+
+        Commands::Work(WorkCommands::Component(ComponentCommands::Rm { work_id, name })) => {
             let base = find_repo_root().unwrap_or_else(|e| {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
             });
-            commands::work::list_work(&base)
+            commands::work::remove_component(&base, &work_id, &name)
         }
 
         Commands::Agent(AgentCommands::Start { role, work_id }) => {
@@ -163,15 +822,153 @@ fn main() {
             commands::agent::nudge(&base, &role, &work_id)
         }
 
+        Commands::Config(ConfigCommands::Get { key, origin, work }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::config::get(&base, &key, origin, work.as_deref(), format)
+        }
+
+        Commands::Config(ConfigCommands::Set { key, value, global, local, work }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            let scope = match (global, local, work) {
+                (_, _, Some(work_id)) => commands::config::Scope::Work(work_id),
+                (true, _, _) => commands::config::Scope::Global,
+                (_, true, _) => commands::config::Scope::Local,
+                _ => commands::config::Scope::Repo,
+            };
+            commands::config::set(&base, &key, &value, &scope)
+        }
+
+        Commands::Config(ConfigCommands::Show { origin, work }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::config::show(&base, origin, work.as_deref(), format)
+        }
+
+        Commands::Prompt(PromptCommands::Render { role, work_id, nudge }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::prompt::render(&base, &role, &work_id, nudge)
+        }
+
+        Commands::Prompt(PromptCommands::Context { role, work_id, budget }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::prompt::context(&base, &role, &work_id, budget)
+        }
+
+        Commands::Note(NoteCommands::Add { work_id, text, kind }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            kind.parse::<state::NoteKind>()
+                .and_then(|kind| commands::note::add(&base, &work_id, &text, kind))
+                .map(|_| ())
+        }
+
+        Commands::Approve { work_id, gate, comment } => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            approval::decide(&base, &work_id, &gate, approval::Decision::Approved, &comment)
+        }
+
+        Commands::Reject { work_id, gate, comment } => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            approval::decide(&base, &work_id, &gate, approval::Decision::Rejected, &comment)
+        }
+
+        Commands::Gate(GateCommands::Set { work_id, gate, status, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            status
+                .parse::<state::GateStatus>()
+                .and_then(|status| commands::gate::set(&base, &work_id, &gate, status, &reason))
+        }
+
+        Commands::Gate(GateCommands::Skip { work_id, gate, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::gate::skip(&base, &work_id, &gate, &reason)
+        }
+
+        Commands::Gate(GateCommands::Reset { work_id, from, reason }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::gate::reset(&base, &work_id, &from, &reason).map(|_| ())
+        }
+
+        Commands::Stack(StackCommands::Detect { path, explain }) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::stack::detect(&base, path.as_deref(), explain, format)
+        }
+
+        Commands::Stack(StackCommands::List) => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::stack::list(&base, format)
+        }
+
+        Commands::Doctor => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::doctor::run(&base, format)
+        }
+
+        Commands::Lint { work_id } => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::lint::run(&base, &work_id, format)
+        }
+
+        Commands::Trace { work_id } => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::trace::run(&base, &work_id, format)
+        }
+
         Commands::Check { work_id } => {
             let base = find_repo_root().unwrap_or_else(|e| {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
             });
-            commands::check::run(&base, &work_id)
+            commands::check::run(&base, &work_id, format)
         }
 
-        Commands::Run { work_id, to, mode } => {
+        Commands::Run { work_id, to, mode, wait, git } => {
             let base = find_repo_root().unwrap_or_else(|e| {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
@@ -180,7 +977,8 @@ fn main() {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
             });
-            commands::run::run(&base, &work_id, to.as_deref(), mode)
+            commands::git_gate::GitGateOptions::resolve(&base, git.squash, git.base, git.no_push)
+                .and_then(|git| commands::run::run(&base, &work_id, to.as_deref(), mode, wait, &git))
         }
 
         Commands::Status { work_id } => {
@@ -188,7 +986,16 @@ fn main() {
                 eprintln!("error: {}", e);
                 std::process::exit(1);
             });
-            commands::status::show(&base, &work_id)
+            commands::status::show(&base, &work_id, format)
+        }
+
+        Commands::Git { work_id, git } => {
+            let base = find_repo_root().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            });
+            commands::git_gate::GitGateOptions::resolve(&base, git.squash, git.base, git.no_push)
+                .and_then(|opts| commands::git_gate::run(&base, &work_id, &opts))
         }
     };
 
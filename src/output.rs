@@ -0,0 +1,74 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Table => write!(f, "table"),
+            Format::Json => write!(f, "json"),
+            Format::Yaml => write!(f, "yaml"),
+        }
+    }
+}
+
+impl Format {
+    /// Progress messages are only printed for tables, so JSON and YAML stay parseable
+    pub fn is_table(self) -> bool {
+        self == Format::Table
+    }
+
+    /// Print `view` in this format, drawing tables with `table`
+    pub fn emit<T: Serialize>(self, view: &T, table: impl FnOnce(&T)) -> Result<(), String> {
+        match self {
+            Format::Table => table(view),
+            Format::Json => println!("{}", to_json(view)?),
+            Format::Yaml => print!("{}", to_yaml(view)?),
+        }
+        Ok(())
+    }
+}
+
+pub fn to_json<T: Serialize>(view: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(view).map_err(|e| format!("failed to serialize output: {}", e))
+}
+
+pub fn to_yaml<T: Serialize>(view: &T) -> Result<String, String> {
+    serde_yaml::to_string(view).map_err(|e| format!("failed to serialize output: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct View {
+        id: &'static str,
+        done: usize,
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(Format::from_str("json", false).unwrap(), Format::Json);
+        assert_eq!(Format::from_str("yaml", false).unwrap(), Format::Yaml);
+        assert_eq!(Format::from_str("table", false).unwrap(), Format::Table);
+        assert!(Format::from_str("xml", false).is_err());
+        for format in Format::value_variants() {
+            assert_eq!(Format::from_str(&format.to_string(), false).unwrap(), *format);
+        }
+    }
+
+    #[test]
+    fn test_serialize_view() {
+        let view = View { id: "FEAT-x", done: 3 };
+        assert_eq!(to_json(&view).unwrap(), "{\n  \"id\": \"FEAT-x\",\n  \"done\": 3\n}");
+        assert_eq!(to_yaml(&view).unwrap(), "id: FEAT-x\ndone: 3\n");
+    }
+}
@@ -0,0 +1,646 @@
+use crate::state::{default_pipeline, read_state, role_to_gate, Role};
+use crate::templates;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Var(String),
+    Open(String, String),
+    Else,
+    Close(String),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Var(String),
+    If {
+        cond: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        path: String,
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Truthy(String),
+    Falsy(String),
+    Eq(String, String),
+    Ne(String, String),
+}
+
+/// Render `template` against a JSON context. Supports `{{work.id}}` (dotted paths),
+/// `{{#if x}}…{{else}}…{{/if}}` (also `x == "v"` and `!=`), `{{#unless x}}` and
+/// `{{#each list}}…{{this.field}}…{{/each}}`. A block tag alone on a line consumes the line.
+pub fn render(template: &str, context: &Value) -> Result<String, String> {
+    let tokens = strip_standalone(tokenize(template)?);
+    let mut pos = 0;
+    let nodes = parse(&tokens, &mut pos, None)?;
+    let mut out = String::new();
+    render_nodes(&nodes, context, None, &mut out)?;
+    Ok(out)
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("unclosed tag near: {}", truncate(&rest[start..], 40)))?;
+        let tag = after[..end].trim();
+        tokens.push(classify(tag)?);
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+fn classify(tag: &str) -> Result<Token, String> {
+    if tag == "else" {
+        return Ok(Token::Else);
+    }
+    if let Some(block) = tag.strip_prefix('#') {
+        let (name, args) = block.split_once(char::is_whitespace).unwrap_or((block, ""));
+        return match name {
+            "if" | "unless" | "each" if !args.trim().is_empty() => {
+                Ok(Token::Open(name.to_string(), args.trim().to_string()))
+            }
+            "if" | "unless" | "each" => Err(format!("{{{{#{}}}}} needs an argument", name)),
+            other => Err(format!("unknown block: #{}", other)),
+        };
+    }
+    if let Some(name) = tag.strip_prefix('/') {
+        return Ok(Token::Close(name.trim().to_string()));
+    }
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        return Err(format!("invalid tag: {{{{{}}}}}", tag));
+    }
+    Ok(Token::Var(tag.to_string()))
+}
+
+/// Remove the surrounding whitespace and newline of block tags that are alone on their line
+fn strip_standalone(mut tokens: Vec<Token>) -> Vec<Token> {
+    let is_block = |t: &Token| matches!(t, Token::Open(..) | Token::Else | Token::Close(_));
+
+    // Decide on the original tokens first so adjacent block lines don't affect each other
+    let standalone: Vec<bool> = (0..tokens.len())
+        .map(|i| {
+            if !is_block(&tokens[i]) {
+                return false;
+            }
+            let before_ok = match i.checked_sub(1).map(|j| &tokens[j]) {
+                None => true,
+                Some(Token::Text(t)) => {
+                    let tail = &t[t.rfind('\n').map(|p| p + 1).unwrap_or(0)..];
+                    tail.trim().is_empty() && (t.contains('\n') || i == 1)
+                }
+                Some(_) => false,
+            };
+            let after_ok = match tokens.get(i + 1) {
+                None => true,
+                Some(Token::Text(t)) => t[..t.find('\n').unwrap_or(t.len())].trim().is_empty(),
+                Some(_) => false,
+            };
+            before_ok && after_ok
+        })
+        .collect();
+
+    for (i, _) in standalone.iter().enumerate().filter(|(_, s)| **s) {
+        if let Some(Token::Text(t)) = i.checked_sub(1).and_then(|j| tokens.get_mut(j)) {
+            let keep = t.rfind('\n').map(|p| p + 1).unwrap_or(0);
+            t.truncate(keep);
+        }
+        if let Some(Token::Text(t)) = tokens.get_mut(i + 1) {
+            *t = match t.find('\n') {
+                Some(p) => t[p + 1..].to_string(),
+                None => String::new(),
+            };
+        }
+    }
+    tokens
+}
+
+fn parse(tokens: &[Token], pos: &mut usize, open: Option<&str>) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    while *pos < tokens.len() {
+        let token = tokens[*pos].clone();
+        *pos += 1;
+        match token {
+            Token::Text(t) => nodes.push(Node::Text(t)),
+            Token::Var(v) => nodes.push(Node::Var(v)),
+            Token::Open(kind, args) => {
+                let body = parse(tokens, pos, Some(&kind))?;
+                let mut otherwise = Vec::new();
+                if matches!(tokens.get(*pos - 1), Some(Token::Else)) {
+                    if kind == "each" {
+                        return Err("{{else}} is not supported inside {{#each}}".into());
+                    }
+                    otherwise = parse(tokens, pos, Some(&kind))?;
+                }
+                nodes.push(match kind.as_str() {
+                    "each" => Node::Each { path: args, body },
+                    "unless" => Node::If {
+                        cond: Condition::Falsy(args),
+                        then: body,
+                        otherwise,
+                    },
+                    _ => Node::If {
+                        cond: parse_condition(&args)?,
+                        then: body,
+                        otherwise,
+                    },
+                });
+            }
+            Token::Else => {
+                return match open {
+                    Some(_) => Ok(nodes),
+                    None => Err("{{else}} outside of a block".into()),
+                };
+            }
+            Token::Close(name) => {
+                return match open {
+                    Some(kind) if kind == name => Ok(nodes),
+                    Some(kind) => Err(format!("expected {{{{/{}}}}}, found {{{{/{}}}}}", kind, name)),
+                    None => Err(format!("unexpected {{{{/{}}}}}", name)),
+                };
+            }
+        }
+    }
+    match open {
+        Some(kind) => Err(format!("missing {{{{/{}}}}}", kind)),
+        None => Ok(nodes),
+    }
+}
+
+fn parse_condition(args: &str) -> Result<Condition, String> {
+    for (op, ctor) in [("!=", Condition::Ne as fn(String, String) -> Condition), ("==", Condition::Eq)] {
+        if let Some((lhs, rhs)) = args.split_once(op) {
+            let rhs = rhs.trim();
+            let literal = rhs
+                .strip_prefix('"')
+                .and_then(|r| r.strip_suffix('"'))
+                .ok_or_else(|| format!("right side of '{}' must be a quoted string: {}", op, args))?;
+            return Ok(ctor(lhs.trim().to_string(), literal.to_string()));
+        }
+    }
+    Ok(Condition::Truthy(args.to_string()))
+}
+
+fn render_nodes(nodes: &[Node], ctx: &Value, this: Option<&Value>, out: &mut String) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Var(path) => out.push_str(&to_text(lookup(ctx, this, path)?)),
+            Node::If { cond, then, otherwise } => {
+                let holds = match cond {
+                    Condition::Truthy(p) => truthy(lookup(ctx, this, p)?),
+                    Condition::Falsy(p) => !truthy(lookup(ctx, this, p)?),
+                    Condition::Eq(p, lit) => to_text(lookup(ctx, this, p)?) == *lit,
+                    Condition::Ne(p, lit) => to_text(lookup(ctx, this, p)?) != *lit,
+                };
+                render_nodes(if holds { then } else { otherwise }, ctx, this, out)?;
+            }
+            Node::Each { path, body } => match lookup(ctx, this, path)? {
+                Value::Array(items) => {
+                    for item in items {
+                        render_nodes(body, ctx, Some(item), out)?;
+                    }
+                }
+                Value::Null => {}
+                _ => return Err(format!("{{{{#each {}}}}} needs a list", path)),
+            },
+        }
+    }
+    Ok(())
+}
+
+fn lookup<'a>(ctx: &'a Value, this: Option<&'a Value>, path: &str) -> Result<&'a Value, String> {
+    let (root, rest) = match (path.split_once('.'), this) {
+        (Some(("this", rest)), Some(item)) => (item, Some(rest)),
+        (None, Some(item)) if path == "this" => (item, None),
+        _ => (ctx, Some(path)),
+    };
+    let Some(rest) = rest else {
+        return Ok(root);
+    };
+    rest.split('.')
+        .try_fold(root, |v, part| v.get(part))
+        .ok_or_else(|| format!("unknown variable: {}", path))
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(to_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+/// Load `.pfm/prompts/<name>` if it has been edited, falling back to the built-in template.
+/// A copy that is still exactly what `pfm init` wrote is ignored, so repos pick up
+/// improvements to the built-in templates.
+pub fn load_template(base: &Path, name: &str) -> Result<String, String> {
+    if let Some(content) = edited_template(base, name)? {
+        return Ok(content);
+    }
+    builtin_template(name)
+        .map(str::to_string)
+        .ok_or_else(|| format!("unknown prompt template: {}", name))
+}
+
+/// The repo's copy of a prompt template, unless it is missing or unedited
+pub fn edited_template(base: &Path, name: &str) -> Result<Option<String>, String> {
+    let path = base.join(".pfm/prompts").join(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    Ok((!is_stock(base, name, &content)).then_some(content))
+}
+
+/// Fingerprints of the stock copies `pfm init` wrote, by template name
+fn stock_path(base: &Path) -> std::path::PathBuf {
+    base.join(".pfm/prompts/.stock.json")
+}
+
+fn fingerprint(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn read_stock(base: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(stock_path(base))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Whether `content` is the stock copy `pfm init` wrote for this template
+pub fn is_stock(base: &Path, name: &str, content: &str) -> bool {
+    read_stock(base).get(name).is_some_and(|hash| *hash == fingerprint(content))
+}
+
+/// Write the built-in template to `.pfm/prompts/<name>` and remember it as a stock copy
+pub fn write_stock(base: &Path, name: &str) -> Result<(), String> {
+    let content = builtin_template(name).ok_or_else(|| format!("unknown prompt template: {}", name))?;
+    let path = base.join(".pfm/prompts").join(name);
+    fs::write(&path, content).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    let mut stock = read_stock(base);
+    stock.insert(name.to_string(), fingerprint(content));
+    let json = serde_json::to_string_pretty(&stock).map_err(|e| format!("failed to serialize prompt fingerprints: {}", e))?;
+    fs::write(stock_path(base), json + "\n").map_err(|e| format!("failed to write {}: {}", stock_path(base).display(), e))
+}
+
+fn builtin_template(name: &str) -> Option<&'static str> {
+    templates::prompt_templates()
+        .into_iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, content)| content)
+}
+
+/// Render a named prompt. A broken override in `.pfm/prompts/` is reported and
+/// the built-in template is used instead, so an agent can always be started.
+pub fn render_named(base: &Path, name: &str, context: &Value) -> String {
+    let rendered = load_template(base, name).and_then(|tpl| render(&tpl, context));
+    match rendered {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("warning: prompt template {}: {} — using built-in template", name, e);
+            let builtin = builtin_template(name).expect("built-in prompt template");
+            render(builtin, context).expect("built-in prompt template renders")
+        }
+    }
+}
+
+/// Variables available to prompt templates for a work item (and optionally a role)
+pub fn context(base: &Path, work_dir: &Path, role: Option<&Role>) -> Value {
+    let state = read_state(&work_dir.join("state.json")).ok();
+    let role_name = role.map(|r| r.to_string()).unwrap_or_default();
+    let gate = role.map(role_to_gate).unwrap_or("");
+
+    let work = match &state {
+        Some(s) => json!({
+            "id": s.id,
+            "title": s.title,
+            "repo": s.repo,
+            "branch": s.branch,
+            "status": s.status.to_string(),
+            "owner": s.owner.to_string(),
+            "updated_at": s.updated_at,
+        }),
+        None => json!({
+            "id": work_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            "title": "", "repo": "", "branch": "", "status": "", "owner": "", "updated_at": "",
+        }),
+    };
+
+    let pipeline = state
+        .as_ref()
+        .map(|s| s.pipeline.clone())
+        .unwrap_or_else(default_pipeline);
+    let mut gates = Map::new();
+    for gate_name in &pipeline {
+        let status = state
+            .as_ref()
+            .and_then(|s| s.gates.get(gate_name))
+            .map(|g| g.to_string())
+            .unwrap_or_default();
+        gates.insert(gate_name.to_string(), Value::String(status));
+    }
+    let gate_status = gates.get(gate).cloned().unwrap_or(Value::String(String::new()));
+
+    let commands = match &state {
+        Some(s) => json!({
+            "verify": s.commands.verify,
+            "security": s.commands.security,
+            "qa_smoke": s.commands.qa_smoke,
+        }),
+        None => json!({ "verify": "", "security": "", "qa_smoke": "" }),
+    };
+
+    // Notes the role should see, oldest first; without a role every note is shown
+    let notes: Vec<Value> = state
+        .iter()
+        .flat_map(|s| &s.notes)
+        .filter(|n| role.map(|r| n.kind.relevant_to(r)).unwrap_or(true))
+        .map(|n| json!({ "kind": n.kind.to_string(), "text": n.text, "author": n.author, "at": n.at }))
+        .collect();
+
+    json!({
+        "role": role_name,
+        "gate": gate,
+        "gate_status": gate_status,
+        "role_spec": base.join(".pfm/roles").join(format!("{}.md", role_name)).display().to_string(),
+        "work_dir": work_dir.display().to_string(),
+        "pfm_dir": base.join(".pfm").display().to_string(),
+        "work": work,
+        "gates": gates,
+        "pipeline": pipeline,
+        "commands": commands,
+        "latest_handoff": latest_handoff(&work_dir.join("handoffs"))
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        "check": check_summary(&work_dir.join("artifacts/check.json")),
+        "notes": notes,
+        "context_pack": role.map(|r| context_pack(base, work_dir, r)).unwrap_or_default(),
+    })
+}
+
+/// Path of the role's context pack, if packing is enabled and the pack was built
+pub fn context_pack(base: &Path, work_dir: &Path, role: &Role) -> String {
+    let enabled = crate::config::load(base)
+        .map(|c| c.config.context.enabled)
+        .unwrap_or(false);
+    let path = crate::context::pack_path(work_dir, role);
+    if enabled && path.exists() {
+        path.display().to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// The newest handoff note (handoff file names start with a sortable timestamp)
+pub fn latest_handoff(handoffs_dir: &Path) -> Option<std::path::PathBuf> {
+    fs::read_dir(handoffs_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "md").unwrap_or(false))
+        .max_by_key(|p| p.file_name().map(|n| n.to_os_string()))
+}
+
+/// Summarize the last `pfm check` run recorded in artifacts/check.json
+fn check_summary(path: &Path) -> Value {
+    let recorded: Option<Value> = fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    let Some(recorded) = recorded else {
+        return json!({ "ran": false, "passed": false, "at": "", "failed": [] });
+    };
+
+    let mut failed = Vec::new();
+    for suite in recorded["suites"].as_array().into_iter().flatten() {
+        for result in suite["results"].as_array().into_iter().flatten() {
+            if result["passed"] == Value::Bool(false) {
+                let kind = result["kind"].as_str().unwrap_or("?");
+                failed.push(Value::String(match suite["component"].as_str() {
+                    Some(component) => format!("{}: {}", component, kind),
+                    None => kind.to_string(),
+                }));
+            }
+        }
+    }
+
+    json!({
+        "ran": true,
+        "passed": recorded["passed"].as_bool().unwrap_or(false),
+        "at": recorded["at"].as_str().unwrap_or(""),
+        "failed": failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::state::{Note, NoteKind};
+
+    #[test]
+    fn test_variables() {
+        let ctx = json!({"role": "qa", "work": {"id": "FEAT-1"}});
+        assert_eq!(render("{{role}} on {{ work.id }}", &ctx).unwrap(), "qa on FEAT-1");
+    }
+
+    #[test]
+    fn test_unknown_variable_errors() {
+        let ctx = json!({"role": "qa"});
+        let err = render("{{rol}}", &ctx).unwrap_err();
+        assert!(err.contains("unknown variable: rol"));
+    }
+
+    #[test]
+    fn test_if_else_and_comparison() {
+        let ctx = json!({"check": {"ran": true}, "gate_status": "fail", "handoff": ""});
+        let tpl = "{{#if check.ran}}ran{{else}}never{{/if}} {{#if gate_status == \"fail\"}}retry{{/if}}{{#if gate_status != \"fail\"}}ok{{/if}} {{#unless handoff}}none{{/unless}}";
+        assert_eq!(render(tpl, &ctx).unwrap(), "ran retry none");
+    }
+
+    #[test]
+    fn test_each() {
+        let ctx = json!({"roles": [{"role": "test", "gate": "tests"}, {"role": "qa", "gate": "qa"}]});
+        let tpl = "{{#each roles}}- {{this.role}} ({{this.gate}})\n{{/each}}";
+        assert_eq!(render(tpl, &ctx).unwrap(), "- test (tests)\n- qa (qa)\n");
+    }
+
+    #[test]
+    fn test_standalone_block_lines_are_removed() {
+        let ctx = json!({"a": true, "b": false});
+        let tpl = "start\n{{#if a}}\nA\n{{/if}}\n{{#if b}}\nB\n{{/if}}\nend\n";
+        assert_eq!(render(tpl, &ctx).unwrap(), "start\nA\nend\n");
+    }
+
+    #[test]
+    fn test_nested_standalone_blocks() {
+        let ctx = json!({"a": true, "items": ["x", "y"]});
+        let tpl = "start\n{{#if a}}\n{{#each items}}\n- {{this}}\n{{/each}}\n{{/if}}\nend\n";
+        assert_eq!(render(tpl, &ctx).unwrap(), "start\n- x\n- y\nend\n");
+    }
+
+    #[test]
+    fn test_inline_blocks_keep_whitespace() {
+        let ctx = json!({"a": true});
+        assert_eq!(render("x {{#if a}}y{{/if}} z", &ctx).unwrap(), "x y z");
+    }
+
+    #[test]
+    fn test_single_braces_pass_through() {
+        let ctx = json!({"role": "qa"});
+        assert_eq!(render("{TIMESTAMP}-{{role}}.md", &ctx).unwrap(), "{TIMESTAMP}-qa.md");
+    }
+
+    #[test]
+    fn test_builtin_templates_render_with_empty_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-1");
+        let mut ctx = context(dir.path(), &work_dir, Some(&Role::Qa));
+        ctx["roles"] = json!([]);
+        for (name, _) in templates::prompt_templates() {
+            let text = render_named(dir.path(), name, &ctx);
+            assert!(!text.contains("{{"), "{} left tags behind", name);
+        }
+    }
+
+    #[test]
+    fn test_override_template_used() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".pfm/prompts")).unwrap();
+        fs::write(dir.path().join(".pfm/prompts/nudge.md"), "Hurry up, {{role}}!").unwrap();
+        let ctx = context(dir.path(), &dir.path().join("w"), Some(&Role::Test));
+        assert_eq!(render_named(dir.path(), "nudge.md", &ctx), "Hurry up, test!");
+    }
+
+    #[test]
+    fn test_unedited_init_copies_follow_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        let path = dir.path().join(".pfm/prompts/nudge.md");
+
+        // A stock copy from an older pfm is ignored, and refreshed by the next init
+        fs::write(&path, "Old stock nudge").unwrap();
+        let mut stock = read_stock(dir.path());
+        stock.insert("nudge.md".into(), fingerprint("Old stock nudge"));
+        fs::write(stock_path(dir.path()), serde_json::to_string(&stock).unwrap()).unwrap();
+        assert_eq!(load_template(dir.path(), "nudge.md").unwrap(), templates::PROMPT_NUDGE);
+        crate::commands::init::run(dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), templates::PROMPT_NUDGE);
+
+        // Once edited, the repo copy wins and init leaves it alone
+        fs::write(&path, "Hurry up, {{role}}!").unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        assert_eq!(load_template(dir.path(), "nudge.md").unwrap(), "Hurry up, {{role}}!");
+    }
+
+    #[test]
+    fn test_broken_override_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".pfm/prompts")).unwrap();
+        fs::write(dir.path().join(".pfm/prompts/nudge.md"), "{{#if role}}unclosed").unwrap();
+        let ctx = context(dir.path(), &dir.path().join("w"), Some(&Role::Test));
+        assert!(render_named(dir.path(), "nudge.md", &ctx).starts_with("Resume your work."));
+    }
+
+    #[test]
+    fn test_context_reads_check_results_and_handoff() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path().join("work");
+        fs::create_dir_all(work_dir.join("artifacts")).unwrap();
+        fs::create_dir_all(work_dir.join("handoffs")).unwrap();
+        fs::write(work_dir.join("handoffs/20260101-000000-prd.md"), "").unwrap();
+        fs::write(work_dir.join("handoffs/20260102-000000-test.md"), "").unwrap();
+        fs::write(
+            work_dir.join("artifacts/check.json"),
+            r#"{"at":"2026-01-02","passed":false,"suites":[
+                {"component":null,"results":[{"kind":"verify","passed":true}]},
+                {"component":"api","results":[{"kind":"security","passed":false}]}]}"#,
+        ).unwrap();
+
+        let ctx = context(dir.path(), &work_dir, Some(&Role::Implementation));
+        assert!(ctx["latest_handoff"].as_str().unwrap().ends_with("20260102-000000-test.md"));
+        assert_eq!(ctx["check"]["ran"], true);
+        assert_eq!(ctx["check"]["failed"], json!(["api: security"]));
+        assert_eq!(ctx["gate"], "impl");
+    }
+
+    #[test]
+    fn test_bootstrap_surfaces_notes_for_role() {
+        let dir = tempfile::tempdir().unwrap();
+        crate::commands::init::run(dir.path()).unwrap();
+        crate::commands::work::new_work(dir.path(), "Notes", Some("FEAT-1"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-1");
+        let path = work_dir.join("state.json");
+        let mut state = read_state(&path).unwrap();
+        state.notes = vec![
+            Note::new("users are on mobile", "Ada", NoteKind::Context),
+            Note::new("keep the v1 API", "Ada", NoteKind::Decision),
+            Note::new("staging DB is down", "", NoteKind::Blocker),
+        ];
+        crate::state::write_state(&path, &state).unwrap();
+
+        // The repo was initialized by a pfm whose bootstrap template had no notes
+        let old = "You are acting as the {{role}} agent.\n";
+        fs::write(dir.path().join(".pfm/prompts/bootstrap.md"), old).unwrap();
+        let mut stock = read_stock(dir.path());
+        stock.insert("bootstrap.md".into(), fingerprint(old));
+        fs::write(stock_path(dir.path()), serde_json::to_string(&stock).unwrap()).unwrap();
+
+        let ctx = context(dir.path(), &work_dir, Some(&Role::Prd));
+        assert_eq!(ctx["notes"].as_array().unwrap().len(), 3);
+
+        let ctx = context(dir.path(), &work_dir, Some(&Role::Implementation));
+        let text = render_named(dir.path(), "bootstrap.md", &ctx);
+        assert!(!text.contains("users are on mobile"));
+        assert!(text.contains("- [decision] keep the v1 API — Ada\n"));
+        assert!(text.contains("- [blocker] staging DB is down\n"));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let ctx = json!({"a": true});
+        assert!(render("{{#if a}}open", &ctx).is_err());
+        assert!(render("{{/if}}", &ctx).is_err());
+        assert!(render("{{#if a}}x{{/each}}", &ctx).is_err());
+        assert!(render("{{unclosed", &ctx).is_err());
+        assert!(render("{{#while a}}{{/while}}", &ctx).is_err());
+    }
+}
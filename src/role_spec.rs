@@ -0,0 +1,238 @@
+use crate::state::{role_to_gate, GateStatus, Role};
+use std::fs;
+use std::path::Path;
+
+/// What a role needs before it starts and must leave behind when it finishes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoleContract {
+    pub gate: Option<String>,
+    /// Files that must exist before the role starts
+    pub inputs: Vec<String>,
+    /// Files that must be filled in (not missing, empty or still template placeholders) for `pass`
+    pub outputs: Vec<String>,
+    /// Terminal statuses the role may set; empty means any
+    pub statuses: Vec<GateStatus>,
+}
+
+/// Split a role spec into its front matter (if any) and body
+fn front_matter(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n"))?;
+    if rest.starts_with("---") {
+        return Some("");
+    }
+    let end = rest.find("\n---")?;
+    Some(&rest[..end + 1])
+}
+
+/// Parse a role spec's front matter (`gate:`, `inputs:`, `outputs:` and `statuses:` between
+/// `---` lines; lists inline or one `- item` per line). Specs without front matter have no contract.
+pub fn parse(content: &str) -> Result<Option<RoleContract>, String> {
+    let Some(block) = front_matter(content) else {
+        return Ok(None);
+    };
+
+    let mut contract = RoleContract::default();
+    let mut current_list: Option<String> = None;
+    for (n, raw) in block.lines().enumerate() {
+        let line = raw.trim_end();
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            let key = current_list
+                .clone()
+                .ok_or_else(|| format!("line {}: list item outside a list", n + 2))?;
+            add_value(&mut contract, &key, item.trim())?;
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("line {}: expected `key: value`", n + 2))?;
+        let (key, value) = (key.trim(), value.trim());
+        current_list = None;
+        match key {
+            "gate" => contract.gate = Some(value.to_string()),
+            "inputs" | "outputs" | "statuses" => {
+                if value.is_empty() {
+                    current_list = Some(key.to_string());
+                } else {
+                    let inner = value
+                        .strip_prefix('[')
+                        .and_then(|v| v.strip_suffix(']'))
+                        .ok_or_else(|| format!("line {}: {} must be a [list]", n + 2, key))?;
+                    for item in inner.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+                        add_value(&mut contract, key, item)?;
+                    }
+                }
+            }
+            other => return Err(format!("line {}: unknown key '{}'", n + 2, other)),
+        }
+    }
+    Ok(Some(contract))
+}
+
+fn add_value(contract: &mut RoleContract, key: &str, item: &str) -> Result<(), String> {
+    let item = item.trim_matches(|c| c == '"' || c == '\'');
+    match key {
+        "inputs" => contract.inputs.push(item.to_string()),
+        "outputs" => contract.outputs.push(item.to_string()),
+        "statuses" => {
+            let status: GateStatus = serde_json::from_value(serde_json::Value::String(item.into()))
+                .map_err(|_| format!("unknown status '{}'", item))?;
+            if !status.is_terminal() {
+                return Err(format!("'{}' is not a terminal status", item));
+            }
+            contract.statuses.push(status);
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Load and validate the contract for a role from `.pfm/roles/<role>.md`
+pub fn load(base: &Path, role: &Role) -> Result<Option<RoleContract>, String> {
+    let path = crate::commands::agent::role_spec_path(base, role);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+    let contract = parse(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if let Some(RoleContract { gate: Some(gate), .. }) = &contract {
+        if gate != role_to_gate(role) {
+            return Err(format!(
+                "{}: declares gate '{}' but role {} owns '{}'",
+                path.display(),
+                gate,
+                role,
+                role_to_gate(role)
+            ));
+        }
+    }
+    Ok(contract)
+}
+
+/// Inputs that are missing from the work item
+pub fn missing_inputs(contract: &RoleContract, work_dir: &Path) -> Vec<String> {
+    contract
+        .inputs
+        .iter()
+        .filter(|input| !work_dir.join(input).exists())
+        .cloned()
+        .collect()
+}
+
+/// Reasons a role's completion can't be accepted; empty if the contract is met
+pub fn completion_problems(
+    contract: &RoleContract,
+    base: &Path,
+    work_dir: &Path,
+    status: &GateStatus,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !contract.statuses.is_empty() && !contract.statuses.contains(status) {
+        problems.push(format!(
+            "status '{}' is not allowed (expected {})",
+            status,
+            contract.statuses.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" or ")
+        ));
+    }
+    if *status == GateStatus::Pass {
+        for output in &contract.outputs {
+            if let Some(problem) = output_problem(base, work_dir, output) {
+                problems.push(format!("{} {}", output, problem));
+            }
+        }
+    }
+    problems
+}
+
+/// Why an output file doesn't count as written, if it doesn't
+fn output_problem(base: &Path, work_dir: &Path, output: &str) -> Option<String> {
+    let Ok(content) = fs::read_to_string(work_dir.join(output)) else {
+        return Some("is missing".into());
+    };
+    if content.trim().is_empty() {
+        return Some("is empty".into());
+    }
+    crate::lint::check_artifact(base, work_dir, output, &content)
+        .filter(|fill| !fill.is_filled())
+        .map(|fill| fill.describe())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_inline_and_block_lists() {
+        let spec = "---\ngate: qa\ninputs: [acceptance.md, \"state.json\"]\noutputs:\n  - qa.md\nstatuses: [pass, fail]\n---\n# Role: QA\n";
+        let contract = parse(spec).unwrap().unwrap();
+        assert_eq!(contract.gate.as_deref(), Some("qa"));
+        assert_eq!(contract.inputs, vec!["acceptance.md", "state.json"]);
+        assert_eq!(contract.outputs, vec!["qa.md"]);
+        assert_eq!(contract.statuses, vec![GateStatus::Pass, GateStatus::Fail]);
+    }
+
+    #[test]
+    fn test_parse_without_front_matter() {
+        assert_eq!(parse("# Role: Docs\n\nWrite docs.").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("---\nowner: prd\n---\n").unwrap_err().contains("unknown key 'owner'"));
+        assert!(parse("---\nstatuses: [done]\n---\n").unwrap_err().contains("unknown status 'done'"));
+        assert!(parse("---\nstatuses: [todo]\n---\n").unwrap_err().contains("not a terminal status"));
+        assert!(parse("---\ninputs: prd.md\n---\n").unwrap_err().contains("must be a [list]"));
+    }
+
+    #[test]
+    fn test_builtin_specs_have_valid_contracts() {
+        for (filename, content) in crate::templates::role_specs() {
+            let contract = parse(content).unwrap().unwrap_or_else(|| panic!("{} has no contract", filename));
+            let role: Role = filename.trim_end_matches(".md").parse().unwrap();
+            assert_eq!(contract.gate.as_deref(), Some(role_to_gate(&role)), "{}", filename);
+        }
+    }
+
+    #[test]
+    fn test_gate_mismatch_rejected() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        fs::write(dir.path().join(".pfm/roles/docs.md"), "---\ngate: qa\n---\n").unwrap();
+        let err = load(dir.path(), &Role::Custom("docs".into())).unwrap_err();
+        assert!(err.contains("declares gate 'qa' but role docs owns 'docs'"));
+    }
+
+    #[test]
+    fn test_prd_pass_rejected_while_acceptance_is_template() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Login", Some("FEAT-RC"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-RC");
+        let contract = load(dir.path(), &Role::Prd).unwrap().unwrap();
+
+        fs::write(work_dir.join("prd.md"), "# PRD\n\nUsers can log in.\n").unwrap();
+        let problems = completion_problems(&contract, dir.path(), &work_dir, &GateStatus::Pass);
+        assert_eq!(problems, vec!["acceptance.md is still the template"]);
+
+        fs::write(work_dir.join("acceptance.md"), "- [ ] Login works\n").unwrap();
+        assert!(completion_problems(&contract, dir.path(), &work_dir, &GateStatus::Pass).is_empty());
+
+        let problems = completion_problems(&contract, dir.path(), &work_dir, &GateStatus::ChangesRequested);
+        assert!(problems[0].contains("status 'changes_requested' is not allowed"));
+    }
+
+    #[test]
+    fn test_missing_inputs() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("plan.md"), "# Plan").unwrap();
+        let contract = RoleContract {
+            inputs: vec!["plan.md".into(), "tasks.md".into()],
+            ..Default::default()
+        };
+        assert_eq!(missing_inputs(&contract, dir.path()), vec!["tasks.md"]);
+    }
+}
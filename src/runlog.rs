@@ -0,0 +1,31 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// Append an entry to the work item's runlog, creating the file if needed
+pub fn append(work_dir: &Path, entry: &str) -> Result<(), String> {
+    let path = work_dir.join("runlog.md");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    file.write_all(entry.as_bytes())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_append_creates_and_appends() {
+        let dir = tempdir().unwrap();
+        append(dir.path(), "\n## One\n").unwrap();
+        append(dir.path(), "\n## Two\n").unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("runlog.md")).unwrap(), "\n## One\n\n## Two\n");
+        assert!(append(&dir.path().join("missing"), "x").unwrap_err().starts_with("failed to open"));
+    }
+}
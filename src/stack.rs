@@ -0,0 +1,322 @@
+use crate::config::PfmConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Directories never descended into when matching globs
+const GLOB_SKIP_DIRS: &[&str] = &[".git", ".pfm", "node_modules", "target", "vendor", ".venv"];
+
+/// How deep glob matching walks below the repo root
+const GLOB_MAX_DEPTH: usize = 8;
+
+/// A stack detection rule, declared per stack in `config.json`:
+///   {"file_exists": "Gemfile"}
+///   {"file_contains": {"path": "package.json", "text": "react-native"}}
+///   {"glob": "**/*.py"}
+///   {"all": [...]} / {"any": [...]}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectRule {
+    FileExists(String),
+    FileContains { path: String, text: String },
+    Glob(String),
+    All(Vec<DetectRule>),
+    Any(Vec<DetectRule>),
+}
+
+impl std::fmt::Display for DetectRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DetectRule::FileExists(path) => write!(f, "file exists: {}", path),
+            DetectRule::FileContains { path, text } => {
+                write!(f, "{} contains \"{}\"", path, text)
+            }
+            DetectRule::Glob(pattern) => write!(f, "glob matches: {}", pattern),
+            DetectRule::All(rules) => write!(
+                f,
+                "all of ({})",
+                rules.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("; ")
+            ),
+            DetectRule::Any(rules) => write!(
+                f,
+                "any of ({})",
+                rules.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("; ")
+            ),
+        }
+    }
+}
+
+impl DetectRule {
+    pub fn matches(&self, base: &Path) -> bool {
+        match self {
+            DetectRule::FileExists(path) => base.join(path).exists(),
+            DetectRule::FileContains { path, text } => fs::read_to_string(base.join(path))
+                .map(|c| c.contains(text.as_str()))
+                .unwrap_or(false),
+            DetectRule::Glob(pattern) => glob_exists(base, pattern),
+            DetectRule::All(rules) => rules.iter().all(|r| r.matches(base)),
+            DetectRule::Any(rules) => rules.iter().any(|r| r.matches(base)),
+        }
+    }
+}
+
+/// Built-in detection rules and priorities for the stacks pfm ships with.
+/// Also used for configs written before stacks could declare their own rules.
+pub fn builtin_rules(stack: &str) -> Option<(Vec<DetectRule>, i32)> {
+    let file = |p: &str| DetectRule::FileExists(p.into());
+    match stack {
+        "rails" => Some((
+            vec![DetectRule::All(vec![
+                file("Gemfile"),
+                DetectRule::Any(vec![
+                    file("config/routes.rb"),
+                    file("bin/rails"),
+                    file("config/application.rb"),
+                ]),
+            ])],
+            50,
+        )),
+        "react_native" => Some((
+            vec![DetectRule::FileContains {
+                path: "package.json".into(),
+                text: "react-native".into(),
+            }],
+            40,
+        )),
+        "cli_node" => Some((vec![file("package.json")], 30)),
+        "rust" => Some((vec![file("Cargo.toml")], 20)),
+        "cli_ruby" => Some((vec![file("Gemfile")], 10)),
+        _ => None,
+    }
+}
+
+/// Result of evaluating one stack's rules against a directory
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub stack: String,
+    pub priority: i32,
+    pub rules: Vec<DetectRule>,
+    /// The first rule that matched, if any
+    pub matched: Option<DetectRule>,
+    /// True when the rules came from the built-in fallback rather than config
+    pub builtin: bool,
+}
+
+/// Evaluate every configured stack, highest priority first (ties broken by name)
+pub fn evaluate(base: &Path, config: &PfmConfig) -> Vec<Evaluation> {
+    let mut evaluations: Vec<Evaluation> = config
+        .stacks
+        .iter()
+        .map(|(name, stack)| {
+            let (rules, priority, builtin) = if stack.detect.is_empty() {
+                match builtin_rules(name) {
+                    Some((rules, priority)) => (rules, stack.priority.unwrap_or(priority), true),
+                    None => (vec![], stack.priority.unwrap_or(0), false),
+                }
+            } else {
+                (stack.detect.clone(), stack.priority.unwrap_or(0), false)
+            };
+            let matched = rules.iter().find(|r| r.matches(base)).cloned();
+            Evaluation {
+                stack: name.clone(),
+                priority,
+                rules,
+                matched,
+                builtin,
+            }
+        })
+        .collect();
+
+    evaluations.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.stack.cmp(&b.stack)));
+    evaluations
+}
+
+/// The highest-priority stack whose rules match `base`
+pub fn detect(base: &Path, config: &PfmConfig) -> Option<Evaluation> {
+    evaluate(base, config).into_iter().find(|e| e.matched.is_some())
+}
+
+/// True if any path under `base` matches the glob pattern
+fn glob_exists(base: &Path, pattern: &str) -> bool {
+    let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    walk_matches(base, &segments, 0)
+}
+
+fn walk_matches(dir: &Path, segments: &[&str], depth: usize) -> bool {
+    let Some((first, rest)) = segments.split_first() else {
+        return false;
+    };
+    if depth > GLOB_MAX_DEPTH {
+        return false;
+    }
+
+    if *first == "**" {
+        // Zero directories…
+        if walk_matches(dir, rest, depth) {
+            return true;
+        }
+        // …or one more directory, keeping `**` in play
+        return subdirs(dir)
+            .iter()
+            .any(|sub| walk_matches(&dir.join(sub), segments, depth + 1));
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !segment_matches(first, &name) {
+            continue;
+        }
+        if rest.is_empty() {
+            return true;
+        }
+        if entry.path().is_dir()
+            && !GLOB_SKIP_DIRS.contains(&name.as_str())
+            && walk_matches(&entry.path(), rest, depth + 1)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn subdirs(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|n| !GLOB_SKIP_DIRS.contains(&n.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Match one path segment against a pattern with `*` and `?` wildcards
+fn segment_matches(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StackConfig;
+    use tempfile::tempdir;
+
+    fn python_stack() -> StackConfig {
+        StackConfig {
+            verify: "pytest".into(),
+            security: "bandit -r .".into(),
+            detect: vec![
+                DetectRule::FileExists("pyproject.toml".into()),
+                DetectRule::Glob("**/*.py".into()),
+            ],
+            priority: Some(15),
+        }
+    }
+
+    #[test]
+    fn test_segment_matches() {
+        assert!(segment_matches("*.py", "app.py"));
+        assert!(segment_matches("go.?od", "go.mod"));
+        assert!(segment_matches("*", "anything"));
+        assert!(!segment_matches("*.py", "app.rb"));
+        assert!(!segment_matches("Cargo.toml", "Cargo.lock"));
+    }
+
+    #[test]
+    fn test_glob_nested() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/pkg")).unwrap();
+        fs::write(dir.path().join("src/pkg/main.py"), "").unwrap();
+        assert!(glob_exists(dir.path(), "**/*.py"));
+        assert!(glob_exists(dir.path(), "src/**/main.py"));
+        assert!(!glob_exists(dir.path(), "*.py"));
+        assert!(!glob_exists(dir.path(), "**/*.go"));
+    }
+
+    #[test]
+    fn test_glob_skips_vendor_dirs() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/x")).unwrap();
+        fs::write(dir.path().join("node_modules/x/setup.py"), "").unwrap();
+        assert!(!glob_exists(dir.path(), "**/*.py"));
+    }
+
+    #[test]
+    fn test_user_defined_stack_detected() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("service")).unwrap();
+        fs::write(dir.path().join("service/app.py"), "").unwrap();
+        let mut config = PfmConfig::default();
+        config.stacks.insert("python".into(), python_stack());
+
+        let detected = detect(dir.path(), &config).unwrap();
+        assert_eq!(detected.stack, "python");
+        assert_eq!(detected.matched, Some(DetectRule::Glob("**/*.py".into())));
+    }
+
+    #[test]
+    fn test_priority_wins() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Gemfile"), "").unwrap();
+        fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+        let mut config = PfmConfig::default();
+        config.stacks.insert("python".into(), python_stack());
+
+        // python (15) outranks cli_ruby (10)
+        assert_eq!(detect(dir.path(), &config).unwrap().stack, "python");
+    }
+
+    #[test]
+    fn test_builtin_fallback_for_configs_without_rules() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        let mut config = PfmConfig::default();
+        for stack in config.stacks.values_mut() {
+            stack.detect.clear();
+            stack.priority = None;
+        }
+        let detected = detect(dir.path(), &config).unwrap();
+        assert_eq!(detected.stack, "rust");
+        assert!(detected.builtin);
+    }
+
+    #[test]
+    fn test_rule_serialization() {
+        let rule = DetectRule::FileContains {
+            path: "package.json".into(),
+            text: "react-native".into(),
+        };
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(json, r#"{"file_contains":{"path":"package.json","text":"react-native"}}"#);
+        let parsed: DetectRule = serde_json::from_str(r#"{"glob":"**/*.go"}"#).unwrap();
+        assert_eq!(parsed, DetectRule::Glob("**/*.go".into()));
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -12,8 +13,13 @@ pub enum GateStatus {
     Pass,
     Fail,
     ChangesRequested,
+    /// Set by hand with `pfm gate skip`; satisfies the gate without running its role
+    Skipped,
 }
 
+/// Gate status names, as accepted by `pfm gate set`
+pub const GATE_STATUSES: &[&str] = &["todo", "in_progress", "pass", "fail", "changes_requested", "skipped"];
+
 impl std::fmt::Display for GateStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -22,13 +28,34 @@ impl std::fmt::Display for GateStatus {
             GateStatus::Pass => write!(f, "pass"),
             GateStatus::Fail => write!(f, "fail"),
             GateStatus::ChangesRequested => write!(f, "changes_requested"),
+            GateStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+impl std::str::FromStr for GateStatus {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "todo" => Ok(GateStatus::Todo),
+            "in_progress" => Ok(GateStatus::InProgress),
+            "pass" => Ok(GateStatus::Pass),
+            "fail" => Ok(GateStatus::Fail),
+            "changes_requested" => Ok(GateStatus::ChangesRequested),
+            "skipped" => Ok(GateStatus::Skipped),
+            _ => Err(format!("unknown gate status: {} (use {})", s, GATE_STATUSES.join(", "))),
         }
     }
 }
 
 impl GateStatus {
     pub fn is_terminal(&self) -> bool {
-        matches!(self, GateStatus::Pass | GateStatus::Fail | GateStatus::ChangesRequested)
+        matches!(self, GateStatus::Pass | GateStatus::Fail | GateStatus::ChangesRequested | GateStatus::Skipped)
+    }
+
+    /// Passed or skipped: the pipeline can move past this gate
+    pub fn is_satisfied(&self) -> bool {
+        matches!(self, GateStatus::Pass | GateStatus::Skipped)
     }
 }
 
@@ -51,9 +78,48 @@ impl std::fmt::Display for WorkStatus {
     }
 }
 
-/// Role names that own work
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// What kind of work an item is. Each type has its own pipeline, templates and ID prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
+pub enum WorkType {
+    #[default]
+    Feature,
+    Bug,
+    Chore,
+    Spike,
+}
+
+/// Work type names, as accepted by `pfm work new --type`
+pub const WORK_TYPES: &[&str] = &["feature", "bug", "chore", "spike"];
+
+impl std::fmt::Display for WorkType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkType::Feature => write!(f, "feature"),
+            WorkType::Bug => write!(f, "bug"),
+            WorkType::Chore => write!(f, "chore"),
+            WorkType::Spike => write!(f, "spike"),
+        }
+    }
+}
+
+impl std::str::FromStr for WorkType {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "feature" => Ok(WorkType::Feature),
+            "bug" => Ok(WorkType::Bug),
+            "chore" => Ok(WorkType::Chore),
+            "spike" => Ok(WorkType::Spike),
+            _ => Err(format!("unknown work type: {} (use {})", s, WORK_TYPES.join(", "))),
+        }
+    }
+}
+
+/// A role agent. The eight built-in roles ship with pfm; any other name refers to a
+/// custom role whose spec lives in `.pfm/roles/<name>.md` and which owns a gate of the same name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum Role {
     Prd,
     Orchestrator,
@@ -63,6 +129,13 @@ pub enum Role {
     ReviewSecurity,
     Qa,
     Git,
+    Custom(String),
+}
+
+impl Role {
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, Role::Custom(_))
+    }
 }
 
 impl std::fmt::Display for Role {
@@ -76,6 +149,7 @@ impl std::fmt::Display for Role {
             Role::ReviewSecurity => write!(f, "review_security"),
             Role::Qa => write!(f, "qa"),
             Role::Git => write!(f, "git"),
+            Role::Custom(name) => write!(f, "{}", name),
         }
     }
 }
@@ -92,11 +166,37 @@ impl std::str::FromStr for Role {
             "review_security" => Ok(Role::ReviewSecurity),
             "qa" => Ok(Role::Qa),
             "git" => Ok(Role::Git),
+            _ if is_valid_name(s) && gate_to_builtin_role(s).is_none() => Ok(Role::Custom(s.to_string())),
             _ => Err(format!("unknown role: {s}")),
         }
     }
 }
 
+impl TryFrom<String> for Role {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        role.to_string()
+    }
+}
+
+/// Custom role and gate names: lowercase letters, digits and underscores, starting with a letter
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The default pipeline for work items whose state predates configurable pipelines
+pub fn default_pipeline() -> Vec<String> {
+    GATE_ORDER.iter().map(|g| g.to_string()).collect()
+}
+
 /// Gate names in pipeline order
 pub const GATE_ORDER: &[&str] = &[
     "prd",
@@ -109,8 +209,22 @@ pub const GATE_ORDER: &[&str] = &[
     "git",
 ];
 
-/// Map gate name to the role that owns it
+/// Map gate name to the role that owns it. Gates outside the built-in set belong
+/// to the custom role of the same name.
 pub fn gate_to_role(gate: &str) -> Option<Role> {
+    match gate_to_builtin_role(gate) {
+        Some(role) => Some(role),
+        None if is_valid_name(gate) && !is_builtin_role_name(gate) => Some(Role::Custom(gate.to_string())),
+        None => None,
+    }
+}
+
+/// Built-in role names that differ from their gate names can't be reused as custom gates
+fn is_builtin_role_name(name: &str) -> bool {
+    matches!(name, "orchestrator" | "test" | "implementation")
+}
+
+fn gate_to_builtin_role(gate: &str) -> Option<Role> {
     match gate {
         "prd" => Some(Role::Prd),
         "plan" => Some(Role::Orchestrator),
@@ -125,7 +239,7 @@ pub fn gate_to_role(gate: &str) -> Option<Role> {
 }
 
 /// Map role to its owned gate
-pub fn role_to_gate(role: &Role) -> &'static str {
+pub fn role_to_gate(role: &Role) -> &str {
     match role {
         Role::Prd => "prd",
         Role::Orchestrator => "plan",
@@ -135,6 +249,7 @@ pub fn role_to_gate(role: &Role) -> &'static str {
         Role::ReviewSecurity => "review_security",
         Role::Qa => "qa",
         Role::Git => "git",
+        Role::Custom(name) => name,
     }
 }
 
@@ -150,6 +265,9 @@ pub struct Gates {
     pub review_security: GateStatus,
     pub qa: GateStatus,
     pub git: GateStatus,
+    /// Gates owned by custom roles
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, GateStatus>,
 }
 
 impl Default for Gates {
@@ -163,6 +281,7 @@ impl Default for Gates {
             review_security: GateStatus::Todo,
             qa: GateStatus::Todo,
             git: GateStatus::Todo,
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -178,7 +297,7 @@ impl Gates {
             "review_security" => Some(&self.review_security),
             "qa" => Some(&self.qa),
             "git" => Some(&self.git),
-            _ => None,
+            other => self.extra.get(other),
         }
     }
 
@@ -192,14 +311,24 @@ impl Gates {
             "review_security" => self.review_security = status,
             "qa" => self.qa = status,
             "git" => self.git = status,
-            _ => return false,
+            other => match self.extra.get_mut(other) {
+                Some(existing) => *existing = status,
+                None => return false,
+            },
         }
         true
     }
+
+    /// Add a custom gate (as todo) unless it already exists
+    pub fn ensure(&mut self, gate: &str) {
+        if self.get(gate).is_none() {
+            self.extra.insert(gate.to_string(), GateStatus::Todo);
+        }
+    }
 }
 
 /// Commands to run for verification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Commands {
     pub verify: String,
     pub security: String,
@@ -207,14 +336,14 @@ pub struct Commands {
     pub qa_smoke: String,
 }
 
-impl Default for Commands {
-    fn default() -> Self {
-        Commands {
-            verify: String::new(),
-            security: String::new(),
-            qa_smoke: String::new(),
-        }
-    }
+/// A sub-project of a monorepo touched by a work item, with its own stack and checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    /// Directory relative to the repo (or worktree) root
+    pub path: String,
+    pub stack: String,
+    pub commands: Commands,
 }
 
 /// Workspace pointers (runtime, optional)
@@ -228,6 +357,134 @@ pub struct Workspace {
     pub container: String,
 }
 
+/// A lifecycle change (close, block, archive, …) and why it was made
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub at: String,
+    pub action: String,
+    pub from: WorkStatus,
+    pub to: WorkStatus,
+    pub reason: String,
+}
+
+/// What a note is for. Kinds decide which role prompts surface the note.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteKind {
+    /// Background for planning, picked up by the PRD and orchestrator roles
+    #[default]
+    Context,
+    /// A choice every role should follow
+    Decision,
+    /// Something every role must resolve or work around before going on
+    Blocker,
+}
+
+/// Note kind names, as accepted by `pfm note add --kind`
+pub const NOTE_KINDS: &[&str] = &["context", "decision", "blocker"];
+
+impl NoteKind {
+    /// Whether notes of this kind go into the given role's prompt
+    pub fn relevant_to(&self, role: &Role) -> bool {
+        match self {
+            NoteKind::Context => matches!(role, Role::Prd | Role::Orchestrator),
+            NoteKind::Decision | NoteKind::Blocker => true,
+        }
+    }
+}
+
+impl std::fmt::Display for NoteKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoteKind::Context => write!(f, "context"),
+            NoteKind::Decision => write!(f, "decision"),
+            NoteKind::Blocker => write!(f, "blocker"),
+        }
+    }
+}
+
+impl std::str::FromStr for NoteKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "context" => Ok(NoteKind::Context),
+            "decision" => Ok(NoteKind::Decision),
+            "blocker" => Ok(NoteKind::Blocker),
+            _ => Err(format!("unknown note kind: {} (use {})", s, NOTE_KINDS.join(", "))),
+        }
+    }
+}
+
+/// A note left on a work item for the people and agents working on it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "NoteRecord")]
+pub struct Note {
+    pub text: String,
+    pub author: String,
+    pub at: String,
+    pub kind: NoteKind,
+}
+
+impl Note {
+    pub fn new(text: &str, author: &str, kind: NoteKind) -> Self {
+        Note {
+            text: text.to_string(),
+            author: author.to_string(),
+            at: Utc::now().to_rfc3339(),
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.kind, self.text)?;
+        if !self.author.is_empty() {
+            write!(f, " — {}", self.author)?;
+        }
+        Ok(())
+    }
+}
+
+/// Notes were plain strings before they had authors and kinds; those load as context notes
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NoteRecord {
+    Plain(String),
+    Full {
+        text: String,
+        #[serde(default)]
+        author: String,
+        #[serde(default)]
+        at: String,
+        #[serde(default)]
+        kind: NoteKind,
+    },
+}
+
+impl From<NoteRecord> for Note {
+    fn from(record: NoteRecord) -> Self {
+        match record {
+            NoteRecord::Plain(text) => Note {
+                text,
+                author: String::new(),
+                at: String::new(),
+                kind: NoteKind::Context,
+            },
+            NoteRecord::Full { text, author, at, kind } => Note { text, author, at, kind },
+        }
+    }
+}
+
+/// Working tree snapshots (git tree hashes) taken when a gate's agent started and
+/// finished, so each gate's changes can be diffed on their own
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GateRefs {
+    pub start: String,
+    #[serde(default)]
+    pub end: String,
+}
+
 /// The main state file for a work item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkState {
@@ -236,13 +493,44 @@ pub struct WorkState {
     pub repo: String,
     pub branch: String,
     pub status: WorkStatus,
+    #[serde(rename = "type", default)]
+    pub work_type: WorkType,
     pub owner: Role,
     pub updated_at: String,
     pub gates: Gates,
+    /// Gate order for this work item, snapshotted from config when it was created
+    #[serde(default = "default_pipeline")]
+    pub pipeline: Vec<String>,
+    /// Approval gates in the pipeline when the item was created, so removing one from
+    /// config later doesn't drop the sign-off
+    #[serde(default)]
+    pub approval_gates: Vec<String>,
     pub commands: Commands,
     pub workspace: Workspace,
     #[serde(default)]
-    pub notes: Vec<String>,
+    pub components: Vec<Component>,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Work items that must be done before this one runs
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Epics plan work and split it into children; their status rolls up from the children
+    #[serde(default)]
+    pub epic: bool,
+    /// The epic this item was split from
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub children: Vec<String>,
+    #[serde(default)]
+    pub gate_refs: BTreeMap<String, GateRefs>,
+    /// Lifecycle transitions, oldest first
+    #[serde(default)]
+    pub history: Vec<Transition>,
 }
 
 impl WorkState {
@@ -253,12 +541,24 @@ impl WorkState {
             repo: repo.to_string(),
             branch: format!("pfm/{}", id),
             status: WorkStatus::InProgress,
+            work_type: WorkType::Feature,
             owner: Role::Prd,
             updated_at: Utc::now().to_rfc3339(),
             gates: Gates::default(),
+            pipeline: default_pipeline(),
+            approval_gates: vec![],
             commands,
             workspace: Workspace::default(),
+            components: vec![],
             notes: vec![],
+            priority: None,
+            labels: vec![],
+            depends_on: vec![],
+            epic: false,
+            parent: None,
+            children: vec![],
+            gate_refs: BTreeMap::new(),
+            history: vec![],
         }
     }
 
@@ -266,17 +566,27 @@ impl WorkState {
         self.updated_at = Utc::now().to_rfc3339();
     }
 
-    /// Find next gate that isn't pass, in pipeline order
-    #[allow(dead_code)]
-    pub fn next_pending_gate(&self) -> Option<&'static str> {
-        for gate_name in GATE_ORDER {
-            if let Some(status) = self.gates.get(gate_name) {
-                if *status != GateStatus::Pass {
-                    return Some(gate_name);
-                }
-            }
+    /// Replace the pipeline, adding any custom gates it introduces and dropping those it leaves out
+    pub fn set_pipeline(&mut self, pipeline: Vec<String>) {
+        for gate in &pipeline {
+            self.gates.ensure(gate);
         }
-        None
+        self.gates.extra.retain(|gate, _| pipeline.contains(gate));
+        self.pipeline = pipeline;
+    }
+
+    /// Position of a gate in this work item's pipeline
+    pub fn gate_index(&self, gate: &str) -> Option<usize> {
+        self.pipeline.iter().position(|g| g == gate)
+    }
+
+    /// Find next gate that isn't passed or skipped, in pipeline order
+    #[allow(dead_code)]
+    pub fn next_pending_gate(&self) -> Option<&str> {
+        self.pipeline
+            .iter()
+            .find(|gate| self.gates.get(gate).map(|s| !s.is_satisfied()).unwrap_or(false))
+            .map(String::as_str)
     }
 }
 
@@ -284,8 +594,25 @@ impl WorkState {
 pub fn read_state(path: &Path) -> Result<WorkState, String> {
     let content = fs::read_to_string(path)
         .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+    let state: WorkState = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    check_gate_keys(&state).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    Ok(state)
+}
+
+/// Custom gate keys are read from whatever `gates` holds besides the built-ins, so a
+/// misspelled gate would otherwise be kept silently and never run
+fn check_gate_keys(state: &WorkState) -> Result<(), String> {
+    let pipeline: Vec<&str> = state.pipeline.iter().map(String::as_str).collect();
+    match state.gates.extra.keys().find(|g| !pipeline.contains(&g.as_str())) {
+        Some(gate) => {
+            let hint = crate::config::suggest(gate, &pipeline)
+                .map(|s| format!(" — did you mean '{}'?", s))
+                .unwrap_or_default();
+            Err(format!("gate '{}' is not in the pipeline ({}){}", gate, pipeline.join(", "), hint))
+        }
+        None => Ok(()),
+    }
 }
 
 /// Write state to a JSON file (pretty-printed)
@@ -352,7 +679,64 @@ mod tests {
         assert_eq!(gate_to_role("review_security"), Some(Role::ReviewSecurity));
         assert_eq!(gate_to_role("qa"), Some(Role::Qa));
         assert_eq!(gate_to_role("git"), Some(Role::Git));
-        assert_eq!(gate_to_role("nonexistent"), None);
+        assert_eq!(gate_to_role("docs"), Some(Role::Custom("docs".into())));
+        assert_eq!(gate_to_role("implementation"), None);
+        assert_eq!(gate_to_role("Not A Gate"), None);
+    }
+
+    #[test]
+    fn test_custom_role_parse_and_serde() {
+        let role: Role = "migration_review".parse().unwrap();
+        assert_eq!(role, Role::Custom("migration_review".into()));
+        assert_eq!(role_to_gate(&role), "migration_review");
+        assert_eq!(serde_json::to_string(&role).unwrap(), r#""migration_review""#);
+        assert_eq!(serde_json::to_string(&Role::ReviewSecurity).unwrap(), r#""review_security""#);
+        assert!("plan".parse::<Role>().is_err());
+        assert!("Docs".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_custom_gate_in_pipeline() {
+        let mut state = WorkState::new("FEAT-001", "Test", "repo", Commands::default());
+        let mut pipeline = default_pipeline();
+        pipeline.insert(5, "docs".into());
+        state.set_pipeline(pipeline);
+        for gate in ["prd", "plan", "env", "tests", "impl"] {
+            state.gates.set(gate, GateStatus::Pass);
+        }
+        assert_eq!(state.next_pending_gate(), Some("docs"));
+        assert!(state.gates.set("docs", GateStatus::Pass));
+        assert_eq!(state.next_pending_gate(), Some("review_security"));
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains(r#""docs":"pass""#));
+        let parsed: WorkState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.gates.get("docs"), Some(&GateStatus::Pass));
+        assert_eq!(parsed.gate_index("docs"), Some(5));
+    }
+
+    #[test]
+    fn test_read_state_rejects_gates_outside_the_pipeline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let mut state = WorkState::new("FEAT-001", "Test", "repo", Commands::default());
+        let mut pipeline = default_pipeline();
+        pipeline.insert(5, "docs".into());
+        state.set_pipeline(pipeline);
+        write_state(&path, &state).unwrap();
+        assert!(read_state(&path).is_ok());
+
+        let content = fs::read_to_string(&path).unwrap().replace(r#""docs": "todo""#, r#""dcos": "todo""#);
+        fs::write(&path, content).unwrap();
+        let err = read_state(&path).unwrap_err();
+        assert!(err.contains("gate 'dcos' is not in the pipeline"), "{}", err);
+        assert!(err.contains("did you mean 'docs'?"));
+
+        // Dropping a custom gate from the pipeline drops its status too
+        state.set_pipeline(default_pipeline());
+        assert_eq!(state.gates.get("docs"), None);
+        write_state(&path, &state).unwrap();
+        assert!(read_state(&path).is_ok());
     }
 
     #[test]
@@ -425,6 +809,38 @@ mod tests {
         assert_eq!(loaded.title, "File test");
     }
 
+    #[test]
+    fn test_state_without_components_parses() {
+        let json = r#"{
+            "id": "FEAT-OLD", "title": "Old", "repo": "r", "branch": "pfm/FEAT-OLD",
+            "status": "in_progress", "owner": "prd", "updated_at": "",
+            "gates": {"prd": "todo", "plan": "todo", "env": "todo", "tests": "todo",
+                      "impl": "todo", "review_security": "todo", "qa": "todo", "git": "todo"},
+            "commands": {"verify": "", "security": ""},
+            "workspace": {}
+        }"#;
+        let state: WorkState = serde_json::from_str(json).unwrap();
+        assert!(state.components.is_empty());
+        assert_eq!(state.pipeline, default_pipeline());
+        assert!(state.notes.is_empty());
+    }
+
+    #[test]
+    fn test_plain_string_notes_load_as_context() {
+        let notes: Vec<Note> = serde_json::from_str(
+            r#"["customer wants CSV", {"text": "use serde", "author": "Ada", "at": "", "kind": "decision"}]"#,
+        )
+        .unwrap();
+        assert_eq!(notes[0].kind, NoteKind::Context);
+        assert_eq!(notes[0].to_string(), "[context] customer wants CSV");
+        assert_eq!(notes[1].to_string(), "[decision] use serde — Ada");
+
+        let saved = serde_json::to_value(&notes[0]).unwrap();
+        assert_eq!(saved["kind"], "context");
+        assert!(!NoteKind::Context.relevant_to(&Role::Implementation));
+        assert!(NoteKind::Blocker.relevant_to(&Role::Git));
+    }
+
     #[test]
     fn test_role_display_and_parse() {
         let roles = vec![
@@ -0,0 +1,136 @@
+use crate::acceptance::{checklist_item, section_lines};
+use crate::runlog;
+use chrono::Utc;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub text: String,
+    pub done: bool,
+}
+
+/// Done and total task counts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn of(tasks: &[Task]) -> Self {
+        Progress {
+            done: tasks.iter().filter(|t| t.done).count(),
+            total: tasks.len(),
+        }
+    }
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} tasks done", self.done, self.total)
+    }
+}
+
+/// Parse checklist items under `## Tasks`, or the whole file if there is no such section
+pub fn parse(content: &str) -> Vec<Task> {
+    section_lines(content, "## Tasks")
+        .into_iter()
+        .filter_map(|(_, line)| checklist_item(line))
+        .map(|(prefix, text)| Task {
+            text: text.to_string(),
+            done: !prefix.ends_with("[ ]"),
+        })
+        .collect()
+}
+
+/// The work item's tasks. Empty if `tasks.md` is missing or still the template,
+/// so placeholder tasks don't show up as progress.
+pub fn load(base: &Path, work_dir: &Path) -> Vec<Task> {
+    let Ok(content) = fs::read_to_string(work_dir.join("tasks.md")) else {
+        return Vec::new();
+    };
+    if crate::lint::check_artifact(base, work_dir, "tasks.md", &content) == Some(crate::lint::Fill::Unfilled) {
+        return Vec::new();
+    }
+    parse(&content)
+}
+
+/// Progress for the work item, if it has tasks
+pub fn progress(base: &Path, work_dir: &Path) -> Option<Progress> {
+    let tasks = load(base, work_dir);
+    (!tasks.is_empty()).then(|| Progress::of(&tasks))
+}
+
+/// Tasks done in `after` that weren't done in `before`
+pub fn newly_done<'a>(before: &[Task], after: &'a [Task]) -> Vec<&'a Task> {
+    after
+        .iter()
+        .filter(|t| t.done && !before.iter().any(|b| b.done && b.text == t.text))
+        .collect()
+}
+
+/// Log tasks ticked off since `seen` to the runlog and update `seen`.
+/// Returns the number of newly completed tasks.
+pub fn record_completed(base: &Path, work_dir: &Path, seen: &mut Vec<Task>, by: &str) -> Result<usize, String> {
+    let current = load(base, work_dir);
+    let done = newly_done(seen, &current);
+    let count = done.len();
+    if count > 0 {
+        let log_entry = format!(
+            "\n## Tasks Done: {} — {} ({})\n\n{}\n",
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            by,
+            Progress::of(&current),
+            done.iter().map(|t| format!("- [x] {}", t.text)).collect::<Vec<_>>().join("\n"),
+        );
+        runlog::append(work_dir, &log_entry)?;
+    }
+    *seen = current;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use tempfile::tempdir;
+
+    const TASKS: &str = "# Task Breakdown\n\n## Tasks\n\n- [x] Add export endpoint\n- [ ] Stream large exports\n  - [X] Chunk rows\n\n## Notes\n\n- [ ] not a task\n";
+
+    #[test]
+    fn test_parse_counts_checked_items() {
+        let tasks = parse(TASKS);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(Progress::of(&tasks).to_string(), "2/3 tasks done");
+        assert!(!tasks[1].done);
+    }
+
+    #[test]
+    fn test_template_tasks_are_not_progress() {
+        let dir = tempdir().unwrap();
+        init::run(dir.path()).unwrap();
+        work::new_work(dir.path(), "Export", Some("FEAT-TASKS"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-TASKS");
+        assert_eq!(progress(dir.path(), &work_dir), None);
+
+        fs::write(work_dir.join("tasks.md"), TASKS).unwrap();
+        assert_eq!(progress(dir.path(), &work_dir), Some(Progress { done: 2, total: 3 }));
+    }
+
+    #[test]
+    fn test_record_completed_logs_only_new_ticks() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("tasks.md"), TASKS).unwrap();
+        let mut seen = load(dir.path(), dir.path());
+        assert_eq!(record_completed(dir.path(), dir.path(), &mut seen, "implementation").unwrap(), 0);
+
+        fs::write(dir.path().join("tasks.md"), TASKS.replace("- [ ] Stream", "- [x] Stream")).unwrap();
+        assert_eq!(record_completed(dir.path(), dir.path(), &mut seen, "implementation").unwrap(), 1);
+        let runlog = fs::read_to_string(dir.path().join("runlog.md")).unwrap();
+        assert!(runlog.contains("— implementation (3/3 tasks done)"));
+        assert!(runlog.contains("- [x] Stream large exports\n"));
+        assert!(!runlog.contains("Add export endpoint"));
+    }
+}
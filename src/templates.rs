@@ -1,4 +1,4 @@
-/// Template content for work item files
+use std::path::{Path, PathBuf};
 
 pub const STATE_JSON: &str = r#"{
   "id": "",
@@ -6,6 +6,7 @@ pub const STATE_JSON: &str = r#"{
   "repo": "",
   "branch": "",
   "status": "in_progress",
+  "type": "feature",
   "owner": "prd",
   "updated_at": "",
   "gates": {
@@ -18,6 +19,8 @@ pub const STATE_JSON: &str = r#"{
     "qa": "todo",
     "git": "todo"
   },
+  "pipeline": ["prd", "plan", "env", "tests", "impl", "review_security", "qa", "git"],
+  "approval_gates": [],
   "commands": {
     "verify": "",
     "security": "",
@@ -28,7 +31,16 @@ pub const STATE_JSON: &str = r#"{
     "tmux_session": "",
     "container": ""
   },
-  "notes": []
+  "components": [],
+  "notes": [],
+  "priority": null,
+  "labels": [],
+  "depends_on": [],
+  "epic": false,
+  "parent": null,
+  "children": [],
+  "gate_refs": {},
+  "history": []
 }"#;
 
 pub const PRD_MD: &str = r#"# Product Requirements Document
@@ -42,7 +54,7 @@ pub const PRD_MD: &str = r#"# Product Requirements Document
 
 ## Requirements
 
-<!-- List functional and non-functional requirements -->
+<!-- One requirement per top-level bullet; PFM numbers them REQ-1, REQ-2, … -->
 
 ## Success Criteria
 
@@ -59,6 +71,7 @@ pub const ACCEPTANCE_MD: &str = r#"# Acceptance Criteria
 
 ## Criteria
 
+<!-- Cite the requirement each criterion covers, e.g. `- [ ] CSV export includes headers (REQ-1)` -->
 - [ ] Criterion 1
 - [ ] Criterion 2
 
@@ -103,6 +116,69 @@ pub const TASKS_MD: &str = r#"# Task Breakdown
 <!-- Additional context for task execution -->
 "#;
 
+pub const BUG_PRD_MD: &str = r#"# Bug Report
+
+## Work ID: {WORK_ID}
+## Title: {TITLE}
+
+## Problem Statement
+
+<!-- What is broken, and who is affected -->
+
+## Steps to Reproduce
+
+1. Step 1
+2. Step 2
+
+## Expected Behaviour
+
+<!-- What should happen -->
+
+## Actual Behaviour
+
+<!-- What happens instead; include error output or logs -->
+
+## Requirements
+
+<!-- One requirement per top-level bullet; start with a regression test that reproduces the bug -->
+"#;
+
+pub const CHORE_PRD_MD: &str = r#"# Chore
+
+## Work ID: {WORK_ID}
+## Title: {TITLE}
+
+## Problem Statement
+
+<!-- What needs changing and why, in a sentence or two -->
+
+## Requirements
+
+<!-- One requirement per top-level bullet -->
+"#;
+
+pub const SPIKE_PRD_MD: &str = r#"# Spike
+
+## Work ID: {WORK_ID}
+## Title: {TITLE}
+
+## Problem Statement
+
+<!-- The question this spike answers, and the decision that depends on it -->
+
+## Timebox
+
+<!-- How long to spend before reporting back -->
+
+## Requirements
+
+<!-- What the findings must cover, one per top-level bullet -->
+
+## Findings
+
+<!-- Written at the end of the spike: the answer, the evidence, the recommended next step -->
+"#;
+
 pub const RUNLOG_MD: &str = r#"# Run Log
 
 ## Work ID: {WORK_ID}
@@ -114,6 +190,10 @@ pub const QA_MD: &str = r#"# QA Report
 
 ## Work ID: {WORK_ID}
 
+## Criteria Results
+
+<!-- One line per acceptance criterion ID, e.g. "- AC-1: PASS — notes" or "- AC-2: FAIL — what broke" -->
+
 ## Test Results
 
 <!-- QA findings will be recorded here -->
@@ -123,7 +203,13 @@ pub const QA_MD: &str = r#"# QA Report
 <!-- List any issues discovered during QA -->
 "#;
 
-pub const ROLE_PRD: &str = r#"# Role: PRD Agent
+pub const ROLE_PRD: &str = r#"---
+gate: prd
+inputs: [state.json]
+outputs: [prd.md, acceptance.md]
+statuses: [pass]
+---
+# Role: PRD Agent
 
 ## Purpose
 Generate a complete Product Requirements Document from the work item title and any existing context.
@@ -131,12 +217,12 @@ Generate a complete Product Requirements Document from the work item title and a
 ## Inputs
 - `state.json` — read work item metadata
 - Any existing `prd.md` content
-- User-provided context in notes
+- Notes in state.json (`pfm note add`): context, decisions and blockers
 
 ## Actions
-1. Read the work title and notes from state.json
-2. Generate a comprehensive PRD in `prd.md`
-3. Generate acceptance criteria in `acceptance.md`
+1. Read the work title and notes from state.json; fold context notes into the PRD
+2. Generate a comprehensive PRD in `prd.md`, one requirement per bullet under `## Requirements`
+3. Generate acceptance criteria in `acceptance.md`, each citing the requirement it covers (`REQ-n`)
 4. Update gate `prd` to `pass` in state.json
 5. Write handoff note
 
@@ -149,7 +235,13 @@ Generate a complete Product Requirements Document from the work item title and a
 - Handoff note written
 "#;
 
-pub const ROLE_ORCHESTRATOR: &str = r#"# Role: Orchestrator Agent
+pub const ROLE_ORCHESTRATOR: &str = r#"---
+gate: plan
+inputs: [prd.md, acceptance.md]
+outputs: [plan.md, tasks.md]
+statuses: [pass]
+---
+# Role: Orchestrator Agent
 
 ## Purpose
 Create an implementation plan and task breakdown from the PRD.
@@ -175,7 +267,13 @@ Create an implementation plan and task breakdown from the PRD.
 - Handoff note written
 "#;
 
-pub const ROLE_ENV: &str = r#"# Role: Environment Agent
+pub const ROLE_ENV: &str = r#"---
+gate: env
+inputs: [state.json, plan.md]
+outputs: []
+statuses: [pass, fail]
+---
+# Role: Environment Agent
 
 ## Purpose
 Set up the development environment (branch, worktree, dependencies).
@@ -201,7 +299,13 @@ Set up the development environment (branch, worktree, dependencies).
 - Handoff note written
 "#;
 
-pub const ROLE_TEST: &str = r#"# Role: Test Agent
+pub const ROLE_TEST: &str = r#"---
+gate: tests
+inputs: [plan.md, tasks.md, acceptance.md]
+outputs: []
+statuses: [pass, fail]
+---
+# Role: Test Agent
 
 ## Purpose
 Write tests based on the plan and acceptance criteria BEFORE implementation.
@@ -216,7 +320,7 @@ Write tests based on the plan and acceptance criteria BEFORE implementation.
 2. Write test files (unit, integration as appropriate)
 3. Verify tests exist and fail (red phase of TDD)
 4. Update gate `tests` to `pass` in state.json
-5. Write handoff note
+5. Write handoff note listing each test file with the criteria it covers (`- tests/export_test.rs: AC-1, AC-2`)
 
 ## Gate Owned
 `tests`
@@ -227,7 +331,13 @@ Write tests based on the plan and acceptance criteria BEFORE implementation.
 - Handoff note written
 "#;
 
-pub const ROLE_IMPLEMENTATION: &str = r#"# Role: Implementation Agent
+pub const ROLE_IMPLEMENTATION: &str = r#"---
+gate: impl
+inputs: [plan.md, tasks.md]
+outputs: []
+statuses: [pass, fail]
+---
+# Role: Implementation Agent
 
 ## Purpose
 Implement the feature to make tests pass.
@@ -242,7 +352,7 @@ Implement the feature to make tests pass.
 2. Implement code changes per the plan
 3. Run tests to verify they pass
 4. Update gate `impl` to `pass` in state.json
-5. Write handoff note
+5. Write handoff note listing each changed source file with the requirements it implements (`- src/export.rs: REQ-1`)
 
 ## Gate Owned
 `impl`
@@ -254,7 +364,13 @@ Implement the feature to make tests pass.
 - Handoff note written
 "#;
 
-pub const ROLE_REVIEW_SECURITY: &str = r#"# Role: Review & Security Agent
+pub const ROLE_REVIEW_SECURITY: &str = r#"---
+gate: review_security
+inputs: [acceptance.md]
+outputs: []
+statuses: [pass, changes_requested, fail]
+---
+# Role: Review & Security Agent
 
 ## Purpose
 Review code quality and run security checks.
@@ -282,7 +398,13 @@ Review code quality and run security checks.
 - Handoff note written
 "#;
 
-pub const ROLE_QA: &str = r#"# Role: QA Agent
+pub const ROLE_QA: &str = r#"---
+gate: qa
+inputs: [acceptance.md]
+outputs: [qa.md]
+statuses: [pass, fail]
+---
+# Role: QA Agent
 
 ## Purpose
 Validate the implementation against acceptance criteria.
@@ -293,24 +415,30 @@ Validate the implementation against acceptance criteria.
 - Implementation artifacts
 
 ## Actions
-1. Read acceptance criteria
+1. Read acceptance criteria (each has an ID such as `AC-1`)
 2. Run smoke tests if configured
 3. Verify each acceptance criterion
-4. Record results in `qa.md`
-5. Update gate `qa` to `pass` or `fail`
+4. Record a result for every criterion ID under "Criteria Results" in `qa.md`, e.g. `- AC-1: PASS — notes`
+5. Update gate `qa` to `pass` or `fail` (`pass` is only accepted when every criterion has passed)
 6. Write handoff note
 
 ## Gate Owned
 `qa`
 
 ## Stop Condition
-- All acceptance criteria verified
+- All acceptance criteria verified, with a result recorded per ID
 - QA report written
 - Gate `qa` = `pass` or `fail`
 - Handoff note written
 "#;
 
-pub const ROLE_GIT: &str = r#"# Role: Git Agent
+pub const ROLE_GIT: &str = r#"---
+gate: git
+inputs: [state.json]
+outputs: []
+statuses: [pass, fail]
+---
+# Role: Git Agent
 
 ## Purpose
 Finalize the branch: clean up, create commit, prepare for merge.
@@ -338,6 +466,89 @@ Finalize the branch: clean up, create commit, prepare for merge.
 - Handoff note written
 "#;
 
+pub const PROMPT_BOOTSTRAP: &str = r#"You are acting as the {{role}} agent.
+Read and follow your role spec exactly: {{role_spec}}
+Your assigned work item directory is: {{work_dir}}
+{{#if context_pack}}
+Key artifacts for your role are already collected in {{context_pack}} — read it first.
+Anything marked as truncated or omitted there can be read from the original file.
+{{/if}}
+Start by reading:
+1) {{work_dir}}/state.json
+2) {{work_dir}}/tasks.md
+{{#if latest_handoff}}
+3) The most recent handoff: {{latest_handoff}}
+{{else}}
+3) The most recent file in {{work_dir}}/handoffs/ (if any)
+{{/if}}
+{{#if check.ran}}
+
+Latest `pfm check` result ({{check.at}}): {{#if check.passed}}all checks passed{{else}}FAILED — {{check.failed}}{{/if}}
+{{/if}}
+{{#if notes}}
+
+Notes left on this work item (follow decisions; resolve or work around blockers before going on):
+{{#each notes}}
+- [{{this.kind}}] {{this.text}}{{#if this.author}} — {{this.author}}{{/if}}
+{{/each}}
+{{/if}}
+
+Hard requirements:
+- Ask the user clarifying questions when requirements are ambiguous or incomplete. Do not assume — confirm with the user.
+- Update only the gate you own in state.json (do not modify other gates).
+- Log commands, outputs, and failures in {{work_dir}}/runlog.md.
+- When finished, write a handoff note to {{work_dir}}/handoffs/{TIMESTAMP}-{{role}}.md using the standard format.
+- When you are done, tell the user you are finished and they can exit the session with /exit to return to PFM.
+- Stop when your role spec stop condition is met."#;
+
+pub const PROMPT_NUDGE: &str = r#"Resume your work. Check {{work_dir}}/state.json for current state. Your gate is '{{gate}}'. Complete your role spec requirements and write a handoff note."#;
+
+pub const PROMPT_TEAMS_LEAD: &str = r#"You are the PFM orchestrator lead agent running in teams mode.
+
+## Work Item
+- ID: {{work.id}}
+- Directory: {{work_dir}}
+- State: {{work_dir}}/state.json
+
+## Your Job
+Spawn a teammate for each role below. Each teammate must:
+1. Read their role spec and follow it exactly
+2. Read {{work_dir}}/state.json and {{work_dir}}/tasks.md before starting
+3. Read the most recent file in {{work_dir}}/handoffs/ for context from prior roles
+4. Update ONLY their own gate in {{work_dir}}/state.json
+5. Log commands and outputs in {{work_dir}}/runlog.md
+6. Write a handoff note to {{work_dir}}/handoffs/{TIMESTAMP}-{ROLE}.md when done
+
+## Roles to Spawn (in order)
+{{#each roles}}
+- **{{this.role}}** (gate: `{{this.gate}}`): role spec at `{{this.spec}}`{{#if this.context}}, context pack at `{{this.context}}`{{/if}}
+{{/each}}
+
+## Sequencing Rules
+- Roles must execute in the order listed above
+- Each role should wait for the prior role's gate to be `pass` before starting
+- After `tests` or `impl` gates complete, run the verify command: `{{commands.verify}}`
+- After `impl` gate, run the security command: `{{commands.security}}`
+
+## Reroute Rules
+- If `tests` gate = `fail` → have the implementation teammate fix and retry
+- If `review_security` gate = `changes_requested` → have the implementation teammate fix and retry
+- If `qa` gate = `fail` → have the implementation teammate fix, then re-run tests and qa
+
+## Completion
+When all gates are `pass` (or you reach the target gate), set work status to `done` in state.json.
+
+Start now by creating the team and spawning the first role."#;
+
+/// Returns (filename, content) pairs for the overridable prompt templates
+pub fn prompt_templates() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("bootstrap.md", PROMPT_BOOTSTRAP),
+        ("nudge.md", PROMPT_NUDGE),
+        ("teams_lead.md", PROMPT_TEAMS_LEAD),
+    ]
+}
+
 /// Returns (filename, content) pairs for all role specs
 pub fn role_specs() -> Vec<(&'static str, &'static str)> {
     vec![
@@ -364,3 +575,31 @@ pub fn work_templates() -> Vec<(&'static str, &'static str)> {
         ("qa.md", QA_MD),
     ]
 }
+
+/// Returns (template set, filename, content) for the built-in work type templates.
+/// Each set overrides some of the default work templates.
+pub fn type_templates() -> Vec<(&'static str, &'static str, &'static str)> {
+    vec![
+        ("bug", "prd.md", BUG_PRD_MD),
+        ("chore", "prd.md", CHORE_PRD_MD),
+        ("spike", "prd.md", SPIKE_PRD_MD),
+    ]
+}
+
+/// Where a work template lives: in the template set's directory if it overrides the file,
+/// else in `.pfm/templates/`
+pub fn template_path(base: &Path, set: Option<&str>, filename: &str) -> PathBuf {
+    let templates_dir = base.join(".pfm/templates");
+    set.map(|set| templates_dir.join(set).join(filename))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| templates_dir.join(filename))
+}
+
+/// Placeholders each work item template is expected to contain
+pub fn template_placeholders(filename: &str) -> &'static [&'static str] {
+    match filename {
+        "prd.md" => &["{WORK_ID}", "{TITLE}"],
+        "acceptance.md" | "plan.md" | "tasks.md" | "runlog.md" | "qa.md" => &["{WORK_ID}"],
+        _ => &[],
+    }
+}
@@ -0,0 +1,396 @@
+use crate::acceptance::{checklist_item, find_ids, numbered_items, IdScheme, CRITERIA};
+use crate::adapters::git;
+use crate::state::{read_state, write_state, GateRefs, WorkState};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const REQUIREMENTS: IdScheme = IdScheme {
+    prefix: "REQ",
+    heading: "## Requirements",
+    item: requirement_item,
+};
+
+/// A top-level bullet; nested bullets are details of the requirement above them
+fn requirement_item(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with([' ', '\t']) {
+        return None;
+    }
+    checklist_item(line).or_else(|| {
+        ["- ", "* "]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker).map(|rest| (&line[..1], rest.trim())))
+    })
+}
+
+/// Write IDs into unnumbered requirements in `prd.md`. Returns true if it was rewritten.
+pub fn assign_requirement_ids(work_dir: &Path) -> Result<bool, String> {
+    crate::acceptance::number_items(&work_dir.join("prd.md"), &REQUIREMENTS)
+}
+
+/// The directory agents work in: the worktree if there is one, else the repo
+pub fn workspace_dir(base: &Path, state: &WorkState) -> PathBuf {
+    if state.workspace.worktree.is_empty() {
+        base.to_path_buf()
+    } else {
+        PathBuf::from(&state.workspace.worktree)
+    }
+}
+
+/// Snapshot the working tree as a gate starts. Outside a git repo this does nothing.
+pub fn mark_gate_start(work_dir: &Path, cwd: &Path, gate: &str) -> Result<(), String> {
+    let Ok(tree) = git::snapshot_tree(cwd) else {
+        return Ok(());
+    };
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    state.gate_refs.insert(gate.to_string(), GateRefs { start: tree, end: String::new() });
+    write_state(&state_path, &state)
+}
+
+/// Snapshot the working tree as a gate finishes
+pub fn mark_gate_end(work_dir: &Path, cwd: &Path, gate: &str) -> Result<(), String> {
+    let state_path = work_dir.join("state.json");
+    let mut state = read_state(&state_path)?;
+    let Some(refs) = state.gate_refs.get_mut(gate) else {
+        return Ok(());
+    };
+    let Ok(tree) = git::snapshot_tree(cwd) else {
+        return Ok(());
+    };
+    refs.end = tree;
+    write_state(&state_path, &state)
+}
+
+/// Mark gate starts and ends from status changes, for runs where PFM doesn't launch
+/// each agent itself (teams mode). A gate starts when seen in progress and ends when
+/// seen terminal.
+pub fn observe_gates(work_dir: &Path, cwd: &Path, gates: &[String]) -> Result<(), String> {
+    let state = read_state(&work_dir.join("state.json"))?;
+    for gate in gates {
+        let Some(status) = state.gates.get(gate) else { continue };
+        let refs = state.gate_refs.get(gate);
+        if *status == crate::state::GateStatus::InProgress && refs.map(|r| !r.end.is_empty()).unwrap_or(true) {
+            mark_gate_start(work_dir, cwd, gate)?;
+        } else if status.is_terminal() && refs.map(|r| r.end.is_empty()).unwrap_or(false) {
+            mark_gate_end(work_dir, cwd, gate)?;
+        }
+    }
+    Ok(())
+}
+
+/// Files changed while a gate ran, excluding PFM's own files. None without both snapshots.
+pub fn gate_changes(cwd: &Path, state: &WorkState, gate: &str) -> Option<Vec<String>> {
+    let refs = state.gate_refs.get(gate)?;
+    if refs.start.is_empty() || refs.end.is_empty() {
+        return None;
+    }
+    let files = git::changed_files(cwd, &refs.start, &refs.end).ok()?;
+    Some(files.into_iter().filter(|f| !f.starts_with(".pfm/")).collect())
+}
+
+/// Words on a line that look like file paths (`tests/export_test.rs`, `Foo.kt`)
+fn mentioned_paths(line: &str) -> Vec<String> {
+    line.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| "`*,;:()[]\"'".contains(c)))
+        .filter(|word| {
+            let Some((stem, ext)) = word.rsplit_once('.') else { return false };
+            !stem.is_empty()
+                && ext.len() >= 2
+                && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                && !word.contains("://")
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Handoff notes written by a role, oldest first
+fn handoffs_by(work_dir: &Path, role: &str) -> Vec<String> {
+    let suffix = format!("-{}.md", role);
+    let mut paths: Vec<PathBuf> = fs::read_dir(work_dir.join("handoffs"))
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| p.file_name().map(|n| n.to_string_lossy().ends_with(&suffix)).unwrap_or(false));
+    paths.sort();
+    paths.iter().filter_map(|p| fs::read_to_string(p).ok()).collect()
+}
+
+/// The traceability matrix for one work item
+#[derive(Debug, Default)]
+pub struct Trace {
+    /// (ID, text) from `prd.md`
+    pub requirements: Vec<(String, String)>,
+    /// Requirement ID → criteria citing it
+    pub criteria: BTreeMap<String, Vec<String>>,
+    /// Test file → requirements it traces to
+    pub tests: BTreeMap<String, BTreeSet<String>>,
+    /// Source file → requirements it traces to
+    pub sources: BTreeMap<String, BTreeSet<String>>,
+    /// Gate → files changed while it ran (None without snapshots)
+    pub changes: Vec<(String, Option<Vec<String>>)>,
+}
+
+impl Trace {
+    /// Requirements no test traces to
+    pub fn untested(&self) -> Vec<&str> {
+        self.requirements
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .filter(|id| !self.tests.values().any(|reqs| reqs.contains(*id)))
+            .collect()
+    }
+
+    /// Test files that trace to no requirement
+    pub fn orphan_tests(&self) -> Vec<&str> {
+        self.tests
+            .iter()
+            .filter(|(_, reqs)| reqs.is_empty())
+            .map(|(file, _)| file.as_str())
+            .collect()
+    }
+
+    /// Test files tracing to a requirement
+    pub fn tests_for(&self, req: &str) -> Vec<&str> {
+        files_for(&self.tests, req)
+    }
+
+    /// Source files tracing to a requirement
+    pub fn sources_for(&self, req: &str) -> Vec<&str> {
+        files_for(&self.sources, req)
+    }
+
+    pub fn render(&self, work_id: &str) -> String {
+        let cell = |items: Vec<String>| if items.is_empty() { "—".to_string() } else { items.join(", ") };
+        let code = |files: Vec<&str>| files.iter().map(|f| format!("`{}`", f)).collect::<Vec<_>>();
+
+        let mut out = format!(
+            "# Traceability: {}\n\nGenerated: {}\n\n## Requirements\n\n",
+            work_id,
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        if self.requirements.is_empty() {
+            out.push_str("No requirements found under `## Requirements` in prd.md.\n");
+        } else {
+            out.push_str("| Requirement | Criteria | Tests | Source files |\n|---|---|---|---|\n");
+            for (id, text) in &self.requirements {
+                out.push_str(&format!(
+                    "| {}: {} | {} | {} | {} |\n",
+                    id,
+                    text.replace('|', "\\|"),
+                    cell(self.criteria.get(id).cloned().unwrap_or_default()),
+                    cell(code(self.tests_for(id))),
+                    cell(code(self.sources_for(id))),
+                ));
+            }
+        }
+
+        out.push_str("\n## Tests\n\n");
+        if self.tests.is_empty() {
+            out.push_str("No test files found in the tests gate's changes or the test handoff.\n");
+        } else {
+            out.push_str("| Test file | Requirements |\n|---|---|\n");
+            for (file, reqs) in &self.tests {
+                out.push_str(&format!("| `{}` | {} |\n", file, cell(reqs.iter().cloned().collect())));
+            }
+        }
+
+        out.push_str("\n## Changes by Gate\n");
+        for (gate, files) in &self.changes {
+            out.push_str(&format!("\n### {}\n\n", gate));
+            match files {
+                None => out.push_str("No snapshots recorded.\n"),
+                Some(files) if files.is_empty() => out.push_str("No files changed.\n"),
+                Some(files) => {
+                    for file in files {
+                        out.push_str(&format!("- `{}`\n", file));
+                    }
+                }
+            }
+        }
+
+        out.push_str("\n## Gaps\n\n");
+        let untested = self.untested();
+        let orphans = self.orphan_tests();
+        if untested.is_empty() && orphans.is_empty() {
+            out.push_str("None.\n");
+        }
+        for id in untested {
+            out.push_str(&format!("- {} has no test\n", id));
+        }
+        for file in orphans {
+            out.push_str(&format!("- `{}` traces to no requirement\n", file));
+        }
+        out
+    }
+}
+
+fn files_for<'a>(files: &'a BTreeMap<String, BTreeSet<String>>, req: &str) -> Vec<&'a str> {
+    files
+        .iter()
+        .filter(|(_, reqs)| reqs.contains(req))
+        .map(|(file, _)| file.as_str())
+        .collect()
+}
+
+/// Requirement IDs a line traces to: cited directly, or through the criteria it cites
+fn line_requirements(line: &str, criterion_reqs: &BTreeMap<String, Vec<String>>) -> BTreeSet<String> {
+    let mut reqs: BTreeSet<String> = find_ids(line, REQUIREMENTS.prefix).into_iter().collect();
+    for ac in find_ids(line, CRITERIA.prefix) {
+        reqs.extend(criterion_reqs.get(&ac).into_iter().flatten().cloned());
+    }
+    reqs
+}
+
+/// Link files to requirements from handoff lines that mention both
+fn link_files(
+    files: &mut BTreeMap<String, BTreeSet<String>>,
+    handoffs: &[String],
+    criterion_reqs: &BTreeMap<String, Vec<String>>,
+) {
+    for line in handoffs.iter().flat_map(|h| h.lines()) {
+        let reqs = line_requirements(line, criterion_reqs);
+        for path in mentioned_paths(line) {
+            let known = files
+                .keys()
+                .find(|f| **f == path || f.ends_with(&format!("/{}", path)))
+                .cloned()
+                .unwrap_or(path);
+            files.entry(known).or_default().extend(reqs.iter().cloned());
+        }
+    }
+}
+
+/// Build the trace for a work item
+pub fn build(base: &Path, work_dir: &Path, state: &WorkState) -> Trace {
+    let read = |name: &str| fs::read_to_string(work_dir.join(name)).unwrap_or_default();
+    let requirements = numbered_items(&read("prd.md"), &REQUIREMENTS);
+
+    let mut criteria: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut criterion_reqs: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (ac, text) in numbered_items(&read("acceptance.md"), &CRITERIA) {
+        let reqs = find_ids(&text, REQUIREMENTS.prefix);
+        for req in &reqs {
+            criteria.entry(req.clone()).or_default().push(ac.clone());
+        }
+        criterion_reqs.insert(ac, reqs);
+    }
+
+    let cwd = workspace_dir(base, state);
+    let changes: Vec<(String, Option<Vec<String>>)> = state
+        .pipeline
+        .iter()
+        .map(|gate| (gate.clone(), gate_changes(&cwd, state, gate)))
+        .collect();
+    let changed_in = |gate: &str| {
+        changes
+            .iter()
+            .find(|(g, _)| g == gate)
+            .and_then(|(_, files)| files.clone())
+            .unwrap_or_default()
+    };
+
+    let mut tests: BTreeMap<String, BTreeSet<String>> =
+        changed_in("tests").into_iter().map(|f| (f, BTreeSet::new())).collect();
+    link_files(&mut tests, &handoffs_by(work_dir, "test"), &criterion_reqs);
+
+    let mut sources: BTreeMap<String, BTreeSet<String>> = changed_in("impl")
+        .into_iter()
+        .filter(|f| !tests.contains_key(f))
+        .map(|f| (f, BTreeSet::new()))
+        .collect();
+    link_files(&mut sources, &handoffs_by(work_dir, "implementation"), &criterion_reqs);
+    sources.retain(|f, _| !tests.contains_key(f));
+
+    Trace {
+        requirements,
+        criteria,
+        tests,
+        sources,
+        changes,
+    }
+}
+
+/// Build the trace and write it to `artifacts/trace.md`
+pub fn write(base: &Path, work_dir: &Path) -> Result<(PathBuf, Trace), String> {
+    let state = read_state(&work_dir.join("state.json"))?;
+    let trace = build(base, work_dir, &state);
+    fs::create_dir_all(work_dir.join("artifacts"))
+        .map_err(|e| format!("failed to create artifacts dir: {}", e))?;
+    let path = work_dir.join("artifacts/trace.md");
+    fs::write(&path, trace.render(&state.id))
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok((path, trace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{init, work};
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    const PRD: &str = "# PRD\n\n## Requirements\n\n- Export reports as CSV\n  - include headers\n- REQ-4: Export reports as PDF\n- Schedule exports\n\n## Out of Scope\n\n- Excel\n";
+
+    #[test]
+    fn test_requirement_ids_skip_nested_bullets() {
+        let reqs = numbered_items(PRD, &REQUIREMENTS);
+        let ids: Vec<&str> = reqs.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["REQ-5", "REQ-4", "REQ-6"]);
+    }
+
+    #[test]
+    fn test_mentioned_paths() {
+        assert_eq!(
+            mentioned_paths("- `tests/export_test.rs`: AC-1, REQ-2 (see e.g. https://x.io/a.html)"),
+            vec!["tests/export_test.rs"]
+        );
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git").args(args).current_dir(dir).output().unwrap().status;
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_trace_links_requirements_to_tests_and_sources() {
+        let dir = tempdir().unwrap();
+        git(dir.path(), &["init", "-q", "-b", "main"]);
+        git(dir.path(), &["config", "user.email", "t@example.com"]);
+        git(dir.path(), &["config", "user.name", "T"]);
+        init::run(dir.path()).unwrap();
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-q", "-m", "init"]);
+        work::new_work(dir.path(), "Export", Some("FEAT-TR"), Some("rust")).unwrap();
+        let work_dir = dir.path().join(".pfm/work/FEAT-TR");
+
+        fs::write(work_dir.join("prd.md"), PRD).unwrap();
+        assert!(assign_requirement_ids(&work_dir).unwrap());
+        fs::write(
+            work_dir.join("acceptance.md"),
+            "## Criteria\n\n- [ ] AC-1: CSV has headers (REQ-5)\n- [ ] AC-2: PDF renders (REQ-4)\n",
+        ).unwrap();
+
+        mark_gate_start(&work_dir, dir.path(), "tests").unwrap();
+        fs::create_dir_all(dir.path().join("tests")).unwrap();
+        fs::write(dir.path().join("tests/csv_test.rs"), "#[test] fn csv() {}").unwrap();
+        fs::write(dir.path().join("tests/misc_test.rs"), "#[test] fn misc() {}").unwrap();
+        mark_gate_end(&work_dir, dir.path(), "tests").unwrap();
+
+        mark_gate_start(&work_dir, dir.path(), "impl").unwrap();
+        fs::write(dir.path().join("export.rs"), "fn csv() {}").unwrap();
+        mark_gate_end(&work_dir, dir.path(), "impl").unwrap();
+
+        fs::create_dir_all(work_dir.join("handoffs")).unwrap();
+        fs::write(work_dir.join("handoffs/20260101-000000-test.md"), "- `csv_test.rs` covers AC-1\n").unwrap();
+        fs::write(work_dir.join("handoffs/20260101-000001-implementation.md"), "- export.rs: REQ-5\n").unwrap();
+
+        let (path, trace) = write(dir.path(), &work_dir).unwrap();
+        assert_eq!(trace.untested(), vec!["REQ-4", "REQ-6"]);
+        assert_eq!(trace.orphan_tests(), vec!["tests/misc_test.rs"]);
+        let report = fs::read_to_string(path).unwrap();
+        assert!(report.contains("| REQ-5: Export reports as CSV | AC-1 | `tests/csv_test.rs` | `export.rs` |"));
+        assert!(report.contains("- REQ-6 has no test"));
+        assert!(report.contains("- `tests/misc_test.rs` traces to no requirement"));
+        assert!(!report.contains(".pfm/"));
+    }
+}